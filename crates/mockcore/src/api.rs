@@ -83,6 +83,35 @@ pub trait Api {
   #[rpc(name = "sendrawtransaction")]
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error>;
 
+  #[rpc(name = "submitpackage")]
+  fn submit_package(&self, rawtxs: Vec<String>) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getmempoolinfo")]
+  fn get_mempool_info(&self) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getdeploymentinfo")]
+  fn get_deployment_info(&self) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getrawmempool")]
+  fn get_raw_mempool(&self, verbose: Option<bool>) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "testmempoolaccept")]
+  fn test_mempool_accept(&self, rawtxs: Vec<String>) -> Result<Vec<Value>, jsonrpc_core::Error>;
+
+  #[rpc(name = "estimatesmartfee")]
+  fn estimate_smart_fee(
+    &self,
+    conf_target: u16,
+    estimate_mode: Option<String>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "scantxoutset")]
+  fn scan_tx_out_set(
+    &self,
+    action: String,
+    scanobjects: Vec<String>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "sendtoaddress")]
   fn send_to_address(
     &self,
@@ -119,7 +148,7 @@ pub trait Api {
     &self,
     minconf: Option<usize>,
     maxconf: Option<usize>,
-    address: Option<Address<NetworkUnchecked>>,
+    addresses: Option<Vec<Address<NetworkUnchecked>>>,
     include_unsafe: Option<bool>,
     query_options: Option<String>,
   ) -> Result<Vec<ListUnspentResultEntry>, jsonrpc_core::Error>;
@@ -139,12 +168,27 @@ pub trait Api {
     desc: String,
   ) -> Result<GetDescriptorInfoResult, jsonrpc_core::Error>;
 
+  #[rpc(name = "deriveaddresses")]
+  fn derive_addresses(
+    &self,
+    descriptor: String,
+  ) -> Result<Vec<Address<NetworkUnchecked>>, jsonrpc_core::Error>;
+
   #[rpc(name = "importdescriptors")]
   fn import_descriptors(
     &self,
     req: Vec<ImportDescriptors>,
   ) -> Result<Vec<ImportMultiResult>, jsonrpc_core::Error>;
 
+  #[rpc(name = "getaddressesbylabel")]
+  fn get_addresses_by_label(&self, label: String) -> Result<Value, jsonrpc_core::Error>;
+
+  #[rpc(name = "getaddressinfo")]
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "getnewaddress")]
   fn get_new_address(
     &self,
@@ -152,6 +196,15 @@ pub trait Api {
     address_type: Option<bitcoincore_rpc::json::AddressType>,
   ) -> Result<Address, jsonrpc_core::Error>;
 
+  #[rpc(name = "listreceivedbyaddress")]
+  fn list_received_by_address(
+    &self,
+    minconf: Option<u32>,
+    include_empty: Option<bool>,
+    include_watchonly: Option<bool>,
+    address_filter: Option<Address<NetworkUnchecked>>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error>;
+
   #[rpc(name = "listtransactions")]
   fn list_transactions(
     &self,
@@ -1,7 +1,13 @@
 use {
   super::*,
   base64::Engine,
-  bitcoin::{consensus::Decodable, psbt::Psbt, Witness},
+  bitcoin::{
+    consensus::Decodable,
+    key::TweakedPublicKey,
+    psbt::Psbt,
+    secp256k1::{Secp256k1, SecretKey},
+    PrivateKey, Witness,
+  },
   std::io::Cursor,
 };
 
@@ -76,8 +82,10 @@ impl Api for Server {
   }
 
   fn get_network_info(&self) -> Result<GetNetworkInfoResult, jsonrpc_core::Error> {
+    let state = self.state();
+
     Ok(GetNetworkInfoResult {
-      version: self.state().version,
+      version: state.version,
       subversion: String::new(),
       protocol_version: 0,
       local_services: String::new(),
@@ -89,7 +97,7 @@ impl Api for Server {
       network_active: true,
       networks: Vec::new(),
       relay_fee: Amount::from_sat(0),
-      incremental_fee: Amount::from_sat(0),
+      incremental_fee: state.incremental_relay_fee,
       local_addresses: Vec::new(),
       warnings: String::new(),
     })
@@ -199,6 +207,11 @@ impl Api for Server {
     verbosity: u64,
   ) -> Result<String, jsonrpc_core::Error> {
     assert_eq!(verbosity, 0, "Verbosity level {verbosity} is unsupported");
+
+    let delay = self.state().get_block_delay;
+
+    thread::sleep(delay);
+
     match self.state().blocks.get(&block_hash) {
       Some(block) => Ok(hex::encode(serialize(block))),
       None => Err(Self::not_found()),
@@ -221,11 +234,47 @@ impl Api for Server {
     &self,
     txid: Txid,
     vout: u32,
-    _include_mempool: Option<bool>,
+    include_mempool: Option<bool>,
   ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error> {
     let state = self.state();
 
-    let Some(value) = state.utxos.get(&OutPoint { txid, vout }) else {
+    let outpoint = OutPoint { txid, vout };
+
+    if include_mempool.unwrap_or(true)
+      && state.mempool.iter().any(|tx| {
+        tx.input
+          .iter()
+          .any(|input| input.previous_output == outpoint)
+      })
+    {
+      return Ok(None);
+    }
+
+    let Some(value) = state.utxos.get(&outpoint) else {
+      if include_mempool.unwrap_or(true) {
+        if let Some(output) = state
+          .mempool
+          .iter()
+          .find(|tx| tx.txid() == txid)
+          .and_then(|tx| tx.output.get(usize::try_from(vout).unwrap()))
+        {
+          return Ok(Some(GetTxOutResult {
+            bestblock: BlockHash::all_zeros(),
+            coinbase: false,
+            confirmations: 0,
+            script_pub_key: GetRawTransactionResultVoutScriptPubKey {
+              asm: String::new(),
+              hex: output.script_pubkey.to_bytes(),
+              req_sigs: None,
+              type_: None,
+              addresses: Vec::new(),
+              address: None,
+            },
+            value: Amount::from_sat(output.value),
+          }));
+        }
+      }
+
       return Ok(None);
     };
 
@@ -479,6 +528,36 @@ impl Api for Server {
 
     let mut state = self.state.lock().unwrap();
 
+    if let Some(remaining) = &mut state.broadcast_limit {
+      if *remaining == 0 {
+        return Err(jsonrpc_core::Error::invalid_params(
+          "broadcast limit reached",
+        ));
+      }
+
+      *remaining -= 1;
+    }
+
+    if state.txid_to_block_height.contains_key(&tx.txid()) {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::ErrorCode::ServerError(-27),
+        message: "Transaction already in block chain".into(),
+        data: None,
+      });
+    }
+
+    if state
+      .mempool
+      .iter()
+      .any(|mempool_tx| mempool_tx.txid() == tx.txid())
+    {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::ErrorCode::ServerError(-26),
+        message: "txn-already-known".into(),
+        data: None,
+      });
+    }
+
     for tx_in in &tx.input {
       if let Some(lock_time) = tx_in.sequence.to_relative_lock_time() {
         match lock_time {
@@ -500,6 +579,39 @@ impl Api for Server {
       }
     }
 
+    let mut spent = tx
+      .input
+      .iter()
+      .map(|tx_in| tx_in.previous_output)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let mut evicted = BTreeSet::new();
+
+    loop {
+      let conflicts = state
+        .mempool
+        .iter()
+        .filter(|mempool_tx| {
+          mempool_tx.input.iter().any(|tx_in| {
+            spent.contains(&tx_in.previous_output) || evicted.contains(&tx_in.previous_output.txid)
+          })
+        })
+        .map(|mempool_tx| mempool_tx.txid())
+        .filter(|txid| !evicted.contains(txid))
+        .collect::<Vec<Txid>>();
+
+      if conflicts.is_empty() {
+        break;
+      }
+
+      evicted.extend(conflicts);
+      spent.clear();
+    }
+
+    state
+      .mempool
+      .retain(|mempool_tx| !evicted.contains(&mempool_tx.txid()));
+
     state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
@@ -583,12 +695,42 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     let state = self.state();
 
-    let Some(tx) = state.transactions.get(&txid) else {
+    let Some(tx) = state
+      .transactions
+      .get(&txid)
+      .or_else(|| state.mempool.iter().find(|tx| tx.txid() == txid))
+    else {
       return Err(jsonrpc_core::Error::new(
         jsonrpc_core::types::error::ErrorCode::ServerError(-8),
       ));
     };
 
+    let fee = if tx.is_coin_base() {
+      None
+    } else {
+      tx.input
+        .iter()
+        .map(|input| {
+          state
+            .transactions
+            .get(&input.previous_output.txid)
+            .or_else(|| {
+              state
+                .mempool
+                .iter()
+                .find(|tx| tx.txid() == input.previous_output.txid)
+            })
+            .and_then(|tx| tx.output.get(input.previous_output.vout as usize))
+            .map(|output| output.value)
+        })
+        .sum::<Option<u64>>()
+        .map(|input_value| {
+          SignedAmount::from_sat(
+            tx.output.iter().map(|output| output.value).sum::<u64>() as i64 - input_value as i64,
+          )
+        })
+    };
+
     let mut confirmations = None;
 
     'outer: for (height, hash) in state.hashes.iter().enumerate() {
@@ -604,7 +746,7 @@ impl Api for Server {
       serde_json::to_value(GetTransactionResult {
         info: WalletTxInfo {
           txid,
-          confirmations: confirmations.unwrap().try_into().unwrap(),
+          confirmations: confirmations.unwrap_or_default().try_into().unwrap(),
           time: 0,
           timereceived: 0,
           blockhash: None,
@@ -612,10 +754,16 @@ impl Api for Server {
           blockheight: None,
           blocktime: None,
           wallet_conflicts: Vec::new(),
-          bip125_replaceable: Bip125Replaceable::Unknown,
+          bip125_replaceable: if confirmations.is_some() {
+            Bip125Replaceable::Unknown
+          } else if tx.is_explicitly_rbf() {
+            Bip125Replaceable::Yes
+          } else {
+            Bip125Replaceable::No
+          },
         },
         amount: SignedAmount::from_sat(0),
-        fee: None,
+        fee,
         details: Vec::new(),
         hex: serialize(tx),
       })
@@ -677,7 +825,11 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match state.transactions.get(&txid) {
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool.iter().find(|tx| tx.txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
@@ -688,20 +840,70 @@ impl Api for Server {
     &self,
     minconf: Option<usize>,
     maxconf: Option<usize>,
-    address: Option<Address<NetworkUnchecked>>,
+    addresses: Option<Vec<Address<NetworkUnchecked>>>,
     include_unsafe: Option<bool>,
     query_options: Option<String>,
   ) -> Result<Vec<ListUnspentResultEntry>, jsonrpc_core::Error> {
-    assert_eq!(minconf, None, "minconf param not supported");
-    assert_eq!(maxconf, None, "maxconf param not supported");
-    assert_eq!(address, None, "address param not supported");
-    assert_eq!(include_unsafe, None, "include_unsafe param not supported");
     assert_eq!(query_options, None, "query_options param not supported");
 
     let state = self.state();
 
     let mut unspent = Vec::new();
 
+    if let Some(addresses) = addresses {
+      assert_eq!(
+        (minconf, maxconf, include_unsafe),
+        (Some(0), Some(0), Some(true)),
+        "only unconfirmed outputs supported when filtering by address"
+      );
+
+      let script_pubkeys = addresses
+        .into_iter()
+        .map(|address| address.assume_checked().script_pubkey())
+        .collect::<Vec<ScriptBuf>>();
+
+      for tx in &state.mempool {
+        for (vout, tx_out) in tx.output.iter().enumerate() {
+          let outpoint = OutPoint {
+            txid: tx.txid(),
+            vout: vout.try_into().unwrap(),
+          };
+
+          if !script_pubkeys.contains(&tx_out.script_pubkey)
+            || state.mempool.iter().any(|tx| {
+              tx.input
+                .iter()
+                .any(|input| input.previous_output == outpoint)
+            })
+          {
+            continue;
+          }
+
+          unspent.push(ListUnspentResultEntry {
+            txid: outpoint.txid,
+            vout: outpoint.vout,
+            address: None,
+            label: None,
+            redeem_script: None,
+            witness_script: None,
+            script_pub_key: tx_out.script_pubkey.clone(),
+            amount: Amount::from_sat(tx_out.value),
+            confirmations: 0,
+            spendable: true,
+            solvable: true,
+            descriptor: None,
+            safe: true,
+          });
+        }
+      }
+
+      return Ok(unspent);
+    }
+
+    assert_eq!(minconf, None, "minconf param not supported");
+    assert_eq!(maxconf, None, "maxconf param not supported");
+    assert_eq!(include_unsafe, None, "include_unsafe param not supported");
+
     for (outpoint, &amount) in &state.utxos {
       if state.locked.contains(outpoint) {
         continue;
@@ -723,10 +925,10 @@ impl Api for Server {
         txid: outpoint.txid,
         vout: outpoint.vout,
         address: None,
-        label: None,
+        label: state.labels.get(&address).cloned(),
         redeem_script: None,
         witness_script: None,
-        script_pub_key: ScriptBuf::new(),
+        script_pub_key: tx_out.script_pubkey.clone(),
         amount,
         confirmations: 0,
         spendable: true,
@@ -770,14 +972,58 @@ impl Api for Server {
     })
   }
 
+  fn derive_addresses(
+    &self,
+    descriptor: String,
+  ) -> Result<Vec<Address<NetworkUnchecked>>, jsonrpc_core::Error> {
+    let private_key = descriptor
+      .strip_prefix("rawtr(")
+      .and_then(|descriptor| descriptor.split_once(')'))
+      .map(|(wif, _checksum)| wif)
+      .expect("only rawtr descriptors supported");
+
+    let mut secret_key = PrivateKey::from_wif(private_key)
+      .map_err(|_| jsonrpc_core::Error::invalid_params("invalid private key"))?
+      .inner;
+
+    if self.state().derive_wrong_addresses {
+      secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+    }
+
+    let (public_key, _parity) = secret_key.x_only_public_key(&Secp256k1::new());
+
+    Ok(vec![Address::p2tr_tweaked(
+      TweakedPublicKey::dangerous_assume_tweaked(public_key),
+      self.network,
+    )
+    .to_string()
+    .parse()
+    .unwrap()])
+  }
+
   fn import_descriptors(
     &self,
     req: Vec<ImportDescriptors>,
   ) -> Result<Vec<ImportMultiResult>, jsonrpc_core::Error> {
-    self
-      .state()
-      .descriptors
-      .extend(req.into_iter().map(|params| params.descriptor));
+    for params in req {
+      if let Some(label) = params.label {
+        if params.descriptor.starts_with("rawtr(") {
+          let address = self.derive_addresses(params.descriptor.clone())?[0]
+            .clone()
+            .require_network(self.network)
+            .unwrap();
+
+          self.state().labels.insert(address, label.clone());
+        }
+
+        self
+          .state()
+          .descriptor_labels
+          .insert(params.descriptor.clone(), label);
+      }
+
+      self.state().descriptors.push(params.descriptor);
+    }
 
     Ok(vec![ImportMultiResult {
       success: true,
@@ -786,12 +1032,331 @@ impl Api for Server {
     }])
   }
 
+  fn scan_tx_out_set(
+    &self,
+    action: String,
+    scanobjects: Vec<String>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(action, "start", "only start action supported");
+
+    let state = self.state();
+
+    let mut unspents = Vec::new();
+    let mut total_amount = Amount::ZERO;
+
+    for scanobject in scanobjects {
+      let address = scanobject
+        .strip_prefix("addr(")
+        .and_then(|address| address.strip_suffix(')'))
+        .expect("only addr descriptors supported")
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .require_network(state.network)
+        .unwrap();
+
+      for (outpoint, amount) in &state.utxos {
+        let tx = state.transactions.get(&outpoint.txid).unwrap();
+
+        let tx_out = &tx.output[usize::try_from(outpoint.vout).unwrap()];
+
+        if tx_out.script_pubkey != address.script_pubkey() {
+          continue;
+        }
+
+        total_amount += *amount;
+
+        unspents.push(serde_json::json!({
+          "txid": outpoint.txid,
+          "vout": outpoint.vout,
+          "scriptPubKey": tx_out.script_pubkey.to_hex_string(),
+          "desc": scanobject,
+          "amount": amount.to_btc(),
+          "height": state.txid_to_block_height.get(&outpoint.txid).copied().unwrap_or_default(),
+        }));
+      }
+    }
+
+    Ok(serde_json::json!({
+      "success": true,
+      "txouts": state.utxos.len(),
+      "height": state.hashes.len() - 1,
+      "bestblock": state.hashes.last().unwrap(),
+      "unspents": unspents,
+      "total_amount": total_amount.to_btc(),
+    }))
+  }
+
+  fn estimate_smart_fee(
+    &self,
+    conf_target: u16,
+    estimate_mode: Option<String>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(estimate_mode, None, "estimate_mode param not supported");
+
+    Ok(match self.state().fee_estimate {
+      Some(fee_rate) => serde_json::json!({
+        "feerate": fee_rate.to_btc(),
+        "blocks": conf_target,
+      }),
+      None => serde_json::json!({
+        "errors": ["Insufficient data or no feerate found"],
+        "blocks": 0,
+      }),
+    })
+  }
+
+  fn get_mempool_info(&self) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+
+    Ok(serde_json::json!({
+      "loaded": true,
+      "size": state.mempool.len(),
+      "minrelaytxfee": state.min_relay_tx_fee.to_btc(),
+      "mempoolminfee": state.min_relay_tx_fee.to_btc(),
+    }))
+  }
+
+  fn get_deployment_info(&self) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let height = state.hashes.len() - 1;
+
+    Ok(serde_json::json!({
+      "hash": state.hashes[height],
+      "height": height,
+      "deployments": {
+        "taproot": {
+          "type": "buried",
+          "active": height + 1 >= state.taproot_activation_height,
+          "height": state.taproot_activation_height,
+        },
+      },
+    }))
+  }
+
+  fn get_raw_mempool(&self, verbose: Option<bool>) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+
+    if !verbose.unwrap_or(false) {
+      return Ok(serde_json::json!(state
+        .mempool
+        .iter()
+        .map(|tx| tx.txid())
+        .collect::<Vec<Txid>>()));
+    }
+
+    let mut entries = serde_json::Map::new();
+
+    for tx in &state.mempool {
+      let input_value = tx
+        .input
+        .iter()
+        .map(|tx_in| {
+          let prevout = tx_in.previous_output;
+          state
+            .transactions
+            .get(&prevout.txid)
+            .or_else(|| state.mempool.iter().find(|tx| tx.txid() == prevout.txid))
+            .unwrap()
+            .output[usize::try_from(prevout.vout).unwrap()]
+          .value
+        })
+        .sum::<u64>();
+
+      let output_value = tx.output.iter().map(|tx_out| tx_out.value).sum::<u64>();
+
+      entries.insert(
+        tx.txid().to_string(),
+        serde_json::json!({
+          "vsize": tx.vsize(),
+          "fees": {
+            "base": Amount::from_sat(input_value - output_value).to_btc(),
+          },
+        }),
+      );
+    }
+
+    Ok(Value::Object(entries))
+  }
+
+  fn submit_package(&self, rawtxs: Vec<String>) -> Result<Value, jsonrpc_core::Error> {
+    let mut package_msg = "success";
+
+    let mut tx_results = serde_json::Map::new();
+
+    for rawtx in rawtxs {
+      let tx: Transaction = deserialize(&hex::decode(&rawtx).unwrap()).unwrap();
+
+      let result = match self.send_raw_transaction(rawtx) {
+        Ok(_txid) => serde_json::json!({
+          "txid": tx.txid(),
+          "vsize": tx.vsize(),
+        }),
+        Err(err) => {
+          package_msg = "transaction failed";
+          serde_json::json!({
+            "txid": tx.txid(),
+            "error": err.message,
+          })
+        }
+      };
+
+      tx_results.insert(tx.wtxid().to_string(), result);
+    }
+
+    Ok(serde_json::json!({
+      "package_msg": package_msg,
+      "tx-results": tx_results,
+    }))
+  }
+
+  fn test_mempool_accept(&self, rawtxs: Vec<String>) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    let state = self.state();
+
+    Ok(
+      rawtxs
+        .into_iter()
+        .map(|rawtx| {
+          let tx: Transaction = deserialize(&hex::decode(rawtx).unwrap()).unwrap();
+
+          match &state.mempool_reject_reason {
+            Some(reason) => serde_json::json!({
+              "txid": tx.txid(),
+              "wtxid": tx.wtxid(),
+              "allowed": false,
+              "reject-reason": reason,
+            }),
+            None => serde_json::json!({
+              "txid": tx.txid(),
+              "wtxid": tx.wtxid(),
+              "allowed": true,
+              "vsize": tx.vsize(),
+            }),
+          }
+        })
+        .collect(),
+    )
+  }
+
+  fn get_addresses_by_label(&self, label: String) -> Result<Value, jsonrpc_core::Error> {
+    let addresses = self
+      .state()
+      .labels
+      .iter()
+      .filter(|(_address, address_label)| **address_label == label)
+      .map(|(address, _label)| {
+        (
+          address.to_string(),
+          serde_json::json!({ "purpose": "receive" }),
+        )
+      })
+      .collect::<serde_json::Map<String, Value>>();
+
+    if addresses.is_empty() {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::ErrorCode::ServerError(-11),
+        message: format!("No addresses with label {label}"),
+        data: None,
+      });
+    }
+
+    Ok(Value::Object(addresses))
+  }
+
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    let address = address.require_network(self.network).unwrap();
+
+    let descriptors = self.state().descriptors.clone();
+
+    let imported = descriptors
+      .into_iter()
+      .filter(|descriptor| descriptor.starts_with("rawtr("))
+      .any(|descriptor| {
+        self.derive_addresses(descriptor).unwrap()[0]
+          .clone()
+          .assume_checked()
+          .script_pubkey()
+          == address.script_pubkey()
+      });
+
+    let state = self.state();
+
+    Ok(serde_json::json!({
+      "address": address.to_string(),
+      "scriptPubKey": address.script_pubkey().to_hex_string(),
+      "ismine": imported || state.is_wallet_address(&address),
+      "labels": state.labels.get(&address).into_iter().collect::<Vec<&String>>(),
+    }))
+  }
+
   fn get_new_address(
     &self,
-    _label: Option<String>,
+    label: Option<String>,
     _address_type: Option<bitcoincore_rpc::json::AddressType>,
   ) -> Result<Address, jsonrpc_core::Error> {
-    Ok(self.state().new_address(false))
+    let mut state = self.state();
+
+    let address = state.new_address(false);
+
+    if let Some(label) = label {
+      state.labels.insert(address.clone(), label);
+    }
+
+    Ok(address)
+  }
+
+  fn list_received_by_address(
+    &self,
+    _minconf: Option<u32>,
+    include_empty: Option<bool>,
+    _include_watchonly: Option<bool>,
+    address_filter: Option<Address<NetworkUnchecked>>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let addresses = match address_filter {
+      Some(address) => {
+        let address = address.assume_checked();
+
+        if state.is_wallet_address(&address) || state.labels.contains_key(&address) {
+          vec![address]
+        } else {
+          Vec::new()
+        }
+      }
+      None => state.receive_addresses.clone(),
+    };
+
+    Ok(
+      addresses
+        .iter()
+        .map(|address| {
+          let txids = state
+            .transactions
+            .values()
+            .chain(state.mempool.iter())
+            .filter(|tx| {
+              tx.output
+                .iter()
+                .any(|output| output.script_pubkey == address.script_pubkey())
+            })
+            .map(|tx| tx.txid())
+            .collect::<Vec<Txid>>();
+
+          (address, txids)
+        })
+        .filter(|(_address, txids)| include_empty.unwrap_or_default() || !txids.is_empty())
+        .map(|(address, txids)| {
+          serde_json::json!({
+            "address": address.to_string(),
+            "txids": txids,
+          })
+        })
+        .collect(),
+    )
   }
 
   fn list_transactions(
@@ -10,12 +10,21 @@ use {
 #[derive(Debug)]
 pub struct State {
   pub blocks: BTreeMap<BlockHash, Block>,
+  pub broadcast_limit: Option<usize>,
+  pub derive_wrong_addresses: bool,
+  pub descriptor_labels: BTreeMap<String, String>,
   pub descriptors: Vec<String>,
   pub fail_lock_unspent: bool,
+  pub fee_estimate: Option<Amount>,
+  pub get_block_delay: Duration,
   pub hashes: Vec<BlockHash>,
+  pub incremental_relay_fee: Amount,
+  pub labels: BTreeMap<Address, String>,
   pub loaded_wallets: BTreeSet<String>,
   pub locked: BTreeSet<OutPoint>,
   pub mempool: Vec<Transaction>,
+  pub mempool_reject_reason: Option<String>,
+  pub min_relay_tx_fee: Amount,
   pub network: Network,
   pub nonce: u32,
   pub transactions: BTreeMap<Txid, Transaction>,
@@ -24,6 +33,7 @@ pub struct State {
   pub version: usize,
   pub receive_addresses: Vec<Address>,
   pub change_addresses: Vec<Address>,
+  pub taproot_activation_height: usize,
   pub wallets: BTreeSet<String>,
 }
 
@@ -39,16 +49,26 @@ impl State {
 
     Self {
       blocks,
+      broadcast_limit: None,
       change_addresses: Vec::new(),
+      derive_wrong_addresses: false,
+      descriptor_labels: BTreeMap::new(),
       descriptors: Vec::new(),
       fail_lock_unspent,
+      fee_estimate: None,
+      get_block_delay: Duration::ZERO,
       hashes,
+      incremental_relay_fee: Amount::ZERO,
+      labels: BTreeMap::new(),
       loaded_wallets: BTreeSet::new(),
       locked: BTreeSet::new(),
       mempool: Vec::new(),
+      mempool_reject_reason: None,
+      min_relay_tx_fee: Amount::ZERO,
       network,
       nonce: 0,
       receive_addresses: Vec::new(),
+      taproot_activation_height: 0,
       transactions: BTreeMap::new(),
       txid_to_block_height: BTreeMap::new(),
       utxos: BTreeMap::new(),
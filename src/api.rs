@@ -78,6 +78,7 @@ pub struct Inscription {
   pub address: Option<String>,
   pub charms: Vec<Charm>,
   pub children: Vec<InscriptionId>,
+  pub content_hash: Option<sha256::Hash>,
   pub content_length: Option<usize>,
   pub content_type: Option<String>,
   pub effective_content_type: Option<String>,
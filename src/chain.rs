@@ -33,6 +33,14 @@ impl Chain {
     }
   }
 
+  /// Fee rate used when none is given on the command line or in settings.
+  pub(crate) fn default_fee_rate(self) -> FeeRate {
+    match self {
+      Self::Mainnet => FeeRate::try_from(10.0).unwrap(),
+      Self::Regtest | Self::Signet | Self::Testnet => FeeRate::try_from(1.0).unwrap(),
+    }
+  }
+
   pub(crate) fn first_inscription_height(self) -> u32 {
     match self {
       Self::Mainnet => 767430,
@@ -138,4 +146,12 @@ mod tests {
       "invalid chain `foo`"
     );
   }
+
+  #[test]
+  fn default_fee_rate() {
+    assert_eq!(Chain::Mainnet.default_fee_rate().n(), 10.0);
+    assert_eq!(Chain::Regtest.default_fee_rate().n(), 1.0);
+    assert_eq!(Chain::Signet.default_fee_rate().n(), 1.0);
+    assert_eq!(Chain::Testnet.default_fee_rate().n(), 1.0);
+  }
 }
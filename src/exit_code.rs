@@ -0,0 +1,108 @@
+use {super::*, wallet::transaction_builder};
+
+/// Process exit codes. Usage errors are reported by clap with code 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ExitCode {
+  /// Validation or other user error
+  Error = 1,
+  /// Bitcoin Core RPC or ord server request failed
+  Rpc = 3,
+  /// Wallet cannot fund the requested operation
+  InsufficientFunds = 4,
+  /// Stopped cleanly by `<CTRL-C>`, 128 plus the SIGINT signal number
+  Interrupted = 130,
+}
+
+impl ExitCode {
+  pub(crate) fn for_error(err: &Error) -> Self {
+    for cause in err.chain() {
+      if cause.is::<InsufficientFunds>()
+        || matches!(
+          cause.downcast_ref::<transaction_builder::Error>(),
+          Some(transaction_builder::Error::NotEnoughCardinalUtxos)
+        )
+      {
+        return Self::InsufficientFunds;
+      }
+
+      if cause.is::<Interrupted>() {
+        return Self::Interrupted;
+      }
+
+      if cause.is::<bitcoincore_rpc::Error>() || cause.is::<reqwest::Error>() {
+        return Self::Rpc;
+      }
+    }
+
+    Self::Error
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct InsufficientFunds(pub(crate) String);
+
+impl Display for InsufficientFunds {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for InsufficientFunds {}
+
+/// Index update stopped by `<CTRL-C>` after committing block `height`, which
+/// the next update resumes from.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Interrupted {
+  pub(crate) height: u32,
+}
+
+impl Display for Interrupted {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "index update interrupted after committing block {}, run again to resume",
+      self.height
+    )
+  }
+}
+
+impl std::error::Error for Interrupted {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn errors_are_classified() {
+    assert_eq!(ExitCode::for_error(&anyhow!("foo")), ExitCode::Error);
+
+    assert_eq!(
+      ExitCode::for_error(&InsufficientFunds("foo".into()).into()),
+      ExitCode::InsufficientFunds
+    );
+
+    assert_eq!(
+      ExitCode::for_error(&Error::from(
+        transaction_builder::Error::NotEnoughCardinalUtxos
+      )),
+      ExitCode::InsufficientFunds
+    );
+
+    assert_eq!(
+      ExitCode::for_error(&Interrupted { height: 0 }.into()),
+      ExitCode::Interrupted
+    );
+
+    assert_eq!(
+      ExitCode::for_error(&Error::from(transaction_builder::Error::ValueOverflow)),
+      ExitCode::Error
+    );
+
+    assert_eq!(
+      ExitCode::for_error(
+        &Error::from(bitcoincore_rpc::Error::ReturnedError("foo".into())).context("bar")
+      ),
+      ExitCode::Rpc
+    );
+  }
+}
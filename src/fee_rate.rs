@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "f64")]
 pub struct FeeRate(f64);
 
 impl FromStr for FeeRate {
@@ -257,11 +257,12 @@ impl Index {
     let once = Once::new();
     let progress_bar = Mutex::new(None);
     let integration_test = settings.integration_test();
+    let quiet = settings.quiet();
 
     let repair_callback = move |progress: &mut RepairSession| {
       once.call_once(|| println!("Index file `{}` needs recovery. This can take a long time, especially for the --index-sats index.", index_path.display()));
 
-      if !(cfg!(test) || log_enabled!(log::Level::Info) || integration_test) {
+      if !(cfg!(test) || log_enabled!(log::Level::Info) || integration_test || quiet) {
         let mut guard = progress_bar.lock().unwrap();
 
         let progress_bar = guard.get_or_insert_with(|| {
@@ -590,6 +591,8 @@ impl Index {
   }
 
   pub fn update(&self) -> Result {
+    let start = Instant::now();
+
     loop {
       let wtx = self.begin_write()?;
 
@@ -610,7 +613,10 @@ impl Index {
       };
 
       match updater.update_index(wtx) {
-        Ok(ok) => return Ok(ok),
+        Ok(ok) => {
+          log::info!("Updated index in {:?}", start.elapsed());
+          return Ok(ok);
+        }
         Err(err) => {
           log::info!("{}", err.to_string());
 
@@ -60,13 +60,17 @@ impl<'index> Updater<'index> {
       || log_enabled!(log::Level::Info)
       || starting_height <= self.height
       || self.index.settings.integration_test()
+      || self.index.settings.quiet()
     {
       None
     } else {
       let progress_bar = ProgressBar::new(starting_height.into());
       progress_bar.set_position(self.height.into());
       progress_bar.set_style(
-        ProgressStyle::with_template("[indexing blocks] {wide_bar} {pos}/{len}").unwrap(),
+        ProgressStyle::with_template(
+          "[indexing blocks] {wide_bar} {pos}/{len} {per_sec} ({eta} remaining)",
+        )
+        .unwrap(),
       );
       Some(progress_bar)
     };
@@ -75,6 +79,7 @@ impl<'index> Updater<'index> {
 
     let (mut outpoint_sender, mut value_receiver) = Self::spawn_fetcher(&self.index.settings)?;
 
+    let mut interrupted = false;
     let mut uncommitted = 0;
     let mut value_cache = HashMap::new();
     while let Ok(block) = rx.recv() {
@@ -128,6 +133,7 @@ impl<'index> Updater<'index> {
       }
 
       if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        interrupted = true;
         break;
       }
     }
@@ -147,6 +153,15 @@ impl<'index> Updater<'index> {
       progress_bar.finish_and_clear();
     }
 
+    if interrupted {
+      return Err(
+        Interrupted {
+          height: self.height.saturating_sub(1),
+        }
+        .into(),
+      );
+    }
+
     Ok(())
   }
 
@@ -734,6 +749,9 @@ impl<'index> Updater<'index> {
     Index::increment_statistic(&wtx, Statistic::SatRanges, self.sat_ranges_since_flush)?;
     self.sat_ranges_since_flush = 0;
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+
+    let _commit = INDEX_COMMIT.lock().unwrap();
+
     wtx.commit()?;
 
     Reorg::update_savepoints(self.index, self.height)?;
@@ -2,13 +2,18 @@ use super::*;
 
 use tag::Tag;
 
-pub(crate) use self::{envelope::ParsedEnvelope, media::Media};
+pub(crate) use self::{compression::Compression, envelope::ParsedEnvelope, media::Media};
 
-pub use self::{envelope::Envelope, inscription::Inscription, inscription_id::InscriptionId};
+pub use self::{
+  envelope::Envelope, inscription::Inscription, inscription_id::InscriptionId, manifest::Manifest,
+};
 
+mod compression;
 mod envelope;
 mod inscription;
 pub(crate) mod inscription_id;
+mod manifest;
 pub(crate) mod media;
 mod tag;
 pub(crate) mod teleburn;
+mod validation;
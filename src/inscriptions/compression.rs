@@ -0,0 +1,188 @@
+use {
+  super::*,
+  brotli::enc::{
+    backward_references::BrotliEncoderMode, writer::CompressorWriter, BrotliEncoderParams,
+  },
+  flate2::{read::GzDecoder, write::GzEncoder},
+  io::Write,
+};
+
+/// Content encodings tried when creating an inscription. Content is only
+/// encoded if that makes it smaller, and the content encoding tag records the
+/// encoding used, so viewers can decode it with `Compression::decode`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Compression {
+  None,
+  Brotli,
+  Auto,
+}
+
+impl Compression {
+  const BROTLI: &'static [u8] = b"br";
+  const GZIP: &'static [u8] = b"gzip";
+
+  /// Compression selected by the `--compress` and `--compress-auto` flags.
+  pub(crate) fn from_flags(compress: bool, compress_auto: bool) -> Self {
+    if compress_auto {
+      Self::Auto
+    } else if compress {
+      Self::Brotli
+    } else {
+      Self::None
+    }
+  }
+
+  fn encodings(self) -> &'static [&'static [u8]] {
+    match self {
+      Self::None => &[],
+      Self::Brotli => &[Self::BROTLI],
+      Self::Auto => &[Self::BROTLI, Self::GZIP],
+    }
+  }
+
+  /// Encode `body` with the smallest of this mode's encodings, returning the
+  /// encoded body and its content encoding tag, or `body` and no tag if no
+  /// encoding makes it smaller.
+  pub(crate) fn apply(
+    self,
+    body: Vec<u8>,
+    mode: BrotliEncoderMode,
+  ) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let mut smallest: Option<(Vec<u8>, &[u8])> = None;
+
+    for &encoding in self.encodings() {
+      let encoded = Self::encode(encoding, &body, mode)?;
+
+      ensure!(
+        Self::decode(encoding, &encoded)? == body,
+        "decompression roundtrip failed"
+      );
+
+      let len = smallest
+        .as_ref()
+        .map(|(encoded, _encoding)| encoded.len())
+        .unwrap_or(body.len());
+
+      if encoded.len() < len {
+        smallest = Some((encoded, encoding));
+      }
+    }
+
+    Ok(match smallest {
+      Some((encoded, encoding)) => (encoded, Some(encoding.to_vec())),
+      None => (body, None),
+    })
+  }
+
+  fn encode(encoding: &[u8], body: &[u8], mode: BrotliEncoderMode) -> Result<Vec<u8>> {
+    let mut encoded = Vec::new();
+
+    match encoding {
+      Self::BROTLI => {
+        CompressorWriter::with_params(
+          &mut encoded,
+          body.len(),
+          &BrotliEncoderParams {
+            lgblock: 24,
+            lgwin: 24,
+            mode,
+            quality: 11,
+            size_hint: body.len(),
+            ..default()
+          },
+        )
+        .write_all(body)?;
+      }
+      Self::GZIP => {
+        let mut encoder = GzEncoder::new(&mut encoded, flate2::Compression::best());
+        encoder.write_all(body)?;
+        encoder.finish()?;
+      }
+      _ => unreachable!(),
+    }
+
+    Ok(encoded)
+  }
+
+  /// Decode `body` according to content encoding tag `encoding`.
+  pub(crate) fn decode(encoding: &[u8], body: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    match encoding {
+      Self::BROTLI => {
+        brotli::Decompressor::new(body, 4096).read_to_end(&mut decoded)?;
+      }
+      Self::GZIP => {
+        GzDecoder::new(body).read_to_end(&mut decoded)?;
+      }
+      _ => bail!(
+        "unsupported content encoding `{}`",
+        String::from_utf8_lossy(encoding)
+      ),
+    }
+
+    Ok(decoded)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TEXT: BrotliEncoderMode = BrotliEncoderMode::BROTLI_MODE_TEXT;
+
+  #[test]
+  fn none_leaves_content_unencoded() {
+    assert_eq!(
+      Compression::None.apply(vec![0; 1000], TEXT).unwrap(),
+      (vec![0; 1000], None)
+    );
+  }
+
+  #[test]
+  fn incompressible_content_is_left_unencoded() {
+    for compression in [Compression::Brotli, Compression::Auto] {
+      assert_eq!(
+        compression.apply(b"a".to_vec(), TEXT).unwrap(),
+        (b"a".to_vec(), None)
+      );
+    }
+  }
+
+  #[test]
+  fn auto_picks_smallest_encoding() {
+    let text = "the quick brown fox jumps over the lazy dog. "
+      .repeat(100)
+      .into_bytes();
+
+    for body in [text, vec![0; 10_000]] {
+      let (encoded, encoding) = Compression::Auto.apply(body.clone(), TEXT).unwrap();
+
+      let encoding = encoding.unwrap();
+
+      let brotli = Compression::Brotli.apply(body.clone(), TEXT).unwrap().0;
+      let gzip = Compression::encode(Compression::GZIP, &body, TEXT).unwrap();
+
+      assert_eq!(encoded.len(), brotli.len().min(gzip.len()));
+      assert_eq!(
+        encoding,
+        if brotli.len() <= gzip.len() {
+          b"br".to_vec()
+        } else {
+          b"gzip".to_vec()
+        }
+      );
+      assert_eq!(Compression::decode(&encoding, &encoded).unwrap(), body);
+    }
+  }
+
+  #[test]
+  fn decode_rejects_unknown_encoding() {
+    assert_eq!(
+      Compression::decode(b"deflate", &[])
+        .unwrap_err()
+        .to_string(),
+      "unsupported content encoding `deflate`",
+    );
+  }
+}
@@ -73,6 +73,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
         content_type,
         delegate,
         duplicate_field,
+        extra_tags: Vec::new(),
         incomplete_field,
         metadata,
         metaprotocol,
@@ -765,6 +766,56 @@ mod tests {
     );
   }
 
+  #[test]
+  fn body_round_trips_across_push_limit_boundaries() {
+    // OP_FALSE, OP_IF, protocol ID, content type tag, content type, body tag
+    const HEADER_INSTRUCTIONS: usize = 6;
+
+    for len in [0, 1, 519, 520, 521, 1040] {
+      let body = (0..len)
+        .map(|i| u8::try_from(i % 255 + 1).unwrap())
+        .collect::<Vec<u8>>();
+
+      let inscription = inscription("foo", &body);
+
+      let script = inscription.append_reveal_script(script::Builder::new());
+
+      let pushes = script
+        .instructions()
+        .skip(HEADER_INSTRUCTIONS)
+        .map(Result::unwrap)
+        .filter_map(|instruction| match instruction {
+          PushBytes(push) => Some(push.len()),
+          Op(_) => None,
+        })
+        .collect::<Vec<usize>>();
+
+      assert_eq!(
+        pushes.len(),
+        (len + MAX_SCRIPT_ELEMENT_SIZE - 1) / MAX_SCRIPT_ELEMENT_SIZE
+      );
+      assert_eq!(pushes.iter().sum::<usize>(), len);
+      assert!(pushes
+        .iter()
+        .all(|push| *push > 0 && *push <= MAX_SCRIPT_ELEMENT_SIZE));
+
+      let mut witness = Witness::new();
+
+      witness.push(script);
+
+      witness.push([]);
+
+      assert_eq!(
+        parse(&[witness]),
+        vec![ParsedEnvelope {
+          payload: inscription,
+          ..default()
+        }],
+        "body of {len} bytes did not round-trip",
+      );
+    }
+  }
+
   #[test]
   fn unknown_odd_fields_are_ignored() {
     assert_eq!(
@@ -1,11 +1,6 @@
 use {
-  super::*,
-  anyhow::ensure,
-  bitcoin::blockdata::opcodes,
-  brotli::enc::{writer::CompressorWriter, BrotliEncoderParams},
-  http::header::HeaderValue,
-  io::Write,
-  std::str,
+  super::*, anyhow::ensure, bitcoin::blockdata::opcodes,
+  brotli::enc::backward_references::BrotliEncoderMode, http::header::HeaderValue, std::str,
 };
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
@@ -15,6 +10,7 @@ pub struct Inscription {
   pub content_type: Option<Vec<u8>>,
   pub delegate: Option<Vec<u8>>,
   pub duplicate_field: bool,
+  pub extra_tags: Vec<(u8, Vec<u8>)>,
   pub incomplete_field: bool,
   pub metadata: Option<Vec<u8>>,
   pub metaprotocol: Option<Vec<u8>>,
@@ -36,7 +32,8 @@ impl Inscription {
 
   pub(crate) fn from_file(
     chain: Chain,
-    compress: bool,
+    compression: Compression,
+    content_type: Option<String>,
     delegate: Option<InscriptionId>,
     metadata: Option<Vec<u8>>,
     metaprotocol: Option<String>,
@@ -44,49 +41,149 @@ impl Inscription {
     path: impl AsRef<Path>,
     pointer: Option<u64>,
     rune: Option<Rune>,
+    validate_content: bool,
   ) -> Result<Self, Error> {
     let path = path.as_ref();
 
     let body = fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
 
-    let (content_type, compression_mode) = Media::content_type_for_path(path)?;
+    let (content_type, compression_mode) = match content_type {
+      Some(content_type) => {
+        let compression_mode = Media::compression_mode_for_content_type(&content_type);
+        (content_type, compression_mode)
+      }
+      None => {
+        let (content_type, compression_mode) = Media::content_type_for_file(path, &body)?;
+        (content_type.into(), compression_mode)
+      }
+    };
 
-    let (body, content_encoding) = if compress {
-      let mut compressed = Vec::new();
+    Self::from_content(
+      body,
+      chain,
+      compression,
+      compression_mode,
+      &content_type,
+      delegate,
+      metadata,
+      metaprotocol,
+      parents,
+      pointer,
+      rune,
+      validate_content,
+    )
+  }
 
-      {
-        CompressorWriter::with_params(
-          &mut compressed,
-          body.len(),
-          &BrotliEncoderParams {
-            lgblock: 24,
-            lgwin: 24,
-            mode: compression_mode,
-            quality: 11,
-            size_hint: body.len(),
-            ..default()
-          },
-        )
-        .write_all(&body)?;
+  pub(crate) fn from_url(
+    chain: Chain,
+    compression: Compression,
+    content_type: Option<String>,
+    delegate: Option<InscriptionId>,
+    metadata: Option<Vec<u8>>,
+    metaprotocol: Option<String>,
+    parents: Vec<InscriptionId>,
+    url: &Url,
+    pointer: Option<u64>,
+    rune: Option<Rune>,
+    validate_content: bool,
+  ) -> Result<Self, Error> {
+    // content larger than a block can never be inscribed
+    const MAX_CONTENT_SIZE: usize = 4_000_000;
 
-        let mut decompressor = brotli::Decompressor::new(compressed.as_slice(), compressed.len());
+    // compressed content may fit even if the download does not, so only
+    // enforce the chain limit on uncompressed downloads
+    let limit = chain
+      .inscription_content_size_limit()
+      .filter(|_| compression == Compression::None)
+      .unwrap_or(MAX_CONTENT_SIZE);
 
-        let mut decompressed = Vec::new();
+    let response =
+      reqwest::blocking::get(url.clone()).with_context(|| format!("error downloading {url}"))?;
 
-        decompressor.read_to_end(&mut decompressed)?;
+    let status = response.status();
 
-        ensure!(decompressed == body, "decompression roundtrip failed");
-      }
+    ensure!(
+      status.is_success(),
+      "error downloading {url}: server returned {status}"
+    );
+
+    if let Some(content_length) = response.content_length() {
+      ensure!(
+        content_length <= u64::try_from(limit).unwrap(),
+        "download of {url} is {content_length} bytes, which exceeds {limit} byte limit"
+      );
+    }
+
+    let header_content_type = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .map(|content_type| {
+        content_type
+          .to_str()
+          .map(str::to_string)
+          .with_context(|| format!("invalid content type header downloading {url}"))
+      })
+      .transpose()?;
+
+    let mut body = Vec::new();
+
+    response
+      .take(u64::try_from(limit).unwrap() + 1)
+      .read_to_end(&mut body)
+      .with_context(|| format!("error downloading {url}"))?;
+
+    ensure!(
+      body.len() <= limit,
+      "download of {url} exceeds {limit} byte limit"
+    );
 
-      if compressed.len() < body.len() {
-        (compressed, Some("br".as_bytes().to_vec()))
-      } else {
-        (body, None)
+    let (content_type, compression_mode) = match content_type.or(header_content_type) {
+      Some(content_type) => {
+        let compression_mode = Media::compression_mode_for_content_type(&content_type);
+        (content_type, compression_mode)
+      }
+      None => {
+        let (content_type, compression_mode) = Media::content_type_for_path(Path::new(url.path()))?;
+        (content_type.into(), compression_mode)
       }
-    } else {
-      (body, None)
     };
 
+    Self::from_content(
+      body,
+      chain,
+      compression,
+      compression_mode,
+      &content_type,
+      delegate,
+      metadata,
+      metaprotocol,
+      parents,
+      pointer,
+      rune,
+      validate_content,
+    )
+  }
+
+  fn from_content(
+    body: Vec<u8>,
+    chain: Chain,
+    compression: Compression,
+    compression_mode: BrotliEncoderMode,
+    content_type: &str,
+    delegate: Option<InscriptionId>,
+    metadata: Option<Vec<u8>>,
+    metaprotocol: Option<String>,
+    parents: Vec<InscriptionId>,
+    pointer: Option<u64>,
+    rune: Option<Rune>,
+    validate_content: bool,
+  ) -> Result<Self, Error> {
+    if validate_content {
+      validation::validate(content_type, &body)?;
+    }
+
+    let (body, content_encoding) = compression.apply(body, compression_mode)?;
+
     if let Some(limit) = chain.inscription_content_size_limit() {
       let len = body.len();
       if len > limit {
@@ -118,6 +215,30 @@ impl Inscription {
     bytes
   }
 
+  /// Check tags added with `--tag`. Tags that ord sets from other fields
+  /// cannot be added, since duplicate fields make the inscription cursed.
+  /// Unrecognized even tags make the inscription cursed and unbound, so even
+  /// tags must be explicitly allowed.
+  pub(crate) fn check_extra_tags(&self, allow_even: bool) -> Result {
+    let mut seen = HashSet::new();
+
+    for (tag, _value) in &self.extra_tags {
+      ensure!(
+        !Tag::is_field(*tag),
+        "tag {tag} is set by ord from inscription fields and cannot be added with --tag"
+      );
+
+      ensure!(seen.insert(tag), "tag {tag} given more than once");
+
+      ensure!(
+        allow_even || tag % 2 == 1,
+        "tag {tag} is even, and unrecognized even tags make the inscription cursed and unbound, use --allow-even-tags to add it anyway"
+      );
+    }
+
+    Ok(())
+  }
+
   pub(crate) fn append_reveal_script_to_builder(
     &self,
     mut builder: script::Builder,
@@ -136,7 +257,16 @@ impl Inscription {
     Tag::Metadata.append(&mut builder, &self.metadata);
     Tag::Rune.append(&mut builder, &self.rune);
 
+    for (tag, value) in &self.extra_tags {
+      builder = builder
+        .push_slice([*tag])
+        .push_slice::<&script::PushBytes>(value.as_slice().try_into().unwrap());
+    }
+
     if let Some(body) = &self.body {
+      // An empty body is encoded as a lone body tag. Chunks are always pushed
+      // as data, since encoding a single byte body as OP_PUSHNUM would mark
+      // the envelope as pushnum.
       builder = builder.push_slice(envelope::BODY_TAG);
       for chunk in body.chunks(MAX_SCRIPT_ELEMENT_SIZE) {
         builder = builder.push_slice::<&script::PushBytes>(chunk.try_into().unwrap());
@@ -224,6 +354,11 @@ impl Inscription {
     self.body
   }
 
+  /// SHA-256 hash of the body as inscribed, without decoding it.
+  pub(crate) fn content_hash(&self) -> Option<sha256::Hash> {
+    Some(sha256::Hash::hash(self.body()?))
+  }
+
   pub(crate) fn content_length(&self) -> Option<usize> {
     Some(self.body()?.len())
   }
@@ -765,6 +900,70 @@ mod tests {
     );
   }
 
+  #[test]
+  fn extra_tags_encode() {
+    assert_eq!(
+      Inscription {
+        body: Some(vec![0xFF]),
+        extra_tags: vec![(17, vec![0xAB, 0xCD]), (21, Vec::new())],
+        rune: Some(vec![1]),
+        ..default()
+      }
+      .to_witness(),
+      envelope(&[
+        b"ord",
+        &[13],
+        &[1],
+        &[17],
+        &[0xAB, 0xCD],
+        &[21],
+        &[],
+        &[],
+        &[0xFF]
+      ]),
+    );
+  }
+
+  #[test]
+  fn check_extra_tags() {
+    #[track_caller]
+    fn case(extra_tags: &[(u8, &[u8])], allow_even: bool, expected: Option<&str>) {
+      let result = Inscription {
+        extra_tags: extra_tags
+          .iter()
+          .map(|(tag, value)| (*tag, value.to_vec()))
+          .collect(),
+        ..default()
+      }
+      .check_extra_tags(allow_even);
+
+      assert_eq!(result.err().map(|err| err.to_string()).as_deref(), expected);
+    }
+
+    case(&[(17, b"foo"), (255, b"")], false, None);
+    case(&[(18, b"foo")], true, None);
+    case(
+      &[(18, b"foo")],
+      false,
+      Some("tag 18 is even, and unrecognized even tags make the inscription cursed and unbound, use --allow-even-tags to add it anyway"),
+    );
+    case(
+      &[(17, b"foo"), (17, b"bar")],
+      false,
+      Some("tag 17 given more than once"),
+    );
+
+    for tag in [1, 2, 3, 5, 7, 9, 11, 13] {
+      case(
+        &[(tag, b"foo")],
+        true,
+        Some(&format!(
+          "tag {tag} is set by ord from inscription fields and cannot be added with --tag"
+        )),
+      );
+    }
+  }
+
   #[test]
   fn pointer_value() {
     let mut file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
@@ -773,7 +972,8 @@ mod tests {
 
     let inscription = Inscription::from_file(
       Chain::Mainnet,
-      false,
+      Compression::None,
+      None,
       None,
       None,
       None,
@@ -781,6 +981,7 @@ mod tests {
       file.path(),
       None,
       None,
+      false,
     )
     .unwrap();
 
@@ -788,7 +989,8 @@ mod tests {
 
     let inscription = Inscription::from_file(
       Chain::Mainnet,
-      false,
+      Compression::None,
+      None,
       None,
       None,
       None,
@@ -796,6 +998,7 @@ mod tests {
       file.path(),
       Some(0),
       None,
+      false,
     )
     .unwrap();
 
@@ -803,7 +1006,8 @@ mod tests {
 
     let inscription = Inscription::from_file(
       Chain::Mainnet,
-      false,
+      Compression::None,
+      None,
       None,
       None,
       None,
@@ -811,6 +1015,7 @@ mod tests {
       file.path(),
       Some(1),
       None,
+      false,
     )
     .unwrap();
 
@@ -818,7 +1023,8 @@ mod tests {
 
     let inscription = Inscription::from_file(
       Chain::Mainnet,
-      false,
+      Compression::None,
+      None,
       None,
       None,
       None,
@@ -826,12 +1032,43 @@ mod tests {
       file.path(),
       Some(256),
       None,
+      false,
     )
     .unwrap();
 
     assert_eq!(inscription.pointer, Some(vec![0, 1]));
   }
 
+  #[test]
+  fn empty_file() {
+    let file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+
+    for compression in [Compression::None, Compression::Auto] {
+      let inscription = Inscription::from_file(
+        Chain::Mainnet,
+        compression,
+        None,
+        None,
+        None,
+        None,
+        Vec::new(),
+        file.path(),
+        None,
+        None,
+        false,
+      )
+      .unwrap();
+
+      assert_eq!(inscription.body, Some(Vec::new()));
+      assert_eq!(inscription.content_encoding, None);
+
+      assert_eq!(
+        inscription.to_witness(),
+        envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[]]),
+      );
+    }
+  }
+
   #[test]
   fn hidden() {
     #[track_caller]
@@ -18,6 +18,20 @@ impl Default for InscriptionId {
 }
 
 impl InscriptionId {
+  /// Ids of the inscriptions created by `reveal`, numbered by envelope in
+  /// input order. The txid does not commit to witnesses, so ids can be
+  /// computed before the reveal transaction is broadcast.
+  pub fn from_reveal(reveal: &Transaction) -> Vec<Self> {
+    let txid = reveal.txid();
+
+    (0..ParsedEnvelope::from_transaction(reveal).len())
+      .map(|index| Self {
+        txid,
+        index: index.try_into().unwrap(),
+      })
+      .collect()
+  }
+
   pub(crate) fn value(self) -> Vec<u8> {
     let index = self.index.to_le_bytes();
     let mut index_slice = index.as_slice();
@@ -183,6 +197,69 @@ mod tests {
     );
   }
 
+  #[test]
+  fn from_str_non_canonical_is_canonicalized() {
+    for (s, canonical) in [
+      (
+        "1111111111111111111111111111111111111111111111111111111111111111i01",
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      ),
+      (
+        "1111111111111111111111111111111111111111111111111111111111111111i+1",
+        "1111111111111111111111111111111111111111111111111111111111111111i1",
+      ),
+      (
+        "1111111111111111111111111111111111111111111111111111111111111111i00",
+        "1111111111111111111111111111111111111111111111111111111111111111i0",
+      ),
+      (
+        "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAi1",
+        "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaai1",
+      ),
+    ] {
+      assert_eq!(s.parse::<InscriptionId>().unwrap().to_string(), canonical);
+    }
+  }
+
+  #[test]
+  fn round_trip() -> Result {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+    let mut next = || {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      state
+    };
+
+    let mut indices = vec![0, 1, 9, 10, 255, 256, u32::MAX - 1, u32::MAX];
+
+    for _ in 0..100 {
+      indices.push(u32::try_from(next() >> 32)?);
+    }
+
+    for index in indices {
+      let mut txid = [0; 32];
+
+      for chunk in txid.chunks_mut(8) {
+        chunk.copy_from_slice(&next().to_le_bytes());
+      }
+
+      let id = InscriptionId {
+        txid: Txid::from_byte_array(txid),
+        index,
+      };
+
+      let s = id.to_string();
+
+      assert_eq!(s.parse::<InscriptionId>().unwrap(), id);
+      assert_eq!(s.parse::<InscriptionId>().unwrap().to_string(), s);
+      assert!(s.ends_with(&format!("i{index}")));
+    }
+
+    Ok(())
+  }
+
   #[test]
   fn from_str_bad_txid() {
     assert_matches!(
@@ -190,4 +267,46 @@ mod tests {
       Err(ParseError::Txid(_)),
     );
   }
+
+  #[test]
+  fn from_reveal() {
+    let reveal = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![
+        TxIn {
+          witness: envelope(&[b"ord", &[1], b"text/plain", &[], b"foo"]),
+          ..tx_in(outpoint(1))
+        },
+        tx_in(outpoint(2)),
+        TxIn {
+          witness: envelope(&[b"ord", &[1], b"text/plain", &[], b"bar"]),
+          ..tx_in(outpoint(3))
+        },
+      ],
+      output: Vec::new(),
+    };
+
+    assert_eq!(
+      InscriptionId::from_reveal(&reveal),
+      [
+        InscriptionId {
+          txid: reveal.txid(),
+          index: 0,
+        },
+        InscriptionId {
+          txid: reveal.txid(),
+          index: 1,
+        },
+      ],
+    );
+
+    assert_eq!(
+      InscriptionId::from_reveal(&Transaction {
+        input: vec![tx_in(outpoint(1))],
+        ..reveal
+      }),
+      Vec::new(),
+    );
+  }
 }
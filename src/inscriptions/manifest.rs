@@ -0,0 +1,193 @@
+use {super::*, bitcoin::hashes::sha256};
+
+/// Manifest inscribed by `ord wallet inscribe --shard-size`. Content too large
+/// to inscribe in one reveal transaction is split into shard inscriptions, and
+/// the manifest lists them in order, so viewers can fetch and concatenate
+/// their contents to reassemble the original content.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+  pub content_encoding: Option<String>,
+  pub content_type: Option<String>,
+  pub length: usize,
+  pub sha256: String,
+  pub shards: Vec<InscriptionId>,
+}
+
+impl Manifest {
+  pub(crate) const CONTENT_TYPE: &'static str = "application/json";
+
+  const SHARD_CONTENT_TYPE: &'static str = "application/octet-stream";
+
+  /// Split the body of `inscription` into shard inscriptions of at most
+  /// `shard_size` bytes.
+  pub(crate) fn shards(inscription: &Inscription, shard_size: usize) -> Result<Vec<Inscription>> {
+    let body = inscription.body().unwrap_or_default();
+
+    ensure!(!body.is_empty(), "cannot shard inscription without content");
+
+    Ok(
+      body
+        .chunks(shard_size)
+        .map(|shard| Inscription {
+          body: Some(shard.into()),
+          content_type: Some(Self::SHARD_CONTENT_TYPE.into()),
+          ..default()
+        })
+        .collect(),
+    )
+  }
+
+  pub(crate) fn new(inscription: &Inscription, shards: Vec<InscriptionId>) -> Self {
+    let body = inscription.body().unwrap_or_default();
+
+    Self {
+      content_encoding: inscription
+        .content_encoding()
+        .and_then(|encoding| encoding.to_str().ok().map(str::to_owned)),
+      content_type: inscription.content_type().map(str::to_owned),
+      length: body.len(),
+      sha256: sha256::Hash::hash(body).to_string(),
+      shards,
+    }
+  }
+
+  /// The manifest inscription, with the metadata, metaprotocol, and extra
+  /// tags of the original inscription.
+  pub(crate) fn inscription(&self, inscription: &Inscription) -> Inscription {
+    Inscription {
+      body: Some(serde_json::to_vec(self).unwrap()),
+      content_type: Some(Self::CONTENT_TYPE.into()),
+      extra_tags: inscription.extra_tags.clone(),
+      metadata: inscription.metadata.clone(),
+      metaprotocol: inscription.metaprotocol.clone(),
+      ..default()
+    }
+  }
+
+  /// Concatenate shard contents, in the same order as `self.shards`, and
+  /// check that the result has the length and hash of the original content.
+  pub fn reassemble(&self, shards: &[Vec<u8>]) -> Result<Vec<u8>> {
+    ensure!(
+      shards.len() == self.shards.len(),
+      "manifest has {} shards but {} were given",
+      self.shards.len(),
+      shards.len(),
+    );
+
+    let content = shards.concat();
+
+    ensure!(
+      content.len() == self.length,
+      "reassembled content is {} bytes but manifest length is {} bytes",
+      content.len(),
+      self.length,
+    );
+
+    ensure!(
+      sha256::Hash::hash(&content).to_string() == self.sha256,
+      "reassembled content does not match manifest hash {}",
+      self.sha256,
+    );
+
+    Ok(content)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn shards_reassemble_to_original_content() {
+    let content = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+
+    let inscription = Inscription {
+      content_encoding: Some("br".into()),
+      ..inscription("image/png", &content)
+    };
+
+    let shards = Manifest::shards(&inscription, 300).unwrap();
+
+    assert_eq!(
+      shards
+        .iter()
+        .map(|shard| shard.content_length().unwrap())
+        .collect::<Vec<usize>>(),
+      [300, 300, 300, 100],
+    );
+
+    for shard in &shards {
+      assert_eq!(shard.content_type(), Some("application/octet-stream"));
+      assert_eq!(shard.content_encoding(), None);
+    }
+
+    let manifest = Manifest::new(
+      &inscription,
+      (0..4).map(|n| inscription_id(n + 1)).collect(),
+    );
+
+    assert_eq!(manifest.content_encoding.as_deref(), Some("br"));
+    assert_eq!(manifest.content_type.as_deref(), Some("image/png"));
+    assert_eq!(manifest.length, 1000);
+
+    let manifest_inscription = manifest.inscription(&inscription);
+
+    assert_eq!(
+      manifest_inscription.content_type(),
+      Some("application/json")
+    );
+
+    let manifest: Manifest = serde_json::from_slice(manifest_inscription.body().unwrap()).unwrap();
+
+    let bodies = shards
+      .into_iter()
+      .map(|shard| shard.into_body().unwrap())
+      .collect::<Vec<Vec<u8>>>();
+
+    assert_eq!(manifest.reassemble(&bodies).unwrap(), content);
+  }
+
+  #[test]
+  fn reassemble_checks_shards() {
+    let inscription = inscription("text/plain", "foobar");
+
+    let manifest = Manifest::new(&inscription, vec![inscription_id(1), inscription_id(2)]);
+
+    assert_eq!(
+      manifest
+        .reassemble(&[b"foo".to_vec()])
+        .unwrap_err()
+        .to_string(),
+      "manifest has 2 shards but 1 were given",
+    );
+
+    assert_eq!(
+      manifest
+        .reassemble(&[b"foo".to_vec(), b"ba".to_vec()])
+        .unwrap_err()
+        .to_string(),
+      "reassembled content is 5 bytes but manifest length is 6 bytes",
+    );
+
+    assert_eq!(
+      manifest
+        .reassemble(&[b"bar".to_vec(), b"foo".to_vec()])
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "reassembled content does not match manifest hash {}",
+        manifest.sha256
+      ),
+    );
+  }
+
+  #[test]
+  fn inscription_without_content_cannot_be_sharded() {
+    assert_eq!(
+      Manifest::shards(&Inscription::default(), 10)
+        .unwrap_err()
+        .to_string(),
+      "cannot shard inscription without content",
+    );
+  }
+}
@@ -5,7 +5,7 @@ use {
     self, BROTLI_MODE_FONT as FONT, BROTLI_MODE_GENERIC as GENERIC, BROTLI_MODE_TEXT as TEXT,
   },
   mp4::{MediaType, Mp4Reader, TrackType},
-  std::{fs::File, io::BufReader},
+  std::{fs::File, io::BufReader, str},
 };
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -109,6 +109,32 @@ impl Media {
     ("video/webm",                  GENERIC, Video,            &["webm"]),
   ];
 
+  /// Signatures of content types recognized in files without a recognized
+  /// extension, as offset, magic bytes, and content type. The first matching
+  /// signature is used.
+  #[rustfmt::skip]
+  const SIGNATURES: &'static [(usize, &'static [u8], &'static str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n",  "image/png"),
+    (0, b"%PDF-",              "application/pdf"),
+    (0, b"GIF87a",             "image/gif"),
+    (0, b"GIF89a",             "image/gif"),
+    (0, b"\xFF\xD8\xFF",       "image/jpeg"),
+    (0, b"\x1A\x45\xDF\xA3",   "video/webm"),
+    (0, b"ID3",                "audio/mpeg"),
+    (0, b"fLaC",               "audio/flac"),
+    (0, b"glTF",               "model/gltf-binary"),
+    (0, b"wOF2",               "font/woff2"),
+    (0, b"wOFF",               "font/woff"),
+    (4, b"ftypavif",           "image/avif"),
+    (4, b"ftypavc1",           "video/mp4"),
+    (4, b"ftypiso2",           "video/mp4"),
+    (4, b"ftypisom",           "video/mp4"),
+    (4, b"ftypmp41",           "video/mp4"),
+    (4, b"ftypmp42",           "video/mp4"),
+    (8, b"WAVE",               "audio/wav"),
+    (8, b"WEBP",               "image/webp"),
+  ];
+
   pub(crate) fn content_type_for_path(
     path: &Path,
   ) -> Result<(&'static str, BrotliEncoderMode), Error> {
@@ -143,6 +169,121 @@ impl Media {
     ))
   }
 
+  /// Returns the content type of the file at `path` with contents `body`,
+  /// from its extension, or if it has no extension or an unrecognized one,
+  /// from the signature of its contents.
+  pub(crate) fn content_type_for_file(
+    path: &Path,
+    body: &[u8],
+  ) -> Result<(&'static str, BrotliEncoderMode), Error> {
+    let known_extension = path
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .map(|extension| extension.to_lowercase())
+      .map(|extension| {
+        Self::TABLE
+          .iter()
+          .any(|(_, _, _, extensions)| extensions.contains(&extension.as_str()))
+      })
+      .unwrap_or_default();
+
+    if known_extension {
+      return Self::content_type_for_path(path);
+    }
+
+    let Some(content_type) = Self::sniff(body) else {
+      return Self::content_type_for_path(path);
+    };
+
+    if content_type == "video/mp4" {
+      Self::check_mp4_codec(path)?;
+    }
+
+    Ok((
+      content_type,
+      Self::compression_mode_for_content_type(content_type),
+    ))
+  }
+
+  /// Returns the content type of `body` from its magic bytes, or, if it is
+  /// UTF-8 without control characters, from the start of its text.
+  pub(crate) fn sniff(body: &[u8]) -> Option<&'static str> {
+    for (offset, signature, content_type) in Self::SIGNATURES {
+      if body
+        .get(*offset..)
+        .map(|rest| rest.starts_with(signature))
+        .unwrap_or_default()
+      {
+        return Some(*content_type);
+      }
+    }
+
+    let text = str::from_utf8(body).ok()?;
+
+    if text.is_empty()
+      || text
+        .chars()
+        .any(|c| c.is_control() && !c.is_ascii_whitespace())
+    {
+      return None;
+    }
+
+    let start = text.trim_start().to_lowercase();
+
+    if (start.starts_with('{') || start.starts_with('['))
+      && serde_json::from_str::<serde_json::Value>(text).is_ok()
+    {
+      Some("application/json")
+    } else if start.starts_with("<!doctype html") || start.starts_with("<html") {
+      Some("text/html;charset=utf-8")
+    } else if start.starts_with("<svg") {
+      Some("image/svg+xml")
+    } else {
+      Some("text/plain;charset=utf-8")
+    }
+  }
+
+  /// Returns the preferred file extension for `content_type`, ignoring
+  /// parameters such as `charset` if there is no exact match.
+  pub(crate) fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+    let entries = || {
+      Self::TABLE
+        .iter()
+        .filter(|(_, _, _, extensions)| !extensions.is_empty())
+    };
+
+    entries()
+      .find(|(entry, _, _, _)| *entry == content_type)
+      .or_else(|| {
+        entries().find(|(entry, _, _, _)| Self::essence(entry) == Self::essence(content_type))
+      })
+      .map(|(_, _, _, extensions)| extensions[0])
+  }
+
+  /// Returns the brotli compression mode for `content_type`, or the generic
+  /// mode if `content_type` is not recognized.
+  pub(crate) fn compression_mode_for_content_type(content_type: &str) -> BrotliEncoderMode {
+    Self::TABLE
+      .iter()
+      .find(|(entry, _, _, _)| *entry == content_type)
+      .or_else(|| {
+        Self::TABLE
+          .iter()
+          .find(|(entry, _, _, _)| Self::essence(entry) == Self::essence(content_type))
+      })
+      .map(|(_, mode, _, _)| *mode)
+      .unwrap_or(GENERIC)
+  }
+
+  fn essence(content_type: &str) -> String {
+    content_type
+      .split(';')
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_lowercase()
+  }
+
   pub(crate) fn check_mp4_codec(path: &Path) -> Result<(), Error> {
     let f = File::open(path)?;
     let size = f.metadata()?.len();
@@ -210,6 +351,131 @@ mod tests {
     );
   }
 
+  #[test]
+  fn sniff() {
+    assert_eq!(
+      Media::sniff(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0DIHDR"),
+      Some("image/png")
+    );
+    assert_eq!(Media::sniff(b"GIF87a\x01\x00"), Some("image/gif"));
+    assert_eq!(Media::sniff(b"GIF89a\x01\x00"), Some("image/gif"));
+    assert_eq!(
+      Media::sniff(b"%PDF-1.7\n%\xE2\xE3"),
+      Some("application/pdf")
+    );
+    assert_eq!(
+      Media::sniff(b"hello world\n"),
+      Some("text/plain;charset=utf-8")
+    );
+    assert_eq!(
+      Media::sniff("\u{1F600}\tunicode\r\n".as_bytes()),
+      Some("text/plain;charset=utf-8")
+    );
+    assert_eq!(
+      Media::sniff(b" {\"foo\": [1, 2]}"),
+      Some("application/json")
+    );
+    assert_eq!(Media::sniff(b"{not json"), Some("text/plain;charset=utf-8"));
+    assert_eq!(
+      Media::sniff(b"<!DOCTYPE html><html></html>"),
+      Some("text/html;charset=utf-8")
+    );
+    assert_eq!(
+      Media::sniff(b"\x00\x00\x00\x18ftypavif"),
+      Some("image/avif")
+    );
+    assert_eq!(Media::sniff(b"\x00\x00\x00\x18ftypisom"), Some("video/mp4"));
+    assert_eq!(Media::sniff(b"\x00\x00\x00\x18ftypheic"), None);
+    assert_eq!(Media::sniff(b"\x00\x00\x00\x14ftypqt  "), None);
+    assert_eq!(Media::sniff(b"\x00\x00\x00\x18ftyp3gp4"), None);
+    assert_eq!(
+      Media::sniff(b"RIFF\x04\x00\x00\x00WEBP"),
+      Some("image/webp")
+    );
+    assert_eq!(Media::sniff(b""), None);
+    assert_eq!(Media::sniff(&[1; 520]), None);
+    assert_eq!(Media::sniff(b"\xFF\xFE\xFD"), None);
+  }
+
+  #[test]
+  fn content_type_for_file() {
+    assert_eq!(
+      Media::content_type_for_file(Path::new("download"), b"GIF89a\x01\x00").unwrap(),
+      ("image/gif", BrotliEncoderMode::BROTLI_MODE_GENERIC)
+    );
+    assert_eq!(
+      Media::content_type_for_file(Path::new("download.tmp"), b"hello").unwrap(),
+      (
+        "text/plain;charset=utf-8",
+        BrotliEncoderMode::BROTLI_MODE_TEXT
+      )
+    );
+    assert_eq!(
+      Media::content_type_for_file(Path::new("pepe.txt"), b"GIF89a\x01\x00").unwrap(),
+      (
+        "text/plain;charset=utf-8",
+        BrotliEncoderMode::BROTLI_MODE_TEXT
+      )
+    );
+    assert_regex_match!(
+      Media::content_type_for_file(Path::new("download"), &[1; 520]).unwrap_err(),
+      "file must have extension"
+    );
+    assert_regex_match!(
+      Media::content_type_for_file(Path::new("pepe.xyz"), &[1; 520]).unwrap_err(),
+      r"unsupported file extension `\.xyz`, supported extensions: apng .*"
+    );
+  }
+
+  #[test]
+  fn sniffed_content_types_are_in_table() {
+    for (_, _, content_type) in Media::SIGNATURES {
+      assert!(
+        Media::TABLE
+          .iter()
+          .any(|(entry, _, _, _)| entry == content_type),
+        "unknown content type `{content_type}`"
+      );
+    }
+  }
+
+  #[test]
+  fn extension_for_content_type() {
+    assert_eq!(Media::extension_for_content_type("image/png"), Some("png"));
+    assert_eq!(Media::extension_for_content_type("image/jpeg"), Some("jpg"));
+    assert_eq!(
+      Media::extension_for_content_type("text/plain;charset=utf-8"),
+      Some("txt")
+    );
+    assert_eq!(Media::extension_for_content_type("text/plain"), Some("txt"));
+    assert_eq!(
+      Media::extension_for_content_type("text/html; charset=UTF-8"),
+      Some("html")
+    );
+    assert_eq!(Media::extension_for_content_type("image/jxl"), None);
+    assert_eq!(Media::extension_for_content_type("foo/bar"), None);
+  }
+
+  #[test]
+  fn compression_mode_for_content_type() {
+    assert_eq!(
+      Media::compression_mode_for_content_type("text/plain"),
+      BrotliEncoderMode::BROTLI_MODE_TEXT
+    );
+    assert_eq!(
+      Media::compression_mode_for_content_type("application/json; charset=utf-8"),
+      BrotliEncoderMode::BROTLI_MODE_TEXT
+    );
+    assert_eq!(
+      Media::compression_mode_for_content_type("font/woff2"),
+      BrotliEncoderMode::BROTLI_MODE_FONT
+    );
+    assert_eq!(
+      Media::compression_mode_for_content_type("foo/bar"),
+      BrotliEncoderMode::BROTLI_MODE_GENERIC
+    );
+  }
+
   #[test]
   fn h264_in_mp4_is_allowed() {
     assert!(Media::check_mp4_codec(Path::new("examples/h264.mp4")).is_ok(),);
@@ -21,6 +21,22 @@ pub(crate) enum Tag {
 }
 
 impl Tag {
+  const FIELDS: [Tag; 8] = [
+    Self::ContentType,
+    Self::Pointer,
+    Self::Parent,
+    Self::Metadata,
+    Self::Metaprotocol,
+    Self::ContentEncoding,
+    Self::Delegate,
+    Self::Rune,
+  ];
+
+  /// Whether `tag` is set from an inscription field.
+  pub(crate) fn is_field(tag: u8) -> bool {
+    Self::FIELDS.iter().any(|field| *field as u8 == tag)
+  }
+
   fn chunked(self) -> bool {
     matches!(self, Self::Metadata)
   }
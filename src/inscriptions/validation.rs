@@ -0,0 +1,256 @@
+use {super::*, flate2::Crc};
+
+type Validator = fn(&[u8]) -> Result;
+
+/// Structure checks run on inscription content when `--validate-content` is
+/// given, keyed by content type. Content types without a validator are not
+/// checked.
+const VALIDATORS: &[(&str, Validator)] = &[
+  ("image/gif", gif),
+  ("image/jpeg", jpeg),
+  ("image/png", png),
+  ("image/webp", webp),
+];
+
+/// Check that `body` is well-formed content of type `content_type`.
+pub(crate) fn validate(content_type: &str, body: &[u8]) -> Result {
+  let Some((_, validator)) = VALIDATORS
+    .iter()
+    .find(|(validated, _)| *validated == content_type)
+  else {
+    return Ok(());
+  };
+
+  validator(body).map_err(|err| anyhow!("invalid {content_type} content: {err}"))
+}
+
+fn gif(body: &[u8]) -> Result {
+  const LOGICAL_SCREEN_DESCRIPTOR_END: usize = 13;
+
+  ensure!(
+    body.starts_with(b"GIF87a") || body.starts_with(b"GIF89a"),
+    "missing GIF header"
+  );
+
+  ensure!(
+    body.len() > LOGICAL_SCREEN_DESCRIPTOR_END,
+    "truncated logical screen descriptor"
+  );
+
+  ensure!(
+    body.last() == Some(&0x3B),
+    "missing GIF trailer, file may be truncated"
+  );
+
+  Ok(())
+}
+
+fn jpeg(body: &[u8]) -> Result {
+  ensure!(
+    body.starts_with(&[0xFF, 0xD8, 0xFF]),
+    "missing JPEG start of image marker"
+  );
+
+  ensure!(
+    body.ends_with(&[0xFF, 0xD9]),
+    "missing JPEG end of image marker, file may be truncated"
+  );
+
+  Ok(())
+}
+
+fn png(body: &[u8]) -> Result {
+  const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+  let mut rest = body
+    .strip_prefix(SIGNATURE)
+    .ok_or_else(|| anyhow!("missing PNG signature"))?;
+
+  let mut first = true;
+
+  while !rest.is_empty() {
+    ensure!(rest.len() >= 12, "truncated chunk header");
+
+    let length = usize::try_from(u32::from_be_bytes(rest[0..4].try_into().unwrap())).unwrap();
+
+    let kind = String::from_utf8_lossy(&rest[4..8]).into_owned();
+
+    ensure!(
+      rest.len() >= length.saturating_add(12),
+      "truncated {kind} chunk"
+    );
+
+    ensure!(!first || kind == "IHDR", "first chunk is {kind}, not IHDR");
+
+    let mut crc = Crc::new();
+    crc.update(&rest[4..length + 8]);
+
+    ensure!(
+      crc.sum().to_be_bytes() == rest[length + 8..length + 12],
+      "{kind} chunk has invalid CRC"
+    );
+
+    if kind == "IEND" {
+      return Ok(());
+    }
+
+    first = false;
+    rest = &rest[length + 12..];
+  }
+
+  bail!("missing IEND chunk, file may be truncated")
+}
+
+fn webp(body: &[u8]) -> Result {
+  ensure!(
+    body.len() >= 16 && body.starts_with(b"RIFF") && &body[8..12] == b"WEBP",
+    "missing RIFF WEBP header"
+  );
+
+  let size = u32::from_le_bytes(body[4..8].try_into().unwrap());
+
+  ensure!(
+    u64::from(size) + 8 == u64::try_from(body.len()).unwrap(),
+    "RIFF size of {size} bytes does not match actual size of {} bytes, file may be truncated",
+    body.len() - 8,
+  );
+
+  let chunk = &body[12..16];
+
+  ensure!(
+    matches!(chunk, b"VP8 " | b"VP8L" | b"VP8X"),
+    "first chunk is `{}`, not VP8, VP8L, or VP8X",
+    String::from_utf8_lossy(chunk),
+  );
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+
+    let mut chunk = u32::try_from(data.len()).unwrap().to_be_bytes().to_vec();
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&crc.sum().to_be_bytes());
+    chunk
+  }
+
+  fn valid_png() -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend(chunk(b"IHDR", &[0; 13]));
+    png.extend(chunk(b"IDAT", &[1, 2, 3]));
+    png.extend(chunk(b"IEND", &[]));
+    png
+  }
+
+  fn valid_webp() -> Vec<u8> {
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&12u32.to_le_bytes());
+    webp.extend_from_slice(b"WEBPVP8L");
+    webp.extend_from_slice(&[0; 4]);
+    webp
+  }
+
+  #[track_caller]
+  fn case(content_type: &str, body: &[u8], expected: &str) {
+    assert_eq!(
+      validate(content_type, body).unwrap_err().to_string(),
+      format!("invalid {content_type} content: {expected}"),
+    );
+  }
+
+  #[test]
+  fn valid_content_passes() {
+    validate("image/png", &valid_png()).unwrap();
+    validate("image/jpeg", &[0xFF, 0xD8, 0xFF, 0xE0, 0, 0, 0xFF, 0xD9]).unwrap();
+    validate("image/gif", b"GIF89a\x01\x00\x01\x00\x00\x00\x00\x3B").unwrap();
+    validate("image/webp", &valid_webp()).unwrap();
+  }
+
+  #[test]
+  fn content_types_without_validator_pass() {
+    validate("text/plain;charset=utf-8", b"\x89PNG").unwrap();
+  }
+
+  #[test]
+  fn png() {
+    let valid = valid_png();
+
+    case("image/png", b"GIF89a", "missing PNG signature");
+    case(
+      "image/png",
+      &valid[..valid.len() - 12],
+      "missing IEND chunk, file may be truncated",
+    );
+    case(
+      "image/png",
+      &valid[..valid.len() - 3],
+      "truncated chunk header",
+    );
+    case("image/png", &valid[..46], "truncated IDAT chunk");
+
+    let mut corrupt = valid.clone();
+    corrupt[20] ^= 1;
+    case("image/png", &corrupt, "IHDR chunk has invalid CRC");
+
+    let mut no_header = b"\x89PNG\r\n\x1a\n".to_vec();
+    no_header.extend(chunk(b"IEND", &[]));
+    case("image/png", &no_header, "first chunk is IEND, not IHDR");
+  }
+
+  #[test]
+  fn jpeg() {
+    case(
+      "image/jpeg",
+      b"\x89PNG",
+      "missing JPEG start of image marker",
+    );
+    case(
+      "image/jpeg",
+      &[0xFF, 0xD8, 0xFF, 0xE0, 0, 0],
+      "missing JPEG end of image marker, file may be truncated",
+    );
+  }
+
+  #[test]
+  fn gif() {
+    case("image/gif", b"GIF90a", "missing GIF header");
+    case(
+      "image/gif",
+      b"GIF89a\x01\x00",
+      "truncated logical screen descriptor",
+    );
+    case(
+      "image/gif",
+      b"GIF89a\x01\x00\x01\x00\x00\x00\x00\x00",
+      "missing GIF trailer, file may be truncated",
+    );
+  }
+
+  #[test]
+  fn webp() {
+    let valid = valid_webp();
+
+    case("image/webp", b"RIFF", "missing RIFF WEBP header");
+    case(
+      "image/webp",
+      &valid[..valid.len() - 1],
+      "RIFF size of 12 bytes does not match actual size of 11 bytes, file may be truncated",
+    );
+
+    let mut unknown = valid;
+    unknown[12..16].copy_from_slice(b"ABCD");
+    case(
+      "image/webp",
+      &unknown,
+      "first chunk is `ABCD`, not VP8, VP8L, or VP8X",
+    );
+  }
+}
@@ -17,16 +17,17 @@ use {
     blocktime::Blocktime,
     decimal::Decimal,
     deserialize_from_str::DeserializeFromStr,
+    exit_code::{ExitCode, InsufficientFunds, Interrupted},
     index::BitcoinCoreRpcResultExt,
     inscriptions::{
       inscription_id,
       media::{self, ImageRendering, Media},
-      teleburn, ParsedEnvelope,
+      teleburn, Compression, ParsedEnvelope,
     },
     into_usize::IntoUsize,
     representation::Representation,
     settings::Settings,
-    subcommand::{Subcommand, SubcommandResult},
+    subcommand::{warning::Warning, Subcommand, SubcommandResult},
     tally::Tally,
   },
   anyhow::{anyhow, bail, ensure, Context, Error},
@@ -39,7 +40,7 @@ use {
     },
     consensus::{self, Decodable, Encodable},
     hash_types::{BlockHash, TxMerkleNode},
-    hashes::Hash,
+    hashes::{sha256, Hash},
     script, Amount, Block, Network, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn,
     TxOut, Txid, Witness,
   },
@@ -85,7 +86,7 @@ pub use self::{
   chain::Chain,
   fee_rate::FeeRate,
   index::{Index, RuneEntry},
-  inscriptions::{Envelope, Inscription, InscriptionId},
+  inscriptions::{Envelope, Inscription, InscriptionId, Manifest},
   object::Object,
   options::Options,
   wallet::transaction_builder::{Target, TransactionBuilder},
@@ -113,6 +114,7 @@ mod blocktime;
 pub mod chain;
 mod decimal;
 mod deserialize_from_str;
+mod exit_code;
 mod fee_rate;
 pub mod index;
 mod inscriptions;
@@ -131,11 +133,13 @@ pub mod wallet;
 
 type Result<T = (), E = Error> = std::result::Result<T, E>;
 
+const MAX_STANDARD_OP_RETURN_DATA: usize = 80;
 const TARGET_POSTAGE: Amount = Amount::from_sat(10_000);
 
 static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
 static LISTENERS: Mutex<Vec<axum_server::Handle>> = Mutex::new(Vec::new());
 static INDEXER: Mutex<Option<thread::JoinHandle<()>>> = Mutex::new(None);
+static INDEX_COMMIT: Mutex<()> = Mutex::new(());
 
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 fn fund_raw_transaction(
@@ -175,7 +179,7 @@ fn fund_raw_transaction(
             bitcoincore_rpc::jsonrpc::error::RpcError { code: -6, .. }
           ))
         ) {
-          anyhow!("not enough cardinal utxos")
+          anyhow::Error::from(InsufficientFunds("not enough cardinal utxos".into()))
         } else {
           err.into()
         }
@@ -240,10 +244,23 @@ fn gracefully_shutdown_indexer() {
 }
 
 pub fn main() {
-  env_logger::init();
+  let args = Arguments::parse();
+
+  {
+    let mut builder = env_logger::Builder::from_default_env();
+
+    if let Some(log_level) = args.options.log_level {
+      builder.filter_level(log_level);
+    }
+
+    builder.init();
+  }
 
   ctrlc::set_handler(move || {
     if SHUTTING_DOWN.fetch_or(true, atomic::Ordering::Relaxed) {
+      // Wait for an in-progress index commit to finish, so that exiting
+      // immediately doesn't leave the index needing repair
+      let _commit = INDEX_COMMIT.lock();
       process::exit(1);
     }
 
@@ -259,11 +276,22 @@ pub fn main() {
   })
   .expect("Error setting <CTRL-C> handler");
 
-  let args = Arguments::parse();
-
   let minify = args.options.minify;
 
-  match args.run() {
+  let output_file = args.options.output.clone();
+
+  let result = args.run().and_then(|output| {
+    if let Some(output) = output {
+      match &output_file {
+        Some(path) => output.write_json(path, minify)?,
+        None => output.print_json(minify),
+      }
+    }
+
+    Ok(())
+  });
+
+  match result {
     Err(err) => {
       eprintln!("error: {err}");
       err
@@ -279,13 +307,8 @@ pub fn main() {
 
       gracefully_shutdown_indexer();
 
-      process::exit(1);
-    }
-    Ok(output) => {
-      if let Some(output) = output {
-        output.print_json(minify);
-      }
-      gracefully_shutdown_indexer();
+      process::exit(ExitCode::for_error(&err) as i32);
     }
+    Ok(()) => gracefully_shutdown_indexer(),
   }
 }
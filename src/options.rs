@@ -63,7 +63,16 @@ pub struct Options {
   pub(crate) index_transactions: bool,
   #[arg(long, help = "Run in integration test mode.")]
   pub(crate) integration_test: bool,
-  #[arg(long, help = "Minify JSON output.")]
+  #[arg(
+    long,
+    value_name = "LOG_LEVEL",
+    help = "Log messages at <LOG_LEVEL> and above to stderr, one of `off`, `error`, `warn`, `info`, `debug`, or `trace`. Takes precedence over RUST_LOG."
+  )]
+  pub(crate) log_level: Option<log::LevelFilter>,
+  #[arg(
+    long,
+    help = "Minify JSON output. `ord wallet batch` prints one line per inscription."
+  )]
   pub(crate) minify: bool,
   #[arg(
     long,
@@ -72,6 +81,13 @@ pub struct Options {
     help = "Do not index inscriptions."
   )]
   pub(crate) no_index_inscriptions: bool,
+  #[arg(
+    long,
+    help = "Atomically write JSON output to <OUTPUT> instead of stdout."
+  )]
+  pub(crate) output: Option<PathBuf>,
+  #[arg(long, help = "Do not show progress while indexing.")]
+  pub(crate) quiet: bool,
   #[arg(
     long,
     help = "Require basic HTTP authentication with <SERVER_PASSWORD>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
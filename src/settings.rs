@@ -1,4 +1,10 @@
-use {super::*, bitcoincore_rpc::Auth};
+use {
+  super::*,
+  bitcoincore_rpc::{
+    jsonrpc::{self, simple_http::SimpleHttpTransport},
+    Auth,
+  },
+};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
@@ -13,6 +19,7 @@ pub struct Settings {
   config_dir: Option<PathBuf>,
   cookie_file: Option<PathBuf>,
   data_dir: Option<PathBuf>,
+  fee_rate: Option<FeeRate>,
   first_inscription_height: Option<u32>,
   height_limit: Option<u32>,
   hidden: Option<HashSet<InscriptionId>>,
@@ -24,6 +31,7 @@ pub struct Settings {
   index_transactions: bool,
   integration_test: bool,
   no_index_inscriptions: bool,
+  quiet: bool,
   server_password: Option<String>,
   server_url: Option<String>,
   server_username: Option<String>,
@@ -117,6 +125,7 @@ impl Settings {
       config_dir: self.config_dir.or(source.config_dir),
       cookie_file: self.cookie_file.or(source.cookie_file),
       data_dir: self.data_dir.or(source.data_dir),
+      fee_rate: self.fee_rate.or(source.fee_rate),
       first_inscription_height: self
         .first_inscription_height
         .or(source.first_inscription_height),
@@ -138,6 +147,7 @@ impl Settings {
       index_transactions: self.index_transactions || source.index_transactions,
       integration_test: self.integration_test || source.integration_test,
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
+      quiet: self.quiet || source.quiet,
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
       server_username: self.server_username.or(source.server_username),
@@ -161,6 +171,7 @@ impl Settings {
       config_dir: options.config_dir,
       cookie_file: options.cookie_file,
       data_dir: options.data_dir,
+      fee_rate: None,
       first_inscription_height: options.first_inscription_height,
       height_limit: options.height_limit,
       hidden: None,
@@ -172,6 +183,7 @@ impl Settings {
       index_transactions: options.index_transactions,
       integration_test: options.integration_test,
       no_index_inscriptions: options.no_index_inscriptions,
+      quiet: options.quiet,
       server_password: options.server_password,
       server_url: None,
       server_username: options.server_username,
@@ -213,6 +225,14 @@ impl Settings {
         })
     };
 
+    let get_fee_rate = |key| {
+      env
+        .get(key)
+        .map(|fee_rate| fee_rate.parse::<FeeRate>())
+        .transpose()
+        .with_context(|| format!("failed to parse environment variable ORD_{key} as fee rate"))
+    };
+
     let get_u32 = |key| {
       env
         .get(key)
@@ -239,6 +259,7 @@ impl Settings {
       config_dir: get_path("CONFIG_DIR"),
       cookie_file: get_path("COOKIE_FILE"),
       data_dir: get_path("DATA_DIR"),
+      fee_rate: get_fee_rate("FEE_RATE")?,
       first_inscription_height: get_u32("FIRST_INSCRIPTION_HEIGHT")?,
       height_limit: get_u32("HEIGHT_LIMIT")?,
       hidden: inscriptions("HIDDEN")?,
@@ -250,6 +271,7 @@ impl Settings {
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
       integration_test: get_bool("INTEGRATION_TEST"),
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
+      quiet: get_bool("QUIET"),
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
       server_username: get_string("SERVER_USERNAME"),
@@ -268,6 +290,7 @@ impl Settings {
       config_dir: None,
       cookie_file: None,
       data_dir: Some(dir.into()),
+      fee_rate: None,
       first_inscription_height: None,
       height_limit: None,
       hidden: None,
@@ -279,6 +302,7 @@ impl Settings {
       index_transactions: false,
       integration_test: false,
       no_index_inscriptions: false,
+      quiet: false,
       server_password: None,
       server_url: Some(server_url.into()),
       server_username: None,
@@ -334,6 +358,7 @@ impl Settings {
       config_dir: None,
       cookie_file: Some(cookie_file),
       data_dir: Some(data_dir),
+      fee_rate: self.fee_rate,
       first_inscription_height: Some(if self.integration_test {
         0
       } else {
@@ -358,6 +383,7 @@ impl Settings {
       index_transactions: self.index_transactions,
       integration_test: self.integration_test,
       no_index_inscriptions: self.no_index_inscriptions,
+      quiet: self.quiet,
       server_password: self.server_password,
       server_url: self.server_url,
       server_username: self.server_username,
@@ -385,6 +411,16 @@ impl Settings {
   }
 
   pub(crate) fn bitcoin_rpc_client(&self, wallet: Option<String>) -> Result<Client> {
+    self.bitcoin_rpc_client_with_timeout(wallet, None)
+  }
+
+  /// Connect to Bitcoin Core, failing requests which take longer than
+  /// `timeout` instead of the default of 15 seconds.
+  pub(crate) fn bitcoin_rpc_client_with_timeout(
+    &self,
+    wallet: Option<String>,
+    timeout: Option<Duration>,
+  ) -> Result<Client> {
     let rpc_url = self.bitcoin_rpc_url(wallet);
 
     let bitcoin_credentials = self.bitcoin_credentials()?;
@@ -407,8 +443,26 @@ impl Settings {
       );
     }
 
-    let client = Client::new(&rpc_url, bitcoin_credentials)
-      .with_context(|| format!("failed to connect to Bitcoin Core RPC at `{rpc_url}`"))?;
+    let client = match timeout {
+      Some(timeout) => {
+        let (user, pass) = bitcoin_credentials
+          .get_user_pass()
+          .with_context(|| format!("failed to connect to Bitcoin Core RPC at `{rpc_url}`"))?;
+
+        let mut builder = SimpleHttpTransport::builder()
+          .url(&rpc_url)
+          .with_context(|| format!("failed to connect to Bitcoin Core RPC at `{rpc_url}`"))?
+          .timeout(timeout);
+
+        if let Some(user) = user {
+          builder = builder.auth(user, pass);
+        }
+
+        Client::from_jsonrpc(jsonrpc::Client::with_transport(builder.build()))
+      }
+      None => Client::new(&rpc_url, bitcoin_credentials)
+        .with_context(|| format!("failed to connect to Bitcoin Core RPC at `{rpc_url}`"))?,
+    };
 
     let mut checks = 0;
     let rpc_chain = loop {
@@ -424,7 +478,10 @@ impl Settings {
         }
         Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(err)))
           if err.code == -28 => {}
-        Err(err) => bail!("Failed to connect to Bitcoin Core RPC at `{rpc_url}`:  {err}"),
+        Err(err) => {
+          return Err(err)
+            .with_context(|| format!("Failed to connect to Bitcoin Core RPC at `{rpc_url}`"))
+        }
       }
 
       ensure! {
@@ -486,6 +543,10 @@ impl Settings {
     self.data_dir.as_ref().unwrap().into()
   }
 
+  pub(crate) fn fee_rate(&self) -> Option<FeeRate> {
+    self.fee_rate
+  }
+
   pub(crate) fn first_inscription_height(&self) -> u32 {
     self.first_inscription_height.unwrap()
   }
@@ -550,6 +611,10 @@ impl Settings {
     }
   }
 
+  pub(crate) fn quiet(&self) -> bool {
+    self.quiet
+  }
+
   pub(crate) fn server_url(&self) -> Option<&str> {
     self.server_url.as_deref()
   }
@@ -987,6 +1052,7 @@ mod tests {
       ("CONFIG_DIR", "config dir"),
       ("COOKIE_FILE", "cookie file"),
       ("DATA_DIR", "/data/dir"),
+      ("FEE_RATE", "2.5"),
       ("FIRST_INSCRIPTION_HEIGHT", "2"),
       ("HEIGHT_LIMIT", "3"),
       ("HIDDEN", "6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0 703e5f7c49d82aab99e605af306b9a30e991e57d42f982908a962a81ac439832i0"),
@@ -998,6 +1064,7 @@ mod tests {
       ("INDEX_TRANSACTIONS", "1"),
       ("INTEGRATION_TEST", "1"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
+      ("QUIET", "1"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
       ("SERVER_USERNAME", "server username"),
@@ -1019,6 +1086,7 @@ mod tests {
         config_dir: Some("config dir".into()),
         cookie_file: Some("cookie file".into()),
         data_dir: Some("/data/dir".into()),
+        fee_rate: Some(FeeRate::try_from(2.5).unwrap()),
         first_inscription_height: Some(2),
         height_limit: Some(3),
         hidden: Some(
@@ -1041,6 +1109,7 @@ mod tests {
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
+        quiet: true,
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
@@ -1074,6 +1143,7 @@ mod tests {
           "--index=index",
           "--integration-test",
           "--no-index-inscriptions",
+          "--quiet",
           "--server-password=server password",
           "--server-username=server username",
         ])
@@ -1090,6 +1160,7 @@ mod tests {
         config_dir: Some("config dir".into()),
         cookie_file: Some("cookie file".into()),
         data_dir: Some("/data/dir".into()),
+        fee_rate: None,
         first_inscription_height: Some(2),
         height_limit: Some(3),
         hidden: None,
@@ -1101,6 +1172,7 @@ mod tests {
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
+        quiet: true,
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
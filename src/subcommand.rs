@@ -1,6 +1,7 @@
 use super::*;
 
 pub mod balances;
+pub mod cold_reveal;
 pub mod decode;
 pub mod env;
 pub mod epochs;
@@ -8,19 +9,26 @@ pub mod find;
 pub mod index;
 pub mod list;
 pub mod parse;
+pub mod predict_id;
+pub mod recovery_address;
 pub mod runes;
+pub mod selftest;
 pub(crate) mod server;
 mod settings;
 pub mod subsidy;
 pub mod supply;
 pub mod teleburn;
 pub mod traits;
+pub mod verify;
 pub mod wallet;
+pub(crate) mod warning;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
   #[command(about = "List all rune balances")]
   Balances,
+  #[command(subcommand, about = "Sign reveal transactions on an offline machine")]
+  ColdReveal(cold_reveal::ColdRevealSubcommand),
   #[command(about = "Decode a transaction")]
   Decode(decode::Decode),
   #[command(about = "Start a regtest ord and bitcoind instance")]
@@ -35,8 +43,14 @@ pub(crate) enum Subcommand {
   List(list::List),
   #[command(about = "Parse a satoshi from ordinal notation")]
   Parse(parse::Parse),
+  #[command(about = "Predict inscription ids from a reveal transaction")]
+  PredictId(predict_id::PredictId),
+  #[command(about = "Derive commit address from a recovery key and inscription file")]
+  RecoveryAddress(recovery_address::RecoveryAddress),
   #[command(about = "List all runes")]
   Runes,
+  #[command(subcommand, about = "Self-test commands")]
+  Selftest(selftest::SelftestSubcommand),
   #[command(about = "Run the explorer server")]
   Server(server::Server),
   #[command(about = "Display settings")]
@@ -49,6 +63,8 @@ pub(crate) enum Subcommand {
   Teleburn(teleburn::Teleburn),
   #[command(about = "Display satoshi traits")]
   Traits(traits::Traits),
+  #[command(about = "Verify that a file matches an inscription's content")]
+  Verify(verify::Verify),
   #[command(about = "Wallet commands")]
   Wallet(wallet::WalletCommand),
 }
@@ -57,6 +73,7 @@ impl Subcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
       Self::Balances => balances::run(settings),
+      Self::ColdReveal(cold_reveal) => cold_reveal.run(),
       Self::Decode(decode) => decode.run(settings),
       Self::Env(env) => env.run(),
       Self::Epochs => epochs::run(),
@@ -64,7 +81,10 @@ impl Subcommand {
       Self::Index(index) => index.run(settings),
       Self::List(list) => list.run(settings),
       Self::Parse(parse) => parse.run(),
+      Self::PredictId(predict_id) => predict_id.run(),
+      Self::RecoveryAddress(recovery_address) => recovery_address.run(settings),
       Self::Runes => runes::run(settings),
+      Self::Selftest(selftest) => selftest.run(),
       Self::Server(server) => {
         let index = Arc::new(Index::open(&settings)?);
         let handle = axum_server::Handle::new();
@@ -76,26 +96,53 @@ impl Subcommand {
       Self::Supply => supply::run(),
       Self::Teleburn(teleburn) => teleburn.run(),
       Self::Traits(traits) => traits.run(),
+      Self::Verify(verify) => verify.run(settings),
       Self::Wallet(wallet) => wallet.run(settings),
     }
   }
 }
 
 pub trait Output: Send {
-  fn print_json(&self, minify: bool);
+  /// Write JSON to `writer`, followed by a newline.
+  fn write_json_to(&self, writer: &mut dyn io::Write, minify: bool) -> Result;
+
+  fn print_json(&self, minify: bool) {
+    self.write_json_to(&mut io::stdout().lock(), minify).ok();
+  }
+
+  fn write_json(&self, path: &Path, minify: bool) -> Result {
+    let dir = path
+      .parent()
+      .filter(|parent| !parent.as_os_str().is_empty())
+      .unwrap_or(Path::new("."));
+
+    let mut file = tempfile::NamedTempFile::new_in(dir)
+      .with_context(|| format!("I/O error creating temporary file in `{}`", dir.display()))?;
+
+    self.write_json_to(&mut file, minify)?;
+
+    file
+      .persist(path)
+      .with_context(|| format!("I/O error writing `{}`", path.display()))?;
+
+    Ok(())
+  }
 }
 
 impl<T> Output for T
 where
   T: Serialize + Send,
 {
-  fn print_json(&self, minify: bool) {
+  fn write_json_to(&self, writer: &mut dyn io::Write, minify: bool) -> Result {
     if minify {
-      serde_json::to_writer(io::stdout(), self).ok();
+      serde_json::to_writer(&mut *writer, self)?;
     } else {
-      serde_json::to_writer_pretty(io::stdout(), self).ok();
+      serde_json::to_writer_pretty(&mut *writer, self)?;
     }
-    println!();
+
+    io::Write::write_all(writer, b"\n")?;
+
+    Ok(())
   }
 }
 
@@ -0,0 +1,21 @@
+use super::*;
+
+pub mod key;
+pub mod sign;
+
+#[derive(Debug, Parser)]
+pub(crate) enum ColdRevealSubcommand {
+  #[command(about = "Generate a reveal key for --cold-reveal-key")]
+  Key(key::Key),
+  #[command(about = "Sign a reveal transaction written by --cold-reveal-file")]
+  Sign(sign::Sign),
+}
+
+impl ColdRevealSubcommand {
+  pub(crate) fn run(self) -> SubcommandResult {
+    match self {
+      Self::Key(key) => key.run(),
+      Self::Sign(sign) => sign.run(),
+    }
+  }
+}
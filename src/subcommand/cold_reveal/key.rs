@@ -0,0 +1,43 @@
+use {
+  super::*,
+  bitcoin::{
+    key::UntweakedKeyPair,
+    secp256k1::{
+      rand::{rngs::StdRng, SeedableRng},
+      Secp256k1, XOnlyPublicKey,
+    },
+  },
+  std::io::Write,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Key {
+  #[arg(help = "Write hex-encoded secret reveal key to <KEY_FILE>, which must not exist.")]
+  key_file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub public_key: String,
+}
+
+impl Key {
+  pub(crate) fn run(self) -> SubcommandResult {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut StdRng::from_entropy());
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&self.key_file)
+      .and_then(|mut file| writeln!(file, "{}", hex::encode(key_pair.secret_bytes())))
+      .with_context(|| format!("failed to write key to `{}`", self.key_file.display()))?;
+
+    Ok(Some(Box::new(Output {
+      public_key: public_key.to_string(),
+    })))
+  }
+}
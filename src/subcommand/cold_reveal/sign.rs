@@ -0,0 +1,44 @@
+use {super::*, crate::wallet::batch::ColdReveal, bitcoin::secp256k1::SecretKey};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Sign {
+  #[arg(long, help = "Sign with hex-encoded secret key in <KEY_FILE>.")]
+  key_file: PathBuf,
+  #[arg(help = "Sign reveal transaction in <COLD_REVEAL_FILE>.")]
+  cold_reveal_file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub reveal: Txid,
+  pub reveal_tx: String,
+}
+
+impl Sign {
+  pub(crate) fn run(self) -> SubcommandResult {
+    let secret_key = SecretKey::from_str(
+      fs::read_to_string(&self.key_file)
+        .with_context(|| format!("I/O error reading `{}`", self.key_file.display()))?
+        .trim(),
+    )
+    .context("invalid key")?;
+
+    let cold_reveal = serde_json::from_str::<ColdReveal>(
+      &fs::read_to_string(&self.cold_reveal_file)
+        .with_context(|| format!("I/O error reading `{}`", self.cold_reveal_file.display()))?,
+    )
+    .with_context(|| {
+      format!(
+        "invalid cold reveal file `{}`",
+        self.cold_reveal_file.display()
+      )
+    })?;
+
+    let reveal_tx = cold_reveal.sign(&secret_key)?;
+
+    Ok(Some(Box::new(Output {
+      reveal: reveal_tx.txid(),
+      reveal_tx: hex::encode(consensus::encode::serialize(&reveal_tx)),
+    })))
+  }
+}
@@ -17,7 +17,9 @@ pub struct RawOutput {
 pub struct CompactInscription {
   pub body: Option<String>,
   pub content_encoding: Option<String>,
+  pub content_length: Option<usize>,
   pub content_type: Option<String>,
+  pub delegate: Option<InscriptionId>,
   #[serde(default, skip_serializing_if = "std::ops::Not::not")]
   pub duplicate_field: bool,
   #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -40,7 +42,9 @@ impl TryFrom<Inscription> for CompactInscription {
         .content_encoding()
         .map(|header_value| header_value.to_str().map(str::to_string))
         .transpose()?,
+      content_length: inscription.content_length(),
       content_type: inscription.content_type().map(str::to_string),
+      delegate: inscription.delegate(),
       metaprotocol: inscription.metaprotocol().map(str::to_string),
       parents: inscription.parents(),
       pointer: inscription.pointer(),
@@ -57,12 +61,22 @@ impl TryFrom<Inscription> for CompactInscription {
 pub(crate) struct Decode {
   #[arg(
     long,
-    conflicts_with = "file",
+    conflicts_with_all = ["file", "hex"],
     help = "Fetch transaction with <TXID> from Bitcoin Core."
   )]
   txid: Option<Txid>,
-  #[arg(long, conflicts_with = "txid", help = "Load transaction from <FILE>.")]
+  #[arg(
+    long,
+    conflicts_with_all = ["hex", "txid"],
+    help = "Load transaction from <FILE>."
+  )]
   file: Option<PathBuf>,
+  #[arg(
+    long,
+    conflicts_with_all = ["file", "txid"],
+    help = "Decode hex-encoded transaction <HEX>."
+  )]
+  hex: Option<String>,
   #[arg(
     long,
     help = "Serialize inscriptions in a compact, human-readable format."
@@ -78,12 +92,22 @@ impl Decode {
         .get_raw_transaction(&txid, None)?
     } else if let Some(file) = self.file {
       Transaction::consensus_decode(&mut fs::File::open(file)?)?
+    } else if let Some(hex) = self.hex {
+      consensus::deserialize(&hex::decode(hex.trim()).context("invalid transaction hex")?)?
     } else {
       Transaction::consensus_decode(&mut io::stdin())?
     };
 
     let inscriptions = ParsedEnvelope::from_transaction(&transaction);
 
+    if inscriptions.is_empty() {
+      eprintln!(
+        "No inscriptions found in {} inputs of transaction {}",
+        transaction.input.len(),
+        transaction.txid()
+      );
+    }
+
     let runestone = Runestone::from_transaction(&transaction);
 
     if self.compact {
@@ -0,0 +1,34 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct PredictId {
+  #[arg(help = "Predict inscription ids created by hex-encoded reveal transaction <REVEAL>.")]
+  reveal: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub reveal: Txid,
+  pub inscriptions: Vec<InscriptionId>,
+}
+
+impl PredictId {
+  pub(crate) fn run(self) -> SubcommandResult {
+    let reveal: Transaction =
+      consensus::deserialize(&hex::decode(self.reveal.trim()).context("invalid transaction hex")?)
+        .context("invalid reveal transaction")?;
+
+    let inscriptions = InscriptionId::from_reveal(&reveal);
+
+    ensure!(
+      !inscriptions.is_empty(),
+      "reveal transaction {} contains no inscriptions",
+      reveal.txid(),
+    );
+
+    Ok(Some(Box::new(Output {
+      reveal: reveal.txid(),
+      inscriptions,
+    })))
+  }
+}
@@ -0,0 +1,132 @@
+use {
+  super::{wallet::inscribe::Inscribe, *},
+  crate::wallet::{batch::Plan, recovery_key},
+  bitcoin::{
+    blockdata::opcodes,
+    key::{TweakedPublicKey, UntweakedKeyPair},
+    secp256k1::{Secp256k1, XOnlyPublicKey},
+  },
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct RecoveryAddress {
+  #[arg(
+    long,
+    help = "Include CBOR in file at <METADATA> as inscription metadata",
+    conflicts_with = "json_metadata"
+  )]
+  cbor_metadata: Option<PathBuf>,
+  #[arg(long, help = "Inscription content was compressed with --compress.")]
+  compress: bool,
+  #[arg(
+    long,
+    conflicts_with = "compress",
+    help = "Inscription content was compressed with --compress-auto."
+  )]
+  compress_auto: bool,
+  #[arg(
+    long,
+    help = "Inscription content type was given with --content-type <CONTENT_TYPE>."
+  )]
+  content_type: Option<String>,
+  #[arg(long, help = "Inscription was delegated to <DELEGATE>.")]
+  delegate: Option<InscriptionId>,
+  #[arg(long, help = "Reconstruct reveal script from inscription <FILE>.")]
+  file: PathBuf,
+  #[arg(
+    long,
+    help = "Use commit output internal key <INTERNAL_KEY>, printed as `commit_internal_key` by `ord wallet inscribe`."
+  )]
+  internal_key: XOnlyPublicKey,
+  #[arg(
+    long,
+    help = "Include JSON in file at <METADATA> converted to CBOR as inscription metadata",
+    conflicts_with = "cbor_metadata"
+  )]
+  json_metadata: Option<PathBuf>,
+  #[arg(long, help = "Inscription metaprotocol was <METAPROTOCOL>.")]
+  metaprotocol: Option<String>,
+  #[arg(long, help = "Inscription was a child of <PARENT>.")]
+  parent: Option<InscriptionId>,
+  #[arg(long, help = "Inscription pointer was <POINTER>.")]
+  pointer: Option<u64>,
+  #[arg(
+    long,
+    help = "Read recovery key in WIF format from <RECOVERY_KEY_FILE> instead of stdin."
+  )]
+  recovery_key_file: Option<PathBuf>,
+  #[arg(
+    long,
+    env = "ORD_RECOVERY_KEY_PASSPHRASE",
+    hide_env_values = true,
+    help = "Decrypt encrypted recovery key with <RECOVERY_KEY_PASSPHRASE>. Prefer setting the environment variable, which does not appear in shell history or process lists."
+  )]
+  recovery_key_passphrase: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub commit_address: Address<NetworkUnchecked>,
+}
+
+impl RecoveryAddress {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let chain = settings.chain();
+
+    let network = chain.network();
+
+    let private_key = recovery_key::load(
+      self.recovery_key_file,
+      self.recovery_key_passphrase.as_deref(),
+    )?;
+
+    ensure!(
+      (private_key.network == Network::Bitcoin) == (network == Network::Bitcoin),
+      "recovery key is for {} but chain is {network}",
+      private_key.network,
+    );
+
+    let inscription = Inscription::from_file(
+      chain,
+      Compression::from_flags(self.compress, self.compress_auto),
+      self.content_type,
+      self.delegate,
+      Inscribe::parse_metadata(self.cbor_metadata, self.json_metadata)?,
+      self.metaprotocol,
+      self.parent.into_iter().collect(),
+      &self.file,
+      self.pointer,
+      None,
+      false,
+    )?;
+
+    let reveal_script = Inscription::append_batch_reveal_script(
+      &[inscription],
+      ScriptBuf::builder()
+        .push_slice(self.internal_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let secp256k1 = Secp256k1::new();
+
+    let (_taproot_spend_info, _control_block, commit_address) =
+      Plan::commit_taproot(&secp256k1, self.internal_key, &reveal_script, network);
+
+    let (recovery_public_key, _parity) =
+      UntweakedKeyPair::from_secret_key(&secp256k1, &private_key.inner).x_only_public_key();
+
+    let recovery_address = Address::p2tr_tweaked(
+      TweakedPublicKey::dangerous_assume_tweaked(recovery_public_key),
+      network,
+    );
+
+    ensure!(
+      recovery_address == commit_address,
+      "recovery key controls {recovery_address} but inscription file and internal key derive commit address {commit_address}",
+    );
+
+    Ok(Some(Box::new(Output {
+      commit_address: uncheck(&commit_address),
+    })))
+  }
+}
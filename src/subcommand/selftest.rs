@@ -0,0 +1,17 @@
+use super::*;
+
+pub mod taproot;
+
+#[derive(Debug, Parser)]
+pub(crate) enum SelftestSubcommand {
+  #[command(about = "Check inscription taproot construction against BIP341 test vectors")]
+  Taproot,
+}
+
+impl SelftestSubcommand {
+  pub(crate) fn run(self) -> SubcommandResult {
+    match self {
+      Self::Taproot => taproot::run(),
+    }
+  }
+}
@@ -0,0 +1,103 @@
+use {
+  super::*,
+  crate::wallet::batch::Plan,
+  bitcoin::{
+    secp256k1::{Secp256k1, XOnlyPublicKey},
+    taproot::ControlBlock,
+  },
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub vectors: Vec<Vector>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Vector {
+  pub address: String,
+  pub passed: bool,
+}
+
+struct TestVector {
+  internal_key: &'static str,
+  script: &'static str,
+  output_key: &'static str,
+  control_block: &'static str,
+  address: &'static str,
+}
+
+// Single leaf script path vectors from BIP341 wallet-test-vectors.json
+const TEST_VECTORS: &[TestVector] = &[
+  TestVector {
+    internal_key: "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+    script: "20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac",
+    output_key: "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+    control_block: "c1187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+    address: "bc1pz37fc4cn9ah8anwm4xqqhvxygjf9rjf2resrw8h8w4tmvcs0863sa2e586",
+  },
+  TestVector {
+    internal_key: "93478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+    script: "20b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac",
+    output_key: "e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e",
+    control_block: "c093478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+    address: "bc1punvppl2stp38f7kwv2u2spltjuvuaayuqsthe34hd2dyy5w4g58qqfuag5",
+  },
+];
+
+impl TestVector {
+  fn check(&self) -> Result<bool> {
+    let secp256k1 = Secp256k1::verification_only();
+
+    let (taproot_spend_info, control_block, address) = Plan::commit_taproot(
+      &secp256k1,
+      self.internal_key.parse::<XOnlyPublicKey>()?,
+      &ScriptBuf::from_hex(self.script)?,
+      Network::Bitcoin,
+    );
+
+    Ok(
+      taproot_spend_info.output_key().to_inner() == self.output_key.parse::<XOnlyPublicKey>()?
+        && control_block == ControlBlock::decode(&hex::decode(self.control_block)?)?
+        && address.to_string() == self.address,
+    )
+  }
+}
+
+pub(crate) fn run() -> SubcommandResult {
+  let mut vectors = Vec::new();
+
+  for vector in TEST_VECTORS {
+    vectors.push(Vector {
+      address: vector.address.into(),
+      passed: vector.check()?,
+    });
+  }
+
+  let failed = vectors
+    .iter()
+    .filter(|vector| !vector.passed)
+    .map(|vector| vector.address.as_str())
+    .collect::<Vec<&str>>();
+
+  ensure!(
+    failed.is_empty(),
+    "taproot construction does not match BIP341 test vectors: {}",
+    failed.join(", ")
+  );
+
+  Ok(Some(Box::new(Output { vectors })))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bip341_test_vectors() {
+    assert_eq!(TEST_VECTORS.len(), 2);
+
+    for vector in TEST_VECTORS {
+      assert!(vector.check().unwrap(), "{}", vector.address);
+    }
+  }
+}
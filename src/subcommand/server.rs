@@ -22,7 +22,6 @@ use {
     Router,
   },
   axum_server::Handle,
-  brotli::Decompressor,
   rust_embed::RustEmbed,
   rustls_acme::{
     acme::{LETS_ENCRYPT_PRODUCTION_DIRECTORY, LETS_ENCRYPT_STAGING_DIRECTORY},
@@ -99,7 +98,7 @@ pub struct Server {
   pub(crate) csp_origin: Option<String>,
   #[arg(
     long,
-    help = "Decompress encoded content. Currently only supports brotli. Be careful using this on production instances. A decompressed inscription may be arbitrarily large, making decompression a DoS vector."
+    help = "Decompress encoded content. Currently only supports brotli and gzip. Be careful using this on production instances. A decompressed inscription may be arbitrarily large, making decompression a DoS vector."
   )]
   pub(crate) decompress: bool,
   #[arg(long, help = "Disable JSON API.")]
@@ -152,8 +151,11 @@ impl Server {
         }
 
         if !self.no_sync {
-          if let Err(error) = index_clone.update() {
-            log::warn!("Updating index: {error}");
+          match index_clone.update() {
+            Ok(()) => {}
+            // the index stopped cleanly for shutdown and resumes on restart
+            Err(error) if error.is::<Interrupted>() => {}
+            Err(error) => log::warn!("Updating index: {error}"),
           }
         }
 
@@ -1390,16 +1392,14 @@ impl Server {
     if let Some(content_encoding) = inscription.content_encoding() {
       if accept_encoding.is_acceptable(&content_encoding) {
         headers.insert(header::CONTENT_ENCODING, content_encoding);
-      } else if server_config.decompress && content_encoding == "br" {
+      } else if server_config.decompress && (content_encoding == "br" || content_encoding == "gzip")
+      {
         let Some(body) = inscription.into_body() else {
           return Ok(None);
         };
 
-        let mut decompressed = Vec::new();
-
-        Decompressor::new(body.as_slice(), 4096)
-          .read_to_end(&mut decompressed)
-          .map_err(|err| ServerError::Internal(err.into()))?;
+        let decompressed =
+          Compression::decode(content_encoding.as_bytes(), &body).map_err(ServerError::Internal)?;
 
         return Ok(Some((headers, decompressed)));
       } else {
@@ -1544,6 +1544,7 @@ impl Server {
             .map(|address| address.to_string()),
           charms: Charm::charms(info.charms),
           children: info.children,
+          content_hash: info.inscription.content_hash(),
           content_length: info.inscription.content_length(),
           content_type: info.inscription.content_type().map(|s| s.to_string()),
           effective_content_type: effective_mime_type,
@@ -0,0 +1,82 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Verify {
+  #[arg(help = "Verify content of <INSCRIPTION>.")]
+  inscription: InscriptionId,
+  #[arg(help = "Compare inscription content with <FILE>.")]
+  file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub inscription: InscriptionId,
+  pub verified: bool,
+  pub content_type: Option<String>,
+  pub expected_content_type: Option<String>,
+  pub content_length: Option<usize>,
+  pub expected_content_length: Option<usize>,
+  pub length_difference: Option<i64>,
+}
+
+impl Verify {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    index.update()?;
+
+    let Some(inscription) = index.get_inscription_by_id(self.inscription)? else {
+      bail!("inscription {} not found", self.inscription);
+    };
+
+    let expected = Inscription::from_file(
+      settings.chain(),
+      Compression::None,
+      None,
+      None,
+      None,
+      None,
+      Vec::new(),
+      &self.file,
+      None,
+      None,
+      false,
+    )?;
+
+    // compare decoded content, since re-encoding the local file depends on
+    // the encoder version and settings used when inscribing
+    let content = match (&inscription.content_encoding, inscription.body()) {
+      (Some(encoding), Some(body)) => Some(
+        Compression::decode(encoding, body)
+          .with_context(|| format!("failed to decode content of {}", self.inscription))?,
+      ),
+      (None, Some(body)) => Some(body.to_vec()),
+      (_, None) => None,
+    };
+
+    let verified = inscription.content_type() == expected.content_type()
+      && content.as_deref() == expected.body();
+
+    let content_length = content.as_ref().map(Vec::len);
+    let expected_content_length = expected.content_length();
+
+    let length_difference = if verified {
+      None
+    } else {
+      Some(
+        i64::try_from(expected_content_length.unwrap_or_default())?
+          - i64::try_from(content_length.unwrap_or_default())?,
+      )
+    };
+
+    Ok(Some(Box::new(Output {
+      inscription: self.inscription,
+      verified,
+      content_type: inscription.content_type().map(str::to_string),
+      expected_content_type: expected.content_type().map(str::to_string),
+      content_length,
+      expected_content_length,
+      length_difference,
+    })))
+  }
+}
@@ -1,29 +1,41 @@
 use {
   super::*,
-  crate::wallet::{batch, Wallet},
+  crate::wallet::{batch, recovery_key, Wallet},
   bitcoincore_rpc::bitcoincore_rpc_json::ListDescriptorsResult,
   shared_args::SharedArgs,
 };
 
 pub mod balance;
 mod batch_command;
+pub mod broadcast_bundle;
+pub mod bump_fee;
 pub mod cardinals;
 pub mod create;
 pub mod dump;
+pub mod export;
 pub mod inscribe;
 pub mod inscriptions;
 pub mod mint;
 pub mod outputs;
 pub mod receive;
+pub mod recover_commit;
+pub mod recovery_keys;
 pub mod restore;
+pub mod resume_reveal;
 pub mod sats;
 pub mod send;
-mod shared_args;
+pub(crate) mod shared_args;
+pub mod trace_funding;
 pub mod transactions;
 
 #[derive(Debug, Parser)]
 pub(crate) struct WalletCommand {
-  #[arg(long, default_value = "ord", help = "Use wallet named <WALLET>.")]
+  #[arg(
+    long,
+    visible_alias = "wallet",
+    default_value = "ord",
+    help = "Use Bitcoin Core wallet named <NAME> to fund and sign transactions. The wallet is loaded if it exists but is not loaded."
+  )]
   pub(crate) name: String,
   #[arg(long, alias = "nosync", help = "Do not update index.")]
   pub(crate) no_sync: bool,
@@ -43,10 +55,16 @@ pub(crate) enum Subcommand {
   Balance,
   #[command(about = "Create inscriptions and runes")]
   Batch(batch_command::Batch),
+  #[command(about = "Broadcast commit and reveal transactions prepared with --prepare-only")]
+  BroadcastBundle(broadcast_bundle::BroadcastBundle),
+  #[command(about = "Bump fee of unconfirmed commit transaction")]
+  BumpFee(bump_fee::BumpFee),
   #[command(about = "Create new wallet")]
   Create(create::Create),
   #[command(about = "Dump wallet descriptors")]
   Dump,
+  #[command(about = "Export wallet inscription content to a directory")]
+  Export(export::Export),
   #[command(about = "Create inscription")]
   Inscribe(inscribe::Inscribe),
   #[command(about = "List wallet inscriptions")]
@@ -55,18 +73,47 @@ pub(crate) enum Subcommand {
   Mint(mint::Mint),
   #[command(about = "Generate receive address")]
   Receive(receive::Receive),
+  #[command(about = "Sweep abandoned commit output back to wallet with recovery key")]
+  RecoverCommit(recover_commit::RecoverCommit),
+  #[command(
+    about = "List imported commit recovery keys and whether their commit addresses hold funds"
+  )]
+  RecoveryKeys(recovery_keys::RecoveryKeys),
   #[command(about = "Restore wallet")]
   Restore(restore::Restore),
+  #[command(about = "Broadcast reveal transaction saved after broadcasting it failed")]
+  ResumeReveal(resume_reveal::ResumeReveal),
   #[command(about = "List wallet satoshis")]
   Sats(sats::Sats),
   #[command(about = "Send sat or inscription")]
   Send(send::Send),
+  #[command(about = "Show sat ranges of an inscription's commit and reveal transaction outputs")]
+  TraceFunding(trace_funding::TraceFunding),
   #[command(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
   #[command(about = "List all unspent outputs in wallet")]
   Outputs,
   #[command(about = "List unspent cardinal outputs in wallet")]
-  Cardinals,
+  Cardinals(cardinals::Cardinals),
+}
+
+impl Subcommand {
+  fn no_index_update(&self) -> bool {
+    match self {
+      Self::Batch(batch) => batch.shared.no_index_update,
+      Self::Inscribe(inscribe) => inscribe.shared.no_index_update,
+      _ => false,
+    }
+  }
+
+  fn timeout(&self) -> Option<Duration> {
+    match self {
+      Self::Batch(batch) => batch.shared.timeout,
+      Self::Inscribe(inscribe) => inscribe.shared.timeout,
+      _ => None,
+    }
+    .map(Duration::from_secs)
+  }
 }
 
 impl WalletCommand {
@@ -77,9 +124,14 @@ impl WalletCommand {
       _ => {}
     };
 
+    let start = Instant::now();
+
+    let timeout = self.subcommand.timeout();
+
     let wallet = Wallet::build(
       self.name.clone(),
       self.no_sync,
+      self.subcommand.no_index_update(),
       settings.clone(),
       self
         .server_url
@@ -89,22 +141,49 @@ impl WalletCommand {
         .unwrap_or("http://127.0.0.1:80")
         .parse::<Url>()
         .context("invalid server URL")?,
-    )?;
+      timeout,
+    )
+    .map_err(|err| {
+      if matches!(timeout, Some(timeout) if start.elapsed() >= timeout) {
+        err.context("timed out loading wallet, nothing was broadcast")
+      } else {
+        err
+      }
+    })?;
 
     match self.subcommand {
       Subcommand::Balance => balance::run(wallet),
       Subcommand::Batch(batch) => batch.run(wallet),
+      Subcommand::BroadcastBundle(broadcast_bundle) => broadcast_bundle.run(wallet),
+      Subcommand::BumpFee(bump_fee) => bump_fee.run(wallet),
       Subcommand::Dump => dump::run(wallet),
+      Subcommand::Export(export) => export.run(wallet),
       Subcommand::Inscribe(inscribe) => inscribe.run(wallet),
       Subcommand::Inscriptions => inscriptions::run(wallet),
       Subcommand::Mint(mint) => mint.run(wallet),
       Subcommand::Receive(receive) => receive.run(wallet),
+      Subcommand::RecoverCommit(recover_commit) => recover_commit.run(wallet),
+      Subcommand::RecoveryKeys(recovery_keys) => recovery_keys.run(wallet),
+      Subcommand::ResumeReveal(resume_reveal) => resume_reveal.run(wallet),
       Subcommand::Sats(sats) => sats.run(wallet),
       Subcommand::Send(send) => send.run(wallet),
+      Subcommand::TraceFunding(trace_funding) => trace_funding.run(wallet),
       Subcommand::Transactions(transactions) => transactions.run(wallet),
       Subcommand::Outputs => outputs::run(wallet),
-      Subcommand::Cardinals => cardinals::run(wallet),
+      Subcommand::Cardinals(cardinals) => cardinals.run(wallet),
       Subcommand::Create(_) | Subcommand::Restore(_) => unreachable!(),
     }
   }
 }
+
+/// Look up a transaction, returning `None` if Bitcoin Core does not know
+/// about it. Bitcoin Core returns -5 for transactions that are not in the
+/// wallet, mempool, or chain.
+fn unknown_transaction_as_none<T>(result: Result<T, bitcoincore_rpc::Error>) -> Result<Option<T>> {
+  match result {
+    Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+      bitcoincore_rpc::jsonrpc::error::RpcError { code: -5, .. },
+    ))) => Ok(None),
+    result => result.into_option(),
+  }
+}
@@ -14,11 +14,7 @@ pub struct Output {
 pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
   let unspent_outputs = wallet.utxos();
 
-  let inscription_outputs = wallet
-    .inscriptions()
-    .keys()
-    .map(|satpoint| satpoint.outpoint)
-    .collect::<BTreeSet<OutPoint>>();
+  let inscription_outputs = wallet.inscribed_utxos();
 
   let mut cardinal = 0;
   let mut ordinal = 0;
@@ -47,7 +43,7 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
     }
 
     if is_ordinal && is_runic {
-      eprintln!("warning: output {output} contains both inscriptions and runes");
+      Warning::InscriptionsAndRunes { output: *output }.emit();
     }
   }
 
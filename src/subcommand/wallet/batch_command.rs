@@ -3,7 +3,7 @@ use super::*;
 #[derive(Debug, Parser)]
 pub(crate) struct Batch {
   #[command(flatten)]
-  shared: SharedArgs,
+  pub(super) shared: SharedArgs,
   #[arg(
     long,
     help = "Inscribe multiple inscriptions and rune defined in YAML <BATCH_FILE>."
@@ -11,8 +11,33 @@ pub(crate) struct Batch {
   pub(crate) batch: PathBuf,
 }
 
+/// Output of `wallet batch`, printed with `--minify` as one line per
+/// inscription, each line a batch output holding only that inscription.
+struct BatchOutput(batch::Output);
+
+impl Output for BatchOutput {
+  fn write_json_to(&self, writer: &mut dyn io::Write, minify: bool) -> Result {
+    if !minify || self.0.inscriptions.is_empty() {
+      return self.0.write_json_to(writer, minify);
+    }
+
+    let mut output = self.0.clone();
+
+    for inscription in &self.0.inscriptions {
+      output.inscriptions = vec![inscription.clone()];
+      output.inscriptions_by_output =
+        [(inscription.location.outpoint.vout, vec![inscription.id])].into();
+      output.write_json_to(writer, minify)?;
+    }
+
+    Ok(())
+  }
+}
+
 impl Batch {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let deadline = wallet.deadline();
+
     let utxos = wallet.utxos();
 
     let batchfile = batch::File::load(&self.batch)?;
@@ -23,9 +48,15 @@ impl Batch {
       &wallet,
       utxos,
       parent_info.as_ref().map(|info| info.tx_out.value),
-      self.shared.compress,
+      self.shared.compression(),
+      self.shared.validate_content,
+      self.shared.idempotency_key.as_deref(),
     )?;
 
+    self.shared.check_empty(&inscriptions)?;
+
+    self.shared.check_content_types(&inscriptions)?;
+
     let mut locked_utxos = wallet.locked_utxos().clone();
 
     locked_utxos.extend(
@@ -38,32 +69,84 @@ impl Batch {
       Self::check_etching(&wallet, &etching)?;
     }
 
-    batch::Plan {
-      commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
+    let fee_rate = self.shared.fee_rate(&wallet)?;
+
+    let output = batch::Plan {
+      allow_duplicate: self.shared.allow_duplicate,
+      allow_rare: self.shared.allow_rare,
+      allowed_inscriptions: self.shared.allowed_inscriptions.iter().copied().collect(),
+      await_commit_confirmations: self.shared.await_commit_confirmations,
+      await_number: self.shared.await_number,
+      change_addresses: self.shared.change_addresses(&wallet)?,
+      change_split: self.shared.change_split.clone(),
+      check_rarity: self.shared.check_rarity,
+      cold_reveal_file: self.shared.cold_reveal_file.clone(),
+      cold_reveal_key: self.shared.cold_reveal_key,
+      commit_fee_rate: self.shared.commit_fee_rate(fee_rate),
+      commit_hash: None,
+      commit_locktime: self
+        .shared
+        .commit_locktime
+        .map_or(LockTime::ZERO, LockTime::from_consensus),
+      commit_vout_first: self.shared.commit_vout_first,
+      confirm: self.shared.confirm && !self.shared.yes,
+      consolidate: self.shared.consolidate.unwrap_or_default(),
+      consolidate_max_fee: self.shared.consolidate_max_fee,
+      deadline,
+      debug_sigs: self.shared.debug_sigs,
       destinations,
       dry_run: self.shared.dry_run,
+      dust_change_to_fee: self.shared.dust_change_to_fee,
+      emit_reveal_key: self.shared.emit_reveal_key,
       etching: batchfile.etching,
+      exclude_utxos: self.shared.exclude_utxos.iter().copied().collect(),
+      from_label: self.shared.from_label.clone(),
+      fund_reveal_separately: self.shared.fund_reveal_separately,
+      idempotency_key: self.shared.idempotency_key.clone(),
+      idempotency_secret: self.shared.idempotency_secret(&wallet)?,
       inscriptions,
+      key_seed: self.shared.key_seed()?,
+      label: self.shared.label.clone(),
+      max_funding_utxo: self.shared.max_funding_utxo,
+      min_postage: self.shared.min_postage,
       mode: batchfile.mode,
       no_backup: self.shared.no_backup,
-      no_limit: self.shared.no_limit,
+      no_limit: self.shared.no_limit()?,
+      no_rbf: self.shared.no_rbf,
       parent_info,
+      plan: self.shared.plan,
       postages,
+      prepare_only: self.shared.prepare_only.clone(),
+      psbt: self.shared.psbt,
+      recovery_key_file: self.shared.recovery_key_file.clone(),
+      recovery_key_passphrase: self.shared.recovery_key_passphrase.clone(),
       reinscribe: batchfile.reinscribe,
-      reveal_fee_rate: self.shared.fee_rate,
+      reuse_change: self.shared.reuse_change,
+      reveal_annex: self.shared.reveal_annex.clone(),
+      reveal_csv: self.shared.reveal_csv,
+      reveal_fee_rate: self.shared.reveal_fee_rate(fee_rate),
+      reveal_output_index: None,
       reveal_satpoints,
+      reveal_tx_version: self.shared.reveal_tx_version,
       satpoint: if let Some(sat) = batchfile.sat {
         Some(wallet.find_sat_in_outputs(sat)?)
       } else {
         batchfile.satpoint
       },
+      sighash: self.shared.sighash,
+      signer_command: self.shared.signer_command.clone(),
+      skip_mempool_check: self.shared.skip_mempool_check,
+      use_package_relay: self.shared.use_package_relay,
+      warn_funding_utxo_ratio: self.shared.warn_funding_utxo_ratio,
     }
     .inscribe(
       &locked_utxos.into_keys().collect(),
       wallet.get_runic_outputs()?,
       utxos,
       &wallet,
-    )
+    )?;
+
+    Ok(Some(Box::new(BatchOutput(output))))
   }
 
   fn check_etching(wallet: &Wallet, etching: &batch::Etching) -> Result {
@@ -0,0 +1,59 @@
+use {super::*, crate::wallet::batch::Bundle};
+
+#[derive(Debug, Parser)]
+pub(crate) struct BroadcastBundle {
+  #[arg(help = "Broadcast commit and reveal transactions in <BUNDLE> written by --prepare-only.")]
+  bundle: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub commit: Txid,
+  pub reveal: Txid,
+}
+
+impl BroadcastBundle {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let bundle = serde_json::from_str::<Bundle>(
+      &fs::read_to_string(&self.bundle)
+        .with_context(|| format!("I/O error reading `{}`", self.bundle.display()))?,
+    )
+    .with_context(|| format!("invalid bundle `{}`", self.bundle.display()))?;
+
+    let commit_tx = bundle.commit_tx()?;
+    let reveal_tx = bundle.reveal_tx()?;
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    // a commit transaction that was already broadcast spends its own inputs
+    let commit_known =
+      unknown_transaction_as_none(bitcoin_client.get_transaction(&commit_tx.txid(), Some(true)))?
+        .is_some();
+
+    if !commit_known {
+      for input in &commit_tx.input {
+        let outpoint = input.previous_output;
+
+        ensure!(
+          bitcoin_client
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .is_some(),
+          "commit transaction input {outpoint} was spent after bundle was prepared"
+        );
+      }
+    }
+
+    let commit = wallet.send_raw_transaction(&consensus::encode::serialize(&commit_tx))?;
+
+    let reveal = wallet
+      .send_raw_transaction(&consensus::encode::serialize(&reveal_tx))
+      .with_context(|| {
+        format!(
+          "failed to send reveal transaction, commit transaction {commit} was broadcast and may \
+          be recovered with the bundle recovery key"
+        )
+      })?;
+
+    Ok(Some(Box::new(Output { commit, reveal })))
+  }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct BumpFee {
+  #[arg(
+    long,
+    help = "Replace commit transaction even though this invalidates its reveal transaction. The reveal transaction key is not kept, so the reveal transaction cannot be re-signed against the replacement. Recover the commit output with `ord wallet recover-commit`."
+  )]
+  abandon_reveal: bool,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB for replacement transaction."
+  )]
+  fee_rate: FeeRate,
+  #[arg(help = "Replace unconfirmed commit transaction <TXID>.")]
+  txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub original: Txid,
+  pub original_fee: u64,
+  pub replacement: Txid,
+  pub fee: u64,
+}
+
+impl BumpFee {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      self.abandon_reveal,
+      "replacing commit transaction {} invalidates its reveal transaction, which cannot be \
+      re-signed because the reveal transaction key is not kept, use --abandon-reveal to replace \
+      it anyway and recover the commit output with `ord wallet recover-commit`",
+      self.txid,
+    );
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let Some(info) =
+      unknown_transaction_as_none(bitcoin_client.get_transaction(&self.txid, Some(true)))?
+    else {
+      bail!("transaction {} not found in wallet", self.txid);
+    };
+
+    ensure!(
+      info.info.confirmations == 0,
+      "transaction {} is already confirmed",
+      self.txid
+    );
+
+    let original = info.transaction()?;
+
+    ensure!(
+      original.is_explicitly_rbf(),
+      "transaction {} does not signal replaceability",
+      self.txid
+    );
+
+    // take the fee from the wallet, since looking up prevouts with
+    // getrawtransaction fails for confirmed inputs without -txindex
+    let original_fee = info
+      .fee
+      .ok_or_else(|| anyhow!("transaction {} spends inputs not in wallet", self.txid))?
+      .abs()
+      .to_unsigned()?
+      .to_sat();
+
+    let fee = self.fee_rate.fee(original.vsize()).to_sat();
+
+    // BIP 125 requires the replacement to pay for its own relay at the
+    // incremental relay fee rate, on top of the original fee
+    let incremental_fee = FeeRate::try_from(
+      bitcoin_client.get_network_info()?.incremental_fee.to_sat() as f64 / 1000.0,
+    )?
+    .fee(original.vsize())
+    .to_sat();
+
+    ensure!(
+      fee > original_fee && fee - original_fee >= incremental_fee,
+      "replacement fee of {fee} sat must exceed original fee of {original_fee} sat by at least \
+      the incremental relay fee of {incremental_fee} sat"
+    );
+
+    let commit_script_pubkeys = Self::commit_script_pubkeys(&wallet)?;
+
+    let mut change = None;
+
+    for (vout, output) in original.output.iter().enumerate().rev() {
+      if commit_script_pubkeys.contains(&output.script_pubkey) {
+        continue;
+      }
+
+      let Ok(address) = wallet.chain().address_from_script(&output.script_pubkey) else {
+        continue;
+      };
+
+      if bitcoin_client
+        .get_address_info(&address)?
+        .is_mine
+        .unwrap_or_default()
+      {
+        change = Some(vout);
+        break;
+      }
+    }
+
+    let Some(change) = change else {
+      bail!(
+        "transaction {} has no change output to draw fee from",
+        self.txid
+      );
+    };
+
+    let mut replacement = original.clone();
+
+    for input in &mut replacement.input {
+      input.witness = Witness::new();
+    }
+
+    {
+      let output = &mut replacement.output[change];
+
+      let dust_value = output.script_pubkey.dust_value().to_sat();
+
+      output.value = output
+        .value
+        .checked_sub(fee - original_fee)
+        .filter(|value| *value >= dust_value)
+        .ok_or_else(|| {
+          InsufficientFunds(format!(
+            "change output {}:{change} of {} sat is too small to pay replacement fee of {fee} sat",
+            self.txid, output.value,
+          ))
+        })?;
+    }
+
+    let signed_tx = bitcoin_client
+      .sign_raw_transaction_with_wallet(&replacement, None, None)?
+      .hex;
+
+    let replacement = bitcoin_client.send_raw_transaction(&signed_tx)?;
+
+    eprintln!(
+      "Reveal transaction spending {} is invalid, recover commit output of {replacement} with `ord wallet recover-commit`",
+      self.txid
+    );
+
+    Ok(Some(Box::new(Output {
+      original: self.txid,
+      original_fee,
+      replacement,
+      fee,
+    })))
+  }
+
+  /// Script pubkeys of commit outputs whose recovery keys were imported into
+  /// the wallet. Bitcoin Core considers these outputs the wallet's own, so they
+  /// must not be mistaken for change.
+  fn commit_script_pubkeys(wallet: &Wallet) -> Result<BTreeSet<ScriptBuf>> {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let mut script_pubkeys = BTreeSet::new();
+
+    for descriptor in bitcoin_client.list_descriptors(None)?.descriptors {
+      if !descriptor.desc.starts_with("rawtr(") {
+        continue;
+      }
+
+      for address in bitcoin_client
+        .call::<Vec<Address<NetworkUnchecked>>>("deriveaddresses", &[descriptor.desc.into()])?
+      {
+        script_pubkeys.insert(address.assume_checked().script_pubkey());
+      }
+    }
+
+    Ok(script_pubkeys)
+  }
+}
@@ -1,33 +1,60 @@
 use super::*;
 
+#[derive(Debug, Parser)]
+pub(crate) struct Cardinals {
+  #[arg(
+    long,
+    help = "List every unspent output in wallet, with whether it is cardinal."
+  )]
+  all: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CardinalUtxo {
   pub output: OutPoint,
   pub amount: u64,
 }
 
-pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
-  let unspent_outputs = wallet.utxos();
-
-  let inscribed_utxos = wallet
-    .inscriptions()
-    .keys()
-    .map(|satpoint| satpoint.outpoint)
-    .collect::<BTreeSet<OutPoint>>();
-
-  let cardinal_utxos = unspent_outputs
-    .iter()
-    .filter_map(|(output, txout)| {
-      if inscribed_utxos.contains(output) {
-        None
-      } else {
-        Some(CardinalUtxo {
-          output: *output,
-          amount: txout.value,
-        })
-      }
-    })
-    .collect::<Vec<CardinalUtxo>>();
-
-  Ok(Some(Box::new(cardinal_utxos)))
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Utxo {
+  pub output: OutPoint,
+  pub amount: u64,
+  pub cardinal: bool,
+}
+
+impl Cardinals {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let unspent_outputs = wallet.utxos();
+
+    let inscribed_utxos = wallet.inscribed_utxos();
+
+    if self.all {
+      return Ok(Some(Box::new(
+        unspent_outputs
+          .iter()
+          .map(|(output, txout)| Utxo {
+            output: *output,
+            amount: txout.value,
+            cardinal: !inscribed_utxos.contains(output),
+          })
+          .collect::<Vec<Utxo>>(),
+      )));
+    }
+
+    let cardinal_utxos = unspent_outputs
+      .iter()
+      .filter_map(|(output, txout)| {
+        if inscribed_utxos.contains(output) {
+          None
+        } else {
+          Some(CardinalUtxo {
+            output: *output,
+            amount: txout.value,
+          })
+        }
+      })
+      .collect::<Vec<CardinalUtxo>>();
+
+    Ok(Some(Box::new(cardinal_utxos)))
+  }
 }
@@ -0,0 +1,58 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Export {
+  #[arg(
+    help = "Write inscription content to files in <DIRECTORY>. Inscriptions without content are skipped."
+  )]
+  directory: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub filename: String,
+  pub content_type: Option<String>,
+  pub content_length: usize,
+}
+
+impl Export {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    fs::create_dir_all(&self.directory)
+      .with_context(|| format!("failed to create directory `{}`", self.directory.display()))?;
+
+    let mut output = BTreeMap::new();
+
+    for (id, info) in wallet.inscription_info() {
+      let content = match wallet.inscription_content(id)? {
+        Some(content) => content,
+        None if info.content_length.is_none() => continue,
+        None => bail!("failed to fetch content of inscription {id}"),
+      };
+
+      let content_type = info.effective_content_type.or(info.content_type);
+
+      let extension = content_type
+        .as_deref()
+        .and_then(Media::extension_for_content_type)
+        .unwrap_or("bin");
+
+      let filename = format!("{id}.{extension}");
+
+      let path = self.directory.join(&filename);
+
+      fs::write(&path, &content)
+        .with_context(|| format!("I/O error writing `{}`", path.display()))?;
+
+      output.insert(
+        id,
+        Output {
+          filename,
+          content_type,
+          content_length: content.len(),
+        },
+      );
+    }
+
+    Ok(Some(Box::new(output)))
+  }
+}
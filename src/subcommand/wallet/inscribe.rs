@@ -1,21 +1,71 @@
-use super::*;
+use {
+  super::*,
+  miniscript::{descriptor::DescriptorPublicKey, Descriptor, ForEachKey},
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Inscribe {
   #[command(flatten)]
-  shared: SharedArgs,
+  pub(super) shared: SharedArgs,
+  #[arg(
+    long,
+    requires = "tag",
+    help = "Allow even --tag numbers. Unrecognized even tags make the inscription cursed and unbound."
+  )]
+  pub(crate) allow_even_tags: bool,
+  #[arg(
+    long,
+    help = "Allow <FILE> to be an http:// or https:// URL, which is downloaded and inscribed."
+  )]
+  pub(crate) allow_remote: bool,
   #[arg(
     long,
     help = "Include CBOR in file at <METADATA> as inscription metadata",
     conflicts_with = "json_metadata"
   )]
   pub(crate) cbor_metadata: Option<PathBuf>,
-  #[arg(long, help = "Delegate inscription content to <DELEGATE>.")]
+  #[arg(
+    long,
+    help = "Commit to hex <COMMIT_HASH> in an additional zero-value OP_RETURN output of the reveal transaction. May be at most 80 bytes."
+  )]
+  pub(crate) commit_hash: Option<String>,
+  #[arg(
+    long,
+    requires = "file",
+    help = "Inscribe <FILE> with content type <CONTENT_TYPE> instead of the content type determined from its extension, contents, or HTTP response."
+  )]
+  pub(crate) content_type: Option<String>,
+  #[arg(
+    long,
+    help = "Delegate inscription content to <DELEGATE>. If <FILE> is also given, its content is inscribed, but <DELEGATE> content takes precedence when rendered."
+  )]
   pub(crate) delegate: Option<InscriptionId>,
   #[arg(long, help = "Send inscription to <DESTINATION>.")]
   pub(crate) destination: Option<Address<NetworkUnchecked>>,
-  #[arg(long, help = "Inscribe sat with contents of <FILE>.")]
-  pub(crate) file: PathBuf,
+  #[arg(
+    long,
+    conflicts_with_all = ["destination", "destinations"],
+    help = "Send inscription to an address derived locally from <DESTINATION_DESCRIPTOR>, a taproot `tr()` output descriptor or an extended public key, without asking Bitcoin Core for an address."
+  )]
+  pub(crate) destination_descriptor: Option<String>,
+  #[arg(
+    long,
+    requires = "destination_descriptor",
+    help = "Derive destination at child <DESTINATION_INDEX> of --destination-descriptor. Required if the descriptor has a wildcard."
+  )]
+  pub(crate) destination_index: Option<u32>,
+  #[arg(
+    long,
+    conflicts_with_all = ["destination", "pointer", "reveal_output_index", "shard_size"],
+    help = "Inscribe a copy of the inscription for each address in <DESTINATIONS>, a file of newline-separated addresses, and send each copy to its address. All copies are inscribed by one commit and reveal transaction."
+  )]
+  pub(crate) destinations: Option<PathBuf>,
+  #[arg(
+    long,
+    required_unless_present = "delegate",
+    help = "Inscribe sat with contents of <FILE>. May be an http:// or https:// URL if --allow-remote is given. May be omitted if <DELEGATE> is given."
+  )]
+  pub(crate) file: Option<PathBuf>,
   #[arg(
     long,
     help = "Include JSON in file at <METADATA> converted to CBOR as inscription metadata",
@@ -26,6 +76,11 @@ pub(crate) struct Inscribe {
   pub(crate) metaprotocol: Option<String>,
   #[clap(long, help = "Make inscription a child of <PARENT>.")]
   pub(crate) parent: Option<InscriptionId>,
+  #[arg(
+    long,
+    help = "Place inscription on the sat at offset <POINTER> of the reveal transaction outputs instead of the first sat of its output. Must be less than the total value of the parent and inscription outputs."
+  )]
+  pub(crate) pointer: Option<u64>,
   #[arg(
     long,
     help = "Amount of postage to include in the inscription. Default `10000sat`."
@@ -33,14 +88,57 @@ pub(crate) struct Inscribe {
   pub(crate) postage: Option<Amount>,
   #[clap(long, help = "Allow reinscription.")]
   pub(crate) reinscribe: bool,
+  #[arg(
+    long,
+    value_name = "INDEX",
+    conflicts_with = "pointer",
+    help = "Place inscription output at reveal output <INDEX> instead of first, after the parent output if any. Outputs moved ahead of it must be zero-value, such as the --commit-hash OP_RETURN output, so the inscribed sat lands on it. The separately funded reveal change output is always last."
+  )]
+  pub(crate) reveal_output_index: Option<u32>,
   #[arg(long, help = "Inscribe <SAT>.", conflicts_with = "satpoint")]
   pub(crate) sat: Option<Sat>,
   #[arg(long, help = "Inscribe <SATPOINT>.", conflicts_with = "sat")]
   pub(crate) satpoint: Option<SatPoint>,
+  #[arg(
+    long,
+    value_parser = clap::value_parser!(u64).range(1..),
+    requires = "file",
+    conflicts_with_all = [
+      "cold_reveal_file",
+      "delegate",
+      "dry_run",
+      "fund_reveal_separately",
+      "key_seed",
+      "parent",
+      "plan",
+      "pointer",
+      "prepare_only",
+      "psbt",
+      "sat",
+      "satpoint",
+    ],
+    help = "Split <FILE> content into shard inscriptions of at most <SHARD_SIZE> bytes, each with its own commit and reveal transaction, and then inscribe a JSON manifest listing the shards in order, so viewers can reassemble the content."
+  )]
+  pub(crate) shard_size: Option<u64>,
+  #[arg(
+    long,
+    value_name = "NUM=HEX",
+    value_parser = tag,
+    help = "Add envelope tag <NUM> with hex value <HEX> to the inscription. May be given multiple times. Odd tags may be ignored by indexers that don't recognize them, while even tags that aren't recognized make the inscription cursed and unbound, and require --allow-even-tags."
+  )]
+  pub(crate) tag: Vec<(u8, Vec<u8>)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShardedOutput {
+  pub manifest: batch::Output,
+  pub shards: Vec<batch::Output>,
 }
 
 impl Inscribe {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let deadline = wallet.deadline();
+
     let chain = wallet.chain();
 
     if let Some(delegate) = self.delegate {
@@ -50,48 +148,497 @@ impl Inscribe {
       }
     }
 
-    batch::Plan {
-      commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
-      destinations: vec![match self.destination.clone() {
-        Some(destination) => destination.require_network(chain.network())?,
-        None => wallet.get_change_address()?,
-      }],
+    let metadata =
+      Inscribe::parse_metadata(self.cbor_metadata.clone(), self.json_metadata.clone())?;
+
+    let inscription = match self.file.clone() {
+      Some(file) => match Self::remote_url(&file)? {
+        Some(url) => {
+          ensure!(
+            self.allow_remote,
+            "inscribing from URL {url} requires --allow-remote"
+          );
+
+          Inscription::from_url(
+            chain,
+            self.shared.compression(),
+            self.content_type.clone(),
+            self.delegate,
+            metadata,
+            self.metaprotocol.clone(),
+            self.parent.into_iter().collect(),
+            &url,
+            self.pointer,
+            None,
+            self.shared.validate_content,
+          )?
+        }
+        None => Inscription::from_file(
+          chain,
+          self.shared.compression(),
+          self.content_type.clone(),
+          self.delegate,
+          metadata,
+          self.metaprotocol.clone(),
+          self.parent.into_iter().collect(),
+          file,
+          self.pointer,
+          None,
+          self.shared.validate_content,
+        )?,
+      },
+      None => Inscription {
+        delegate: self.delegate.map(|delegate| delegate.value()),
+        metadata,
+        metaprotocol: self
+          .metaprotocol
+          .clone()
+          .map(|metaprotocol| metaprotocol.into_bytes()),
+        parents: self.parent.iter().map(|parent| parent.value()).collect(),
+        pointer: self.pointer.map(Inscription::pointer_value),
+        ..default()
+      },
+    };
+
+    let inscription = Inscription {
+      extra_tags: self.tag.clone(),
+      ..inscription
+    };
+
+    inscription.check_extra_tags(self.allow_even_tags)?;
+
+    self
+      .shared
+      .check_empty(std::slice::from_ref(&inscription))?;
+
+    self
+      .shared
+      .check_content_types(std::slice::from_ref(&inscription))?;
+
+    let commit_hash = self
+      .commit_hash
+      .as_deref()
+      .map(hex::decode)
+      .transpose()
+      .context("invalid commit hash")?;
+
+    let parent_info = wallet.get_parent_info(self.parent)?;
+
+    let postage = self.postage.unwrap_or(TARGET_POSTAGE);
+
+    if let Some(pointer) = self.pointer {
+      let total = parent_info
+        .as_ref()
+        .map(|parent_info| parent_info.tx_out.value)
+        .unwrap_or_default()
+        + postage.to_sat();
+
+      ensure!(
+        pointer < total,
+        "pointer {pointer} is not less than total reveal output value of {total} sat"
+      );
+    }
+
+    let fee_rate = self.shared.fee_rate(&wallet)?;
+
+    let locked_utxos = wallet.locked_utxos().clone().into_keys().collect();
+
+    let runic_utxos = wallet.get_runic_outputs()?;
+
+    let no_limit = self.shared.no_limit()?;
+
+    let change_addresses = self.shared.change_addresses(&wallet)?;
+
+    let idempotency_secret = self.shared.idempotency_secret(&wallet)?;
+
+    let Some(shard_size) = self.shard_size else {
+      let (inscriptions, destinations) = match &self.destinations {
+        Some(path) => {
+          let destinations = Self::load_destinations(path, chain)?;
+
+          let mut pointer = parent_info
+            .as_ref()
+            .map(|parent_info| parent_info.tx_out.value)
+            .unwrap_or_default();
+
+          let mut inscriptions = Vec::new();
+
+          for _ in &destinations {
+            inscriptions.push(Inscription {
+              pointer: Some(Inscription::pointer_value(pointer)),
+              ..inscription.clone()
+            });
+
+            pointer += postage.to_sat();
+          }
+
+          (inscriptions, destinations)
+        }
+        None => (vec![inscription], vec![self.destination(&wallet)?]),
+      };
+
+      let output = self
+        .plan(
+          &wallet,
+          inscriptions,
+          destinations,
+          commit_hash,
+          parent_info,
+          postage,
+          fee_rate,
+          deadline,
+          no_limit,
+          change_addresses,
+          idempotency_secret,
+        )?
+        .inscribe(&locked_utxos, runic_utxos, wallet.utxos(), &wallet)?;
+
+      return Ok(Some(Box::new(output)));
+    };
+
+    // Bitcoin Core's default limit on the number of unconfirmed ancestors and
+    // descendants of a mempool transaction, including the transaction itself
+    const MAX_UNCONFIRMED_CHAIN: usize = 25;
+
+    let shard_inscriptions = Manifest::shards(&inscription, shard_size.try_into().unwrap())?;
+
+    // each commit transaction spends the previous one's change, so every
+    // commit and reveal transaction descends from the first commit transaction
+    let transactions = (shard_inscriptions.len() + 1) * 2;
+
+    ensure!(
+      transactions <= MAX_UNCONFIRMED_CHAIN,
+      "inscribing {} shards and a manifest creates a chain of {transactions} unconfirmed transactions, \
+      more than the mempool limit of {MAX_UNCONFIRMED_CHAIN}, use a larger --shard-size",
+      shard_inscriptions.len(),
+    );
+
+    // shards and manifest are all sent to the same destination
+    let destination = self.destination(&wallet)?;
+
+    let mut utxos = wallet.utxos().clone();
+
+    let mut shards = Vec::new();
+
+    for shard in shard_inscriptions {
+      let output = self
+        .plan(
+          &wallet,
+          vec![shard],
+          vec![destination.clone()],
+          None,
+          None,
+          postage,
+          fee_rate,
+          deadline,
+          no_limit,
+          change_addresses.clone(),
+          idempotency_secret,
+        )
+        .and_then(|plan| plan.inscribe(&locked_utxos, runic_utxos.clone(), &utxos, &wallet))
+        .map_err(|err| Self::shards_failed(err, &shards))?;
+
+      shards.push(output);
+
+      Self::spend_commit(&wallet, &mut utxos, shards.last().unwrap())
+        .map_err(|err| Self::shards_failed(err, &shards))?;
+    }
+
+    let manifest = Manifest::new(
+      &inscription,
+      shards
+        .iter()
+        .map(|shard| shard.inscriptions[0].id)
+        .collect(),
+    );
+
+    let manifest = self
+      .plan(
+        &wallet,
+        vec![manifest.inscription(&inscription)],
+        vec![destination],
+        commit_hash,
+        None,
+        postage,
+        fee_rate,
+        deadline,
+        no_limit,
+        change_addresses,
+        idempotency_secret,
+      )
+      .and_then(|plan| plan.inscribe(&locked_utxos, runic_utxos, &utxos, &wallet))
+      .map_err(|err| Self::shards_failed(err, &shards))?;
+
+    Ok(Some(Box::new(ShardedOutput { manifest, shards })))
+  }
+
+  fn plan(
+    &self,
+    wallet: &Wallet,
+    inscriptions: Vec<Inscription>,
+    destinations: Vec<Address>,
+    commit_hash: Option<Vec<u8>>,
+    parent_info: Option<batch::ParentInfo>,
+    postage: Amount,
+    fee_rate: FeeRate,
+    deadline: Option<Instant>,
+    no_limit: bool,
+    change_addresses: Option<Vec<Address>>,
+    idempotency_secret: Option<[u8; 32]>,
+  ) -> Result<batch::Plan> {
+    let postages = vec![postage; inscriptions.len()];
+
+    Ok(batch::Plan {
+      allow_duplicate: self.shared.allow_duplicate,
+      allow_rare: self.shared.allow_rare,
+      allowed_inscriptions: self.shared.allowed_inscriptions.iter().copied().collect(),
+      await_commit_confirmations: self.shared.await_commit_confirmations,
+      await_number: self.shared.await_number,
+      change_addresses,
+      change_split: self.shared.change_split.clone(),
+      check_rarity: self.shared.check_rarity,
+      cold_reveal_file: self.shared.cold_reveal_file.clone(),
+      cold_reveal_key: self.shared.cold_reveal_key,
+      commit_fee_rate: self.shared.commit_fee_rate(fee_rate),
+      commit_hash,
+      commit_locktime: self
+        .shared
+        .commit_locktime
+        .map_or(LockTime::ZERO, LockTime::from_consensus),
+      commit_vout_first: self.shared.commit_vout_first,
+      confirm: self.shared.confirm && !self.shared.yes,
+      consolidate: self.shared.consolidate.unwrap_or_default(),
+      consolidate_max_fee: self.shared.consolidate_max_fee,
+      deadline,
+      debug_sigs: self.shared.debug_sigs,
+      destinations,
       dry_run: self.shared.dry_run,
+      dust_change_to_fee: self.shared.dust_change_to_fee,
+      emit_reveal_key: self.shared.emit_reveal_key,
       etching: None,
-      inscriptions: vec![Inscription::from_file(
-        chain,
-        self.shared.compress,
-        self.delegate,
-        Inscribe::parse_metadata(self.cbor_metadata, self.json_metadata)?,
-        self.metaprotocol,
-        self.parent.into_iter().collect(),
-        self.file,
-        None,
-        None,
-      )?],
+      exclude_utxos: self.shared.exclude_utxos.iter().copied().collect(),
+      from_label: self.shared.from_label.clone(),
+      fund_reveal_separately: self.shared.fund_reveal_separately,
+      idempotency_key: self.shared.idempotency_key.clone(),
+      idempotency_secret,
+      inscriptions,
+      key_seed: self.shared.key_seed()?,
+      label: self.shared.label.clone(),
+      max_funding_utxo: self.shared.max_funding_utxo,
+      min_postage: self.shared.min_postage,
       mode: batch::Mode::SeparateOutputs,
       no_backup: self.shared.no_backup,
-      no_limit: self.shared.no_limit,
-      parent_info: wallet.get_parent_info(self.parent)?,
-      postages: vec![self.postage.unwrap_or(TARGET_POSTAGE)],
+      no_limit,
+      no_rbf: self.shared.no_rbf,
+      parent_info,
+      plan: self.shared.plan,
+      postages,
+      prepare_only: self.shared.prepare_only.clone(),
+      psbt: self.shared.psbt,
+      recovery_key_file: self.shared.recovery_key_file.clone(),
+      recovery_key_passphrase: self.shared.recovery_key_passphrase.clone(),
       reinscribe: self.reinscribe,
-      reveal_fee_rate: self.shared.fee_rate,
+      reuse_change: self.shared.reuse_change,
+      reveal_annex: self.shared.reveal_annex.clone(),
+      reveal_csv: self.shared.reveal_csv,
+      reveal_fee_rate: self.shared.reveal_fee_rate(fee_rate),
+      reveal_output_index: self.reveal_output_index,
       reveal_satpoints: Vec::new(),
+      reveal_tx_version: self.shared.reveal_tx_version,
       satpoint: if let Some(sat) = self.sat {
         Some(wallet.find_sat_in_outputs(sat)?)
       } else {
         self.satpoint
       },
+      sighash: self.shared.sighash,
+      signer_command: self.shared.signer_command.clone(),
+      skip_mempool_check: self.shared.skip_mempool_check,
+      use_package_relay: self.shared.use_package_relay,
+      warn_funding_utxo_ratio: self.shared.warn_funding_utxo_ratio,
+    })
+  }
+
+  fn destination(&self, wallet: &Wallet) -> Result<Address> {
+    if let Some(descriptor) = &self.destination_descriptor {
+      return Self::derive_destination(descriptor, self.destination_index, wallet.chain());
     }
-    .inscribe(
-      &wallet.locked_utxos().clone().into_keys().collect(),
-      wallet.get_runic_outputs()?,
-      wallet.utxos(),
-      &wallet,
+
+    match (self.destination.clone(), &self.shared.idempotency_key) {
+      (Some(destination), _) => Ok(destination.require_network(wallet.chain().network())?),
+      (None, Some(idempotency_key)) => {
+        wallet.get_idempotent_address(idempotency_key, "destination")
+      }
+      (None, None) => wallet.get_change_address(),
+    }
+  }
+
+  /// Derive the destination address at `index` of `descriptor`, which may be a
+  /// `tr()` descriptor or a bare extended public key, which is treated as a
+  /// `tr()` descriptor with that key.
+  fn derive_destination(descriptor: &str, index: Option<u32>, chain: Chain) -> Result<Address> {
+    let descriptor = match descriptor.parse::<Descriptor<DescriptorPublicKey>>() {
+      Ok(descriptor) => descriptor,
+      Err(err) => match descriptor.parse::<DescriptorPublicKey>() {
+        Ok(key) => Descriptor::new_tr(key, None)?,
+        Err(_) => {
+          return Err(err).with_context(|| format!("invalid destination descriptor `{descriptor}`"))
+        }
+      },
+    };
+
+    ensure!(
+      matches!(descriptor, Descriptor::Tr(_)),
+      "destination descriptor `{descriptor}` is not a taproot `tr()` descriptor",
+    );
+
+    let mainnet = chain.network() == Network::Bitcoin;
+
+    ensure!(
+      descriptor.for_each_key(|key| match key {
+        DescriptorPublicKey::Single(_) => true,
+        DescriptorPublicKey::XPub(xkey) => (xkey.xkey.network == Network::Bitcoin) == mainnet,
+        DescriptorPublicKey::MultiXPub(xkey) => (xkey.xkey.network == Network::Bitcoin) == mainnet,
+      }),
+      "destination descriptor `{descriptor}` has extended public keys that are not valid for {chain}",
+    );
+
+    let index = match index {
+      Some(index) => index,
+      None => {
+        ensure!(
+          !descriptor.has_wildcard(),
+          "destination descriptor `{descriptor}` has a wildcard, --destination-index is required",
+        );
+        0
+      }
+    };
+
+    Ok(
+      descriptor
+        .at_derivation_index(index)?
+        .address(chain.network())?,
     )
   }
 
-  fn parse_metadata(cbor: Option<PathBuf>, json: Option<PathBuf>) -> Result<Option<Vec<u8>>> {
+  /// Load `--destinations` file, one address per line. Blank lines are
+  /// ignored.
+  fn load_destinations(path: &Path, chain: Chain) -> Result<Vec<Address>> {
+    let mut destinations = Vec::new();
+
+    for (i, line) in fs::read_to_string(path)
+      .with_context(|| format!("failed to read destinations file `{}`", path.display()))?
+      .lines()
+      .enumerate()
+    {
+      let line = line.trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      let address = line.parse::<Address<NetworkUnchecked>>().with_context(|| {
+        format!(
+          "invalid destination address `{line}` on line {} of `{}`",
+          i + 1,
+          path.display()
+        )
+      })?;
+
+      ensure!(
+        address.is_valid_for_network(chain.network()),
+        "destination address {} on line {} of `{}` is not valid for {chain}",
+        address.clone().assume_checked(),
+        i + 1,
+        path.display(),
+      );
+
+      destinations.push(address.assume_checked());
+    }
+
+    ensure!(
+      !destinations.is_empty(),
+      "destinations file `{}` contains no addresses",
+      path.display()
+    );
+
+    Ok(destinations)
+  }
+
+  /// Add the shards inscribed so far to `err`, since their transactions were
+  /// broadcast, and running the command again would inscribe them again.
+  fn shards_failed(err: Error, shards: &[batch::Output]) -> Error {
+    if shards.is_empty() {
+      return err;
+    }
+
+    err.context(format!(
+      "failed after inscribing {} shards: {}",
+      shards.len(),
+      shards
+        .iter()
+        .map(|shard| shard.inscriptions[0].id.to_string())
+        .collect::<Vec<String>>()
+        .join(" "),
+    ))
+  }
+
+  /// Update `utxos` after inscribing a shard, removing outputs spent by its
+  /// commit transaction and adding its change outputs, so the next shard can
+  /// spend them before they confirm.
+  fn spend_commit(
+    wallet: &Wallet,
+    utxos: &mut BTreeMap<OutPoint, TxOut>,
+    output: &batch::Output,
+  ) -> Result {
+    let commit_address = output
+      .commit_address
+      .clone()
+      .require_network(wallet.chain().network())?;
+
+    let commit_tx = wallet
+      .bitcoin_client()
+      .get_transaction(&output.commit, None)?
+      .transaction()?;
+
+    for input in &commit_tx.input {
+      utxos.remove(&input.previous_output);
+    }
+
+    for (vout, tx_out) in commit_tx.output.into_iter().enumerate() {
+      if tx_out.script_pubkey != commit_address.script_pubkey() {
+        utxos.insert(
+          OutPoint {
+            txid: output.commit,
+            vout: vout.try_into().unwrap(),
+          },
+          tx_out,
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  fn remote_url(file: &Path) -> Result<Option<Url>> {
+    let Some(file) = file.to_str() else {
+      return Ok(None);
+    };
+
+    if !file.starts_with("http://") && !file.starts_with("https://") {
+      return Ok(None);
+    }
+
+    Ok(Some(
+      file
+        .parse()
+        .with_context(|| format!("invalid URL {file}"))?,
+    ))
+  }
+
+  pub(crate) fn parse_metadata(
+    cbor: Option<PathBuf>,
+    json: Option<PathBuf>,
+  ) -> Result<Option<Vec<u8>>> {
     if let Some(path) = cbor {
       let cbor = fs::read(path)?;
       let _value: Value = ciborium::from_reader(Cursor::new(cbor.clone()))
@@ -111,6 +658,26 @@ impl Inscribe {
   }
 }
 
+fn tag(s: &str) -> Result<(u8, Vec<u8>)> {
+  let (tag, value) = s
+    .split_once('=')
+    .ok_or_else(|| anyhow!("tag must be of the form <NUM>=<HEX>"))?;
+
+  let tag = tag
+    .parse::<u8>()
+    .with_context(|| format!("invalid tag number `{tag}`, must be between 0 and 255"))?;
+
+  let value = hex::decode(value).with_context(|| format!("invalid hex value for tag {tag}"))?;
+
+  ensure!(
+    value.len() <= MAX_SCRIPT_ELEMENT_SIZE,
+    "tag {tag} value is {} bytes, but may be at most {MAX_SCRIPT_ELEMENT_SIZE} bytes",
+    value.len(),
+  );
+
+  Ok((tag, value))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -135,6 +702,186 @@ mod tests {
     );
   }
 
+  #[test]
+  fn file_is_required_unless_delegate_is_given() {
+    assert_regex_match!(
+      Arguments::try_parse_from(["ord", "wallet", "inscribe", "--fee-rate", "1"])
+        .unwrap_err()
+        .to_string(),
+      ".*required arguments were not provided.*--file <FILE>.*"
+    );
+
+    assert!(Arguments::try_parse_from([
+      "ord",
+      "wallet",
+      "inscribe",
+      "--fee-rate",
+      "1",
+      "--delegate",
+      "6ac5cacb768794f4fd7a78bf00f2074891fce68bd65c4ff36e77177237aacacai0",
+    ])
+    .is_ok());
+  }
+
+  #[test]
+  fn remote_url() {
+    assert_eq!(Inscribe::remote_url(Path::new("foo.txt")).unwrap(), None);
+    assert_eq!(
+      Inscribe::remote_url(Path::new("http://example.com/foo.txt")).unwrap(),
+      Some("http://example.com/foo.txt".parse().unwrap()),
+    );
+    assert_eq!(
+      Inscribe::remote_url(Path::new("https://example.com/foo.txt")).unwrap(),
+      Some("https://example.com/foo.txt".parse().unwrap()),
+    );
+    assert!(Inscribe::remote_url(Path::new("https://")).is_err());
+  }
+
+  #[test]
+  fn destination_and_destinations_flags_conflict() {
+    assert_regex_match!(
+      Arguments::try_parse_from([
+        "ord",
+        "wallet",
+        "inscribe",
+        "--destinations",
+        "destinations.txt",
+        "--destination",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "--file",
+        "baz",
+      ])
+      .unwrap_err()
+      .to_string(),
+      ".*--destinations <DESTINATIONS>.*cannot be used with.*--destination <DESTINATION>.*"
+    );
+  }
+
+  #[test]
+  fn destination_descriptor_and_destination_flags_conflict() {
+    assert_regex_match!(
+      Arguments::try_parse_from([
+        "ord",
+        "wallet",
+        "inscribe",
+        "--destination-descriptor",
+        "tr(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)",
+        "--destination",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "--file",
+        "baz",
+      ])
+      .unwrap_err()
+      .to_string(),
+      ".*--destination-descriptor <DESTINATION_DESCRIPTOR>.*cannot be used with.*--destination <DESTINATION>.*"
+    );
+  }
+
+  #[test]
+  fn derive_destination() {
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const TPUB: &str = "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp";
+
+    #[track_caller]
+    fn case(descriptor: &str, index: Option<u32>, chain: Chain, expected: &str) {
+      assert_eq!(
+        Inscribe::derive_destination(descriptor, index, chain)
+          .unwrap()
+          .to_string(),
+        expected,
+      );
+    }
+
+    case(
+      &format!("tr({XPUB})"),
+      None,
+      Chain::Mainnet,
+      "bc1p7x4krdxtfwv5mwu2hhq6y2pnp5msc8mnczk5rr06zsulrn395zzq78ql6s",
+    );
+    case(
+      &format!("tr({XPUB}/0/*)"),
+      Some(0),
+      Chain::Mainnet,
+      "bc1pgfhzycz8pckwsdspf04hn6rps5tpapgrchtzx5f3k8wlvqhmxu6qrdt83a",
+    );
+    case(
+      &format!("tr({XPUB}/0/*)"),
+      Some(1),
+      Chain::Mainnet,
+      "bc1pdqxk5pjfm2c5ellt67u9rjpajde2qlpjsgc244u6p4qpatf2ug6sv3hmk7",
+    );
+    case(
+      &format!("{TPUB}/0/*"),
+      Some(0),
+      Chain::Regtest,
+      "bcrt1pgfhzycz8pckwsdspf04hn6rps5tpapgrchtzx5f3k8wlvqhmxu6qeuhw7g",
+    );
+    case(
+      &format!("tr({TPUB}/0/*)"),
+      Some(7),
+      Chain::Testnet,
+      "tb1ptl65uhgl6zgavkfh4y4e7uc4237neufcedhnyekjhvlvd0pw208q0sgnfd",
+    );
+  }
+
+  #[test]
+  fn derive_destination_errors() {
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    assert_regex_match!(
+      Inscribe::derive_destination("foo", None, Chain::Mainnet)
+        .unwrap_err()
+        .to_string(),
+      "invalid destination descriptor `foo`",
+    );
+
+    assert_regex_match!(
+      Inscribe::derive_destination(&format!("wpkh({XPUB})"), None, Chain::Mainnet)
+        .unwrap_err()
+        .to_string(),
+      "destination descriptor `wpkh.*` is not a taproot `tr\\(\\)` descriptor",
+    );
+
+    assert_regex_match!(
+      Inscribe::derive_destination(&format!("tr({XPUB})"), None, Chain::Signet)
+        .unwrap_err()
+        .to_string(),
+      "destination descriptor `tr.*` has extended public keys that are not valid for signet",
+    );
+
+    assert_regex_match!(
+      Inscribe::derive_destination(&format!("tr({XPUB}/0/*)"), None, Chain::Mainnet)
+        .unwrap_err()
+        .to_string(),
+      "destination descriptor `tr.*` has a wildcard, --destination-index is required",
+    );
+  }
+
+  #[test]
+  fn tag_parsing() {
+    assert_eq!(tag("17=abcd").unwrap(), (17, vec![0xAB, 0xCD]));
+    assert_eq!(tag("255=").unwrap(), (255, Vec::new()));
+
+    assert_eq!(
+      tag("17").unwrap_err().to_string(),
+      "tag must be of the form <NUM>=<HEX>"
+    );
+    assert_eq!(
+      tag("256=00").unwrap_err().to_string(),
+      "invalid tag number `256`, must be between 0 and 255"
+    );
+    assert_eq!(
+      tag("17=xyz").unwrap_err().to_string(),
+      "invalid hex value for tag 17"
+    );
+    assert_eq!(
+      tag(&format!("17={}", "00".repeat(521)))
+        .unwrap_err()
+        .to_string(),
+      "tag 17 value is 521 bytes, but may be at most 520 bytes"
+    );
+  }
+
   #[test]
   fn satpoint_and_sat_flags_conflict() {
     assert_regex_match!(
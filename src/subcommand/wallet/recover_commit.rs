@@ -0,0 +1,206 @@
+use {
+  super::*,
+  bitcoin::{
+    key::{TweakedPublicKey, UntweakedKeyPair},
+    secp256k1::{
+      self,
+      constants::SCHNORR_SIGNATURE_SIZE,
+      rand::{rngs::StdRng, SeedableRng},
+      Secp256k1, XOnlyPublicKey,
+    },
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::Signature,
+  },
+  bitcoincore_rpc::bitcoincore_rpc_json::ScanTxOutRequest,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct RecoverCommit {
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB for sweep transaction."
+  )]
+  fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Read recovery key in WIF format from <RECOVERY_KEY_FILE> instead of stdin."
+  )]
+  recovery_key_file: Option<PathBuf>,
+  #[arg(
+    long,
+    env = "ORD_RECOVERY_KEY_PASSPHRASE",
+    hide_env_values = true,
+    help = "Decrypt encrypted recovery key with <RECOVERY_KEY_PASSPHRASE>. Prefer setting the environment variable, which does not appear in shell history or process lists."
+  )]
+  recovery_key_passphrase: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub commit_address: Address<NetworkUnchecked>,
+  pub outputs: Vec<OutPoint>,
+  pub txid: Txid,
+  pub amount: u64,
+  pub fee: u64,
+}
+
+impl RecoverCommit {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let private_key = recovery_key::load(
+      self.recovery_key_file,
+      self.recovery_key_passphrase.as_deref(),
+    )?;
+
+    let network = wallet.chain().network();
+
+    ensure!(
+      (private_key.network == Network::Bitcoin) == (network == Network::Bitcoin),
+      "recovery key is for {} but wallet is on {network}",
+      private_key.network,
+    );
+
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::from_secret_key(&secp256k1, &private_key.inner);
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let commit_address = Address::p2tr_tweaked(
+      TweakedPublicKey::dangerous_assume_tweaked(public_key),
+      network,
+    );
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let mut outputs = Vec::new();
+
+    // confirmed outputs spent by mempool transactions are skipped
+    for unspent in bitcoin_client
+      .scan_tx_out_set_blocking(&[ScanTxOutRequest::Single(format!("addr({commit_address})"))])?
+      .unspents
+    {
+      if bitcoin_client
+        .get_tx_out(&unspent.txid, unspent.vout, Some(true))?
+        .is_some()
+      {
+        outputs.push((
+          OutPoint {
+            txid: unspent.txid,
+            vout: unspent.vout,
+          },
+          TxOut {
+            value: unspent.amount.to_sat(),
+            script_pubkey: unspent.script_pub_key,
+          },
+        ));
+      }
+    }
+
+    // unconfirmed outputs are found in the wallet, since the recovery
+    // descriptor is imported into it when inscribing
+    for unspent in
+      bitcoin_client.list_unspent(Some(0), Some(0), Some(&[&commit_address]), Some(true), None)?
+    {
+      outputs.push((
+        OutPoint {
+          txid: unspent.txid,
+          vout: unspent.vout,
+        },
+        TxOut {
+          value: unspent.amount.to_sat(),
+          script_pubkey: commit_address.script_pubkey(),
+        },
+      ));
+    }
+
+    ensure!(
+      !outputs.is_empty(),
+      "no unspent outputs found at commit address {commit_address}"
+    );
+
+    let prevouts = outputs
+      .iter()
+      .map(|(_, tx_out)| tx_out.clone())
+      .collect::<Vec<TxOut>>();
+
+    let destination = wallet.get_change_address()?;
+
+    let mut transaction = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: outputs
+        .iter()
+        .map(|(outpoint, _)| TxIn {
+          previous_output: *outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]),
+        })
+        .collect(),
+      output: vec![TxOut {
+        value: 0,
+        script_pubkey: destination.script_pubkey(),
+      }],
+    };
+
+    let total = prevouts.iter().map(|txout| txout.value).sum::<u64>();
+
+    let fee = self.fee_rate.fee(transaction.vsize()).to_sat();
+
+    let amount = total
+      .checked_sub(fee)
+      .filter(|amount| *amount >= destination.script_pubkey().dust_value().to_sat())
+      .ok_or_else(|| {
+        InsufficientFunds(format!(
+          "commit outputs of {total} sat are too small to pay sweep fee of {fee} sat"
+        ))
+      })?;
+
+    transaction.output[0].value = amount;
+
+    let mut rng = StdRng::from_entropy();
+
+    let mut sighash_cache = SighashCache::new(&mut transaction);
+
+    for input in 0..prevouts.len() {
+      let sighash = sighash_cache
+        .taproot_key_spend_signature_hash(input, &Prevouts::All(&prevouts), TapSighashType::Default)
+        .expect("signature hash should compute");
+
+      let sig = secp256k1.sign_schnorr_with_rng(
+        &secp256k1::Message::from_slice(sighash.as_ref())
+          .expect("should be cryptographically secure hash"),
+        &key_pair,
+        &mut rng,
+      );
+
+      let witness = sighash_cache
+        .witness_mut(input)
+        .expect("getting mutable witness reference should work");
+
+      witness.clear();
+
+      witness.push(
+        Signature {
+          sig,
+          hash_ty: TapSighashType::Default,
+        }
+        .to_vec(),
+      );
+    }
+
+    let txid = bitcoin_client.send_raw_transaction(&transaction)?;
+
+    Ok(Some(Box::new(Output {
+      commit_address: uncheck(&commit_address),
+      outputs: transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect(),
+      txid,
+      amount,
+      fee,
+    })))
+  }
+}
@@ -0,0 +1,102 @@
+use {
+  super::*,
+  bitcoincore_rpc::bitcoincore_rpc_json::{GetAddressInfoResultLabel, ScanTxOutRequest},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct RecoveryKeys {
+  #[arg(
+    long,
+    help = "Only list recovery keys whose commit address holds unspent outputs."
+  )]
+  funded: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub address: Address<NetworkUnchecked>,
+  pub amount: u64,
+  pub descriptor: String,
+  pub label: String,
+  pub outputs: Vec<OutPoint>,
+}
+
+impl RecoveryKeys {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let mut keys = Vec::new();
+
+    for descriptor in bitcoin_client.list_descriptors(None)?.descriptors {
+      if !descriptor.desc.starts_with("rawtr(") {
+        continue;
+      }
+
+      let addresses = bitcoin_client.call::<Vec<Address<NetworkUnchecked>>>(
+        "deriveaddresses",
+        &[descriptor.desc.clone().into()],
+      )?;
+
+      let [address] = addresses.as_slice() else {
+        continue;
+      };
+
+      let address = address.clone().require_network(wallet.chain().network())?;
+
+      let label = bitcoin_client
+        .get_address_info(&address)?
+        .labels
+        .into_iter()
+        .map(|label| match label {
+          GetAddressInfoResultLabel::Simple(name) => name,
+          GetAddressInfoResultLabel::WithPurpose { name, .. } => name,
+        })
+        .find(|label| label.starts_with(batch::Plan::RECOVERY_KEY_LABEL));
+
+      if let Some(label) = label {
+        keys.push((address, descriptor.desc, label));
+      }
+    }
+
+    let unspents = if keys.is_empty() {
+      Vec::new()
+    } else {
+      bitcoin_client
+        .scan_tx_out_set_blocking(
+          &keys
+            .iter()
+            .map(|(address, _descriptor, _label)| {
+              ScanTxOutRequest::Single(format!("addr({address})"))
+            })
+            .collect::<Vec<ScanTxOutRequest>>(),
+        )?
+        .unspents
+    };
+
+    Ok(Some(Box::new(
+      keys
+        .into_iter()
+        .map(|(address, descriptor, label)| {
+          let script_pubkey = address.script_pubkey();
+
+          let unspents = unspents
+            .iter()
+            .filter(|unspent| unspent.script_pub_key == script_pubkey)
+            .collect::<Vec<_>>();
+
+          Output {
+            address: uncheck(&address),
+            amount: unspents.iter().map(|unspent| unspent.amount.to_sat()).sum(),
+            descriptor,
+            label,
+            outputs: unspents
+              .iter()
+              .map(|unspent| OutPoint::new(unspent.txid, unspent.vout))
+              .collect(),
+          }
+        })
+        .filter(|output| !self.funded || !output.outputs.is_empty())
+        .collect::<Vec<Output>>(),
+    )))
+  }
+}
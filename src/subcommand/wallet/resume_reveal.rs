@@ -0,0 +1,59 @@
+use {super::*, crate::wallet::batch::Bundle};
+
+#[derive(Debug, Parser)]
+pub(crate) struct ResumeReveal {
+  #[arg(
+    help = "Broadcast reveal transaction in <RECOVERY_FILE> saved when broadcasting it failed."
+  )]
+  recovery_file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub commit: Txid,
+  pub reveal: Txid,
+}
+
+impl ResumeReveal {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let bundle = serde_json::from_str::<Bundle>(
+      &fs::read_to_string(&self.recovery_file)
+        .with_context(|| format!("I/O error reading `{}`", self.recovery_file.display()))?,
+    )
+    .with_context(|| format!("invalid recovery file `{}`", self.recovery_file.display()))?;
+
+    let reveal_tx = bundle.reveal_tx()?;
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    // a reveal transaction that was already broadcast spends the commit output
+    let reveal_known =
+      unknown_transaction_as_none(bitcoin_client.get_raw_transaction(&reveal_tx.txid(), None))?
+        .is_some();
+
+    if !reveal_known {
+      for input in &reveal_tx.input {
+        let outpoint = input.previous_output;
+
+        if outpoint.txid != bundle.commit {
+          continue;
+        }
+
+        ensure!(
+          bitcoin_client
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .is_some(),
+          "commit output {outpoint} is spent or commit transaction {} is not in mempool or chain",
+          bundle.commit,
+        );
+      }
+    }
+
+    let reveal = wallet.send_raw_transaction(&consensus::encode::serialize(&reveal_tx))?;
+
+    Ok(Some(Box::new(Output {
+      commit: bundle.commit,
+      reveal,
+    })))
+  }
+}
@@ -216,7 +216,6 @@ impl Send {
       "sending runes with `ord send` requires index created with `--index-runes` flag",
     );
 
-    let inscriptions = wallet.inscriptions();
     let runic_outputs = wallet.get_runic_outputs()?;
     let bitcoin_client = wallet.bitcoin_client();
 
@@ -228,10 +227,7 @@ impl Send {
 
     let amount = decimal.to_integer(entry.divisibility)?;
 
-    let inscribed_outputs = inscriptions
-      .keys()
-      .map(|satpoint| satpoint.outpoint)
-      .collect::<HashSet<OutPoint>>();
+    let inscribed_outputs = wallet.inscribed_utxos();
 
     let mut input_runes = 0;
     let mut input = Vec::new();
@@ -253,15 +249,19 @@ impl Send {
       }
     }
 
-    ensure! {
-      input_runes >= amount,
-      "insufficient `{}` balance, only {} in wallet",
-      spaced_rune,
-      Pile {
-        amount: input_runes,
-        divisibility: entry.divisibility,
-        symbol: entry.symbol
-      },
+    if input_runes < amount {
+      return Err(
+        InsufficientFunds(format!(
+          "insufficient `{}` balance, only {} in wallet",
+          spaced_rune,
+          Pile {
+            amount: input_runes,
+            divisibility: entry.divisibility,
+            symbol: entry.symbol
+          },
+        ))
+        .into(),
+      );
     }
 
     let runestone = Runestone {
@@ -1,24 +1,869 @@
-use super::*;
+use {super::*, bitcoin::secp256k1::XOnlyPublicKey, is_terminal::IsTerminal};
 
 #[derive(Debug, Parser)]
 pub(super) struct SharedArgs {
   #[arg(
     long,
+    help = "Allow inscribing content identical to an inscription already in wallet."
+  )]
+  pub(crate) allow_duplicate: bool,
+  #[arg(
+    long,
+    help = "Allow inscribing empty files. The inscription body is encoded as a body tag followed by no content."
+  )]
+  pub(crate) allow_empty: bool,
+  #[arg(
+    long = "allow-content-type",
+    value_name = "CONTENT_TYPE",
+    help = "Only allow inscribing content of type <CONTENT_TYPE>, which may end in `/*` to match any subtype. May be given multiple times."
+  )]
+  pub(crate) allow_content_types: Vec<String>,
+  #[arg(long, help = "Allow inscribing on sats rarer than common.")]
+  pub(crate) allow_rare: bool,
+  #[arg(
+    long = "allow-spending-inscription",
+    value_name = "INSCRIPTION_ID",
+    help = "Allow commit and reveal transactions to spend the output holding <INSCRIPTION_ID>. Outputs holding other inscriptions, except the parent and reinscribed sats, are never spent. May be given multiple times."
+  )]
+  pub(crate) allowed_inscriptions: Vec<InscriptionId>,
+  #[arg(
+    long,
+    default_value = "0",
+    value_name = "CONFIRMATIONS",
+    conflicts_with_all = ["cold_reveal_file", "dry_run", "prepare_only", "psbt", "use_package_relay"],
+    help = "Wait for commit transaction to have <CONFIRMATIONS> confirmations before broadcasting reveal transaction, printing progress to stderr. Aborts if the commit transaction is replaced or dropped while waiting."
+  )]
+  pub(crate) await_commit_confirmations: u16,
+  #[arg(
+    long,
+    help = "Wait for reveal transaction to be mined and indexed, and include inscription numbers in output. Waits until --timeout expires, or for one hour if --timeout is not given."
+  )]
+  pub(crate) await_number: bool,
+  #[arg(
+    long,
+    value_name = "FILE",
+    conflicts_with = "reuse_change",
+    help = "Use addresses in <FILE>, one per line, for change instead of generating new ones. The first two addresses receive commit transaction change, and the third, if present, reveal transaction change. Addresses holding inscriptions are refused."
+  )]
+  pub(crate) change_addresses: Option<PathBuf>,
+  #[arg(
+    long,
+    value_delimiter = ',',
+    help = "Split commit transaction change into outputs of <CHANGE_SPLIT> amounts, separated by commas. The last output also receives any remaining change."
+  )]
+  pub(crate) change_split: Vec<Amount>,
+  #[arg(
+    long,
+    conflicts_with = "allow_rare",
+    help = "Warn if the rarity of the inscribed sat cannot be checked because the index has no sat index. Rare sats are only refused when the index has a sat index."
+  )]
+  pub(crate) check_rarity: bool,
+  #[arg(
+    long,
+    requires = "cold_reveal_key",
+    help = "Write unsigned reveal transaction for --cold-reveal-key to <COLD_REVEAL_FILE>."
+  )]
+  pub(crate) cold_reveal_file: Option<PathBuf>,
+  #[arg(
+    long,
+    requires = "cold_reveal_file",
+    conflicts_with_all = ["key_seed", "prepare_only", "psbt", "recovery_key_file"],
+    help = "Commit to a reveal script signed by x-only public key <COLD_REVEAL_KEY>, created on an offline machine with `ord cold-reveal key`. Only the commit transaction is broadcast. The unsigned reveal transaction is written to <COLD_REVEAL_FILE>, to be signed offline with `ord cold-reveal sign`."
+  )]
+  pub(crate) cold_reveal_key: Option<XOnlyPublicKey>,
+  #[arg(
+    long,
+    value_parser = positive_fee_rate,
     help = "Use <COMMIT_FEE_RATE> sats/vbyte for commit transaction.\nDefaults to <FEE_RATE> if unset."
   )]
   pub(crate) commit_fee_rate: Option<FeeRate>,
+  #[arg(
+    long,
+    value_name = "LOCKTIME",
+    help = "Set commit transaction locktime to <LOCKTIME>, a block height if below 500000000 and a UNIX timestamp otherwise. The mempool rejects transactions whose locktime has not passed, so a future <LOCKTIME> requires --prepare-only, --psbt, or --dry-run, and the transactions can be broadcast once it passes. Commit inputs still signal replaceability unless --no-rbf is given."
+  )]
+  pub(crate) commit_locktime: Option<u32>,
+  #[arg(
+    long,
+    help = "Place commit output at vout 0 of commit transaction, with change following."
+  )]
+  pub(crate) commit_vout_first: bool,
+  #[arg(
+    long,
+    help = "Print a summary and require typing `yes` before broadcasting transactions."
+  )]
+  pub(crate) confirm: bool,
+  #[arg(
+    long,
+    value_name = "MAX_INPUTS",
+    help = "Sweep up to <MAX_INPUTS> additional cardinal UTXOs, smallest first, into commit transaction change, even if they are not needed to fund it. UTXOs holding inscriptions or runes, and UTXOs worth less than the fee to spend them, are never swept."
+  )]
+  pub(crate) consolidate: Option<usize>,
+  #[arg(
+    long,
+    value_name = "AMOUNT",
+    requires = "consolidate",
+    help = "Stop sweeping UTXOs with --consolidate before the commit transaction fee would exceed <AMOUNT>."
+  )]
+  pub(crate) consolidate_max_fee: Option<Amount>,
   #[arg(long, help = "Compress inscription content with brotli.")]
   pub(crate) compress: bool,
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
-  pub(crate) fee_rate: FeeRate,
+  #[arg(
+    long,
+    conflicts_with = "compress",
+    help = "Compress inscription content with brotli or gzip, whichever is smaller. Content is left uncompressed if neither makes it smaller."
+  )]
+  pub(crate) compress_auto: bool,
+  #[arg(
+    long,
+    help = "Print reveal transaction script-path public key, sighash, and signature to stderr as hex."
+  )]
+  pub(crate) debug_sigs: bool,
+  #[arg(
+    long = "deny-content-type",
+    value_name = "CONTENT_TYPE",
+    help = "Refuse to inscribe content of type <CONTENT_TYPE>, which may end in `/*` to match any subtype. May be given multiple times."
+  )]
+  pub(crate) deny_content_types: Vec<String>,
+  #[arg(
+    long,
+    conflicts_with = "fee_rate",
+    help = "Use fee rate estimated by Bitcoin Core to confirm within <CONFIRMATION_TARGET> blocks."
+  )]
+  pub(crate) confirmation_target: Option<u16>,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB. Defaults to the `fee_rate` setting, which can be set with the `ORD_FEE_RATE` environment variable or `fee_rate` in the config file, or if unset, to 10 sats/vB on mainnet and 1 sat/vB on test networks."
+  )]
+  pub(crate) fee_rate: Option<FeeRate>,
+  #[arg(
+    long,
+    conflicts_with_all = ["confirmation_target", "fee_rate"],
+    help = "Use fee rate of mempool transactions at the percentile of mempool vsize given by --mempool-percentile, 50 by default, ordered by fee rate. Requires a node that reports transaction fees in verbose `getrawmempool` output. Uses the mempool minimum fee rate, but at least 1 sat/vB, if the mempool is empty."
+  )]
+  pub(crate) fee_rate_from_mempool: bool,
+  #[arg(
+    long,
+    value_name = "PHASE=RATE,...",
+    conflicts_with_all = ["commit_fee_rate", "reveal_fee_rate"],
+    help = "Use per-phase fee rates in sats/vB, given as comma-separated <PHASE>=<RATE> pairs, for example `commit=5,reveal=10`. The only phases are `commit` and `reveal`, since inscribing creates no other transactions. Phases without a rate use <FEE_RATE>."
+  )]
+  pub(crate) fee_rates: Option<FeeRates>,
   #[arg(long, help = "Don't sign or broadcast transactions.")]
   pub(crate) dry_run: bool,
+  #[arg(
+    long,
+    value_name = "THRESHOLD",
+    help = "Add commit transaction change of less than <THRESHOLD> to the fee instead of creating a change output."
+  )]
+  pub(crate) dust_change_to_fee: Option<Amount>,
+  #[arg(
+    long,
+    help = "Include reveal transaction private key in WIF format in output, so the reveal transaction can be rebuilt and re-signed. Anyone with the key can spend the commit output, so only allowed on regtest and signet."
+  )]
+  pub(crate) emit_reveal_key: bool,
+  #[arg(
+    long = "exclude-utxo",
+    value_name = "OUTPOINT",
+    help = "Never spend <OUTPOINT> to fund transactions. May be given multiple times."
+  )]
+  pub(crate) exclude_utxos: Vec<OutPoint>,
+  #[arg(
+    long,
+    requires = "confirmation_target",
+    help = "Use fee rate of <FALLBACK_FEE_RATE> sats/vB if Bitcoin Core cannot estimate fee rate for <CONFIRMATION_TARGET>. [default: 1.0]"
+  )]
+  pub(crate) fallback_fee_rate: Option<FeeRate>,
+  #[arg(
+    long,
+    help = "Only fund transactions with wallet outputs received to addresses with label <FROM_LABEL>."
+  )]
+  pub(crate) from_label: Option<String>,
+  #[arg(
+    long,
+    help = "Pay reveal transaction fee from a separate cardinal input instead of the commit output."
+  )]
+  pub(crate) fund_reveal_separately: bool,
+  #[arg(long, help = "Do not ask for confirmation when passing --no-limit.")]
+  pub(crate) i_know_what_im_doing: bool,
+  #[arg(
+    long,
+    conflicts_with_all = ["cold_reveal_file", "key_seed", "no_backup"],
+    help = "Derive reveal key from <IDEMPOTENCY_KEY>, a secret derived from the wallet's private keys, and the inscriptions, destinations, and postages, so a retried run with the same key and wallet state builds the same commit and reveal transactions. Destination and change addresses not given explicitly are wallet addresses labeled with <IDEMPOTENCY_KEY>, which are reused by retries. Refuse to inscribe if a wallet transaction already funds the resulting commit address with a different transaction."
+  )]
+  pub(crate) idempotency_key: Option<String>,
+  #[arg(
+    long,
+    help = "Derive reveal key from 32-byte hex <KEY_SEED> instead of generating it randomly, making commit and reveal transactions reproducible. Never reuse a seed for different inscriptions, since anyone who learns it can spend the commit output."
+  )]
+  pub(crate) key_seed: Option<String>,
+  #[arg(
+    long,
+    default_value = "50",
+    value_parser = percentile,
+    requires = "fee_rate_from_mempool",
+    help = "Use fee rate at <MEMPOOL_PERCENTILE> of mempool with --fee-rate-from-mempool, from 0 to 100."
+  )]
+  pub(crate) mempool_percentile: f64,
+  #[arg(
+    long,
+    help = "Include <LABEL> in the label of the recovery key imported into the Bitcoin Core wallet."
+  )]
+  pub(crate) label: Option<String>,
+  #[arg(
+    long,
+    help = "Never fund transactions with cardinal outputs worth more than <MAX_FUNDING_UTXO>. An output given with --satpoint may still be inscribed."
+  )]
+  pub(crate) max_funding_utxo: Option<Amount>,
+  #[arg(
+    long,
+    help = "Refuse to create inscription outputs with less than <MIN_POSTAGE>. Outputs below the dust value of their script are always refused."
+  )]
+  pub(crate) min_postage: Option<Amount>,
   #[arg(long, alias = "nobackup", help = "Do not back up recovery key.")]
   pub(crate) no_backup: bool,
   #[arg(
     long,
     alias = "nolimit",
-    help = "Do not check that transactions are equal to or below the MAX_STANDARD_TX_WEIGHT of 400,000 weight units. Transactions over this limit are currently nonstandard and will not be relayed by bitcoind in its default configuration. Do not use this flag unless you understand the implications."
+    help = "Do not check that transactions are equal to or below the MAX_STANDARD_TX_WEIGHT of 400,000 weight units. Transactions over this limit are currently nonstandard and will not be relayed by bitcoind in its default configuration. Requires confirmation, either interactively or with --i-know-what-im-doing. Do not use this flag unless you understand the implications."
   )]
   pub(crate) no_limit: bool,
+  #[arg(
+    long,
+    help = "Don't wait for `ord server` to index the latest block before inscribing. Inscription selection and duplicate checks use whatever `ord server` has already indexed, which may be stale."
+  )]
+  pub(crate) no_index_update: bool,
+  #[arg(
+    long,
+    help = "Don't signal replaceability in commit and reveal transactions. Non-replaceable transactions cannot be fee-bumped later, so a transaction stuck at too low a fee rate can only wait or be evicted from the mempool."
+  )]
+  pub(crate) no_rbf: bool,
+  #[arg(
+    long,
+    conflicts_with_all = ["cold_reveal_file", "dry_run", "prepare_only", "psbt", "signer_command"],
+    help = "Print a plan of the commit and reveal transactions, with the inscribed satpoint, funding outputs, change outputs, fees, postages, recovery key fingerprint, and predicted inscription IDs, without signing or broadcasting transactions or backing up the recovery key. With --key-seed, --destination, and --change-addresses, a later run with the same wallet outputs creates the predicted inscription IDs."
+  )]
+  pub(crate) plan: bool,
+  #[arg(
+    long,
+    conflicts_with = "dry_run",
+    help = "Don't sign or broadcast commit transaction. Output unsigned commit PSBT for external signing and signed reveal PSBT to broadcast after commit transaction."
+  )]
+  pub(crate) psbt: bool,
+  #[arg(
+    long,
+    value_name = "BUNDLE",
+    conflicts_with_all = ["dry_run", "psbt"],
+    help = "Sign commit and reveal transactions and write them with the recovery key to new file at <BUNDLE> without broadcasting. Broadcast later with `ord wallet broadcast-bundle`."
+  )]
+  pub(crate) prepare_only: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Write commit transaction recovery key to new file at <RECOVERY_KEY_FILE>. The key is encrypted with --recovery-key-passphrase, and written in plaintext if it is not given."
+  )]
+  pub(crate) recovery_key_file: Option<PathBuf>,
+  #[arg(
+    long,
+    env = "ORD_RECOVERY_KEY_PASSPHRASE",
+    hide_env_values = true,
+    help = "Encrypt recovery key written to <RECOVERY_KEY_FILE> or saved with a failed reveal transaction with <RECOVERY_KEY_PASSPHRASE>. Prefer setting the environment variable, which does not appear in shell history or process lists."
+  )]
+  pub(crate) recovery_key_passphrase: Option<String>,
+  #[arg(
+    long,
+    help = "Use unused wallet addresses for change before generating new ones. Never reuses addresses holding inscriptions."
+  )]
+  pub(crate) reuse_change: bool,
+  #[arg(
+    long,
+    value_name = "HEX",
+    value_parser = annex,
+    conflicts_with = "cold_reveal_key",
+    help = "Append taproot annex <HEX>, which must begin with 0x50, to the reveal transaction's commit input witness. Annexes are non-standard, so the reveal transaction will not be relayed and must be submitted directly to a miner."
+  )]
+  // qualified path stops clap from parsing the annex as a list of bytes
+  pub(crate) reveal_annex: Option<std::vec::Vec<u8>>,
+  #[arg(
+    long,
+    value_name = "BLOCKS",
+    value_parser = clap::value_parser!(u16).range(1..),
+    conflicts_with_all = ["no_rbf", "prepare_only"],
+    help = "Lock reveal transaction until commit transaction has <BLOCKS> confirmations, by setting reveal input sequence numbers to a relative locktime. Requires --reveal-tx-version 2. Relative locktimes always signal replaceability, so the reveal transaction can be fee-bumped."
+  )]
+  pub(crate) reveal_csv: Option<u16>,
+  #[arg(
+    long,
+    value_parser = positive_fee_rate,
+    help = "Use <REVEAL_FEE_RATE> sats/vbyte for reveal transaction.\nDefaults to <FEE_RATE> if unset."
+  )]
+  pub(crate) reveal_fee_rate: Option<FeeRate>,
+  #[arg(
+    long,
+    default_value = "2",
+    value_name = "VERSION",
+    help = "Use <VERSION> as reveal transaction version. Etching requires version 2."
+  )]
+  pub(crate) reveal_tx_version: i32,
+  #[arg(
+    long,
+    value_enum,
+    ignore_case = true,
+    default_value = "default",
+    help = "Sign reveal transaction with <SIGHASH> type."
+  )]
+  pub(crate) sighash: batch::Sighash,
+  #[arg(
+    long,
+    value_name = "CMD",
+    conflicts_with_all = ["dry_run", "psbt"],
+    help = "Sign commit transaction by running <CMD> with the shell instead of with the Bitcoin Core wallet. The base64-encoded unsigned commit PSBT, with witness UTXOs, is written to <CMD>'s stdin, and the signed commit transaction is read from its stdout, as a base64-encoded finalized PSBT or as hex. The signed transaction is checked with testmempoolaccept before broadcasting."
+  )]
+  pub(crate) signer_command: Option<String>,
+  #[arg(
+    long,
+    help = "Don't check that commit and reveal transactions would be accepted by mempool before broadcasting."
+  )]
+  pub(crate) skip_mempool_check: bool,
+  #[arg(
+    long,
+    value_name = "SECONDS",
+    value_parser = clap::value_parser!(u64).range(1..),
+    help = "Abort if inscribing takes longer than <SECONDS>. Requests to Bitcoin Core and `ord server`, including waiting for `ord server` to synchronize, time out after <SECONDS>, and the deadline is checked between phases, such as building, signing, and broadcasting transactions. Never aborts between broadcasting commit and reveal transactions without reporting the unbroadcast reveal transaction."
+  )]
+  pub(crate) timeout: Option<u64>,
+  #[arg(
+    long,
+    conflicts_with_all = ["cold_reveal_file", "dry_run", "prepare_only", "psbt", "reveal_csv"],
+    help = "Broadcast commit and reveal transactions together as a package with `submitpackage`, so the reveal transaction fee can pay for a commit transaction below the minimum relay fee rate. Falls back to broadcasting them separately if Bitcoin Core is older than 28.0."
+  )]
+  pub(crate) use_package_relay: bool,
+  #[arg(
+    long,
+    help = "Check that GIF, JPEG, PNG, and WEBP inscription content is well-formed and not truncated before creating transactions."
+  )]
+  pub(crate) validate_content: bool,
+  #[arg(
+    long,
+    value_name = "RATIO",
+    default_value = "1000000",
+    value_parser = clap::value_parser!(u64).range(1..),
+    help = "Warn if the commit transaction spends an output worth more than <RATIO> times the total postage and fees."
+  )]
+  pub(crate) warn_funding_utxo_ratio: u64,
+  #[arg(
+    long,
+    help = "Broadcast transactions without asking for --confirm confirmation."
+  )]
+  pub(crate) yes: bool,
+}
+
+impl SharedArgs {
+  /// Commit transaction fee rate, from `--fee-rates` or `--commit-fee-rate`,
+  /// defaulting to `fee_rate`.
+  pub(crate) fn commit_fee_rate(&self, fee_rate: FeeRate) -> FeeRate {
+    self
+      .fee_rates
+      .and_then(|fee_rates| fee_rates.commit)
+      .or(self.commit_fee_rate)
+      .unwrap_or(fee_rate)
+  }
+
+  /// Reveal transaction fee rate, from `--fee-rates` or `--reveal-fee-rate`,
+  /// defaulting to `fee_rate`.
+  pub(crate) fn reveal_fee_rate(&self, fee_rate: FeeRate) -> FeeRate {
+    self
+      .fee_rates
+      .and_then(|fee_rates| fee_rates.reveal)
+      .or(self.reveal_fee_rate)
+      .unwrap_or(fee_rate)
+  }
+
+  pub(crate) fn no_limit(&self) -> Result<bool> {
+    if self.no_limit {
+      if !self.i_know_what_im_doing {
+        ensure!(
+          io::stdin().is_terminal(),
+          "--no-limit requires --i-know-what-im-doing when not running interactively"
+        );
+
+        eprint!(
+          "Transactions over MAX_STANDARD_TX_WEIGHT are nonstandard and will not be relayed by bitcoind in its default configuration. Continue? [y/N] "
+        );
+
+        let mut buffer = String::new();
+        io::stdin().read_line(&mut buffer)?;
+
+        ensure!(
+          matches!(buffer.trim(), "y" | "Y" | "yes"),
+          "aborted by user"
+        );
+      }
+
+      Warning::NoLimitCheck.emit();
+    }
+
+    Ok(self.no_limit)
+  }
+
+  /// Load `--change-addresses` file, one address per line. Blank lines are
+  /// ignored.
+  pub(crate) fn change_addresses(&self, wallet: &Wallet) -> Result<Option<Vec<Address>>> {
+    let Some(path) = &self.change_addresses else {
+      return Ok(None);
+    };
+
+    let chain = wallet.chain();
+
+    let inscribed = wallet.inscribed_script_pubkeys();
+
+    let mut addresses = Vec::new();
+
+    for (i, line) in fs::read_to_string(path)
+      .with_context(|| format!("failed to read change addresses file `{}`", path.display()))?
+      .lines()
+      .enumerate()
+    {
+      let line = line.trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      let address = line.parse::<Address<NetworkUnchecked>>().with_context(|| {
+        format!(
+          "invalid change address `{line}` on line {} of `{}`",
+          i + 1,
+          path.display()
+        )
+      })?;
+
+      ensure!(
+        address.is_valid_for_network(chain.network()),
+        "change address {} on line {} of `{}` is not valid for {chain}",
+        address.clone().assume_checked(),
+        i + 1,
+        path.display(),
+      );
+
+      let address = address.assume_checked();
+
+      ensure!(
+        !inscribed.contains(&address.script_pubkey()),
+        "change address {address} on line {} of `{}` holds inscriptions",
+        i + 1,
+        path.display(),
+      );
+
+      addresses.push(address);
+    }
+
+    ensure!(
+      addresses.len() >= 2,
+      "change addresses file `{}` must contain at least 2 addresses but contains {}",
+      path.display(),
+      addresses.len(),
+    );
+
+    Ok(Some(addresses))
+  }
+
+  pub(crate) fn compression(&self) -> Compression {
+    Compression::from_flags(self.compress, self.compress_auto)
+  }
+
+  pub(crate) fn fee_rate(&self, wallet: &Wallet) -> Result<FeeRate> {
+    if self.fee_rate_from_mempool {
+      return self.mempool_fee_rate(wallet);
+    }
+
+    let Some(confirmation_target) = self.confirmation_target else {
+      return Ok(
+        self
+          .fee_rate
+          .or(wallet.default_fee_rate())
+          .unwrap_or_else(|| wallet.chain().default_fee_rate()),
+      );
+    };
+
+    let estimate = wallet
+      .bitcoin_client()
+      .estimate_smart_fee(confirmation_target, None)?;
+
+    match estimate.fee_rate {
+      Some(fee_rate) => FeeRate::try_from(fee_rate.to_sat() as f64 / 1000.0),
+      None => {
+        let fallback_fee_rate = self
+          .fallback_fee_rate
+          .unwrap_or(FeeRate::try_from(1.0).unwrap());
+
+        Warning::FallbackFeeRate {
+          confirmation_target,
+          fee_rate: fallback_fee_rate,
+        }
+        .emit();
+
+        Ok(fallback_fee_rate)
+      }
+    }
+  }
+
+  fn mempool_fee_rate(&self, wallet: &Wallet) -> Result<FeeRate> {
+    #[derive(Deserialize)]
+    struct Fees {
+      base: f64,
+    }
+
+    #[derive(Deserialize)]
+    struct MempoolEntry {
+      vsize: u64,
+      fees: Fees,
+    }
+
+    #[derive(Deserialize)]
+    struct MempoolInfo {
+      mempoolminfee: f64,
+    }
+
+    let entries = wallet
+      .bitcoin_client()
+      .call::<BTreeMap<Txid, MempoolEntry>>("getrawmempool", &[true.into()])?
+      .into_values()
+      .map(|entry| Ok((Amount::from_btc(entry.fees.base)?.to_sat(), entry.vsize)))
+      .collect::<Result<Vec<(u64, u64)>>>()?;
+
+    if let Some(fee_rate) = percentile_fee_rate(entries, self.mempool_percentile) {
+      return Ok(fee_rate);
+    }
+
+    let mempool_min_fee = Amount::from_btc(
+      wallet
+        .bitcoin_client()
+        .call::<MempoolInfo>("getmempoolinfo", &[])?
+        .mempoolminfee,
+    )?;
+
+    let fee_rate = FeeRate::try_from((mempool_min_fee.to_sat() as f64 / 1000.0).max(1.0))?;
+
+    Warning::EmptyMempool { fee_rate }.emit();
+
+    Ok(fee_rate)
+  }
+
+  pub(crate) fn idempotency_secret(&self, wallet: &Wallet) -> Result<Option<[u8; 32]>> {
+    if self.idempotency_key.is_none() {
+      return Ok(None);
+    }
+
+    wallet.idempotency_secret().map(Some)
+  }
+
+  pub(crate) fn key_seed(&self) -> Result<Option<[u8; 32]>> {
+    self
+      .key_seed
+      .as_deref()
+      .map(|seed| {
+        <[u8; 32]>::try_from(hex::decode(seed)?)
+          .map_err(|seed| anyhow!("key seed must be 32 bytes but is {} bytes", seed.len()))
+      })
+      .transpose()
+  }
+
+  pub(crate) fn check_empty(&self, inscriptions: &[Inscription]) -> Result {
+    if self.allow_empty {
+      return Ok(());
+    }
+
+    ensure!(
+      inscriptions
+        .iter()
+        .all(|inscription| inscription.content_length() != Some(0)),
+      "inscription content is empty, use --allow-empty to inscribe it anyway"
+    );
+
+    Ok(())
+  }
+
+  pub(crate) fn check_content_types(&self, inscriptions: &[Inscription]) -> Result {
+    if self.allow_content_types.is_empty() && self.deny_content_types.is_empty() {
+      return Ok(());
+    }
+
+    for inscription in inscriptions {
+      let Some(content_type) = inscription.content_type() else {
+        ensure!(
+          self.allow_content_types.is_empty(),
+          "inscription has no content type but --allow-content-type was given"
+        );
+        continue;
+      };
+
+      if let Some(pattern) = self
+        .deny_content_types
+        .iter()
+        .find(|pattern| content_type_matches(pattern, content_type))
+      {
+        bail!("content type `{content_type}` is denied by --deny-content-type `{pattern}`");
+      }
+
+      ensure!(
+        self.allow_content_types.is_empty()
+          || self
+            .allow_content_types
+            .iter()
+            .any(|pattern| content_type_matches(pattern, content_type)),
+        "content type `{content_type}` is not allowed by --allow-content-type"
+      );
+    }
+
+    Ok(())
+  }
+}
+
+/// Fee rates for individual phases given with `--fee-rates`. Phases without a
+/// rate are `None`.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub(crate) struct FeeRates {
+  pub(crate) commit: Option<FeeRate>,
+  pub(crate) reveal: Option<FeeRate>,
+}
+
+impl FromStr for FeeRates {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let mut fee_rates = Self::default();
+
+    for entry in s.split(',') {
+      let (phase, rate) = entry
+        .split_once('=')
+        .ok_or_else(|| anyhow!("fee rate `{entry}` must be of the form <PHASE>=<RATE>"))?;
+
+      let phase = phase.trim();
+
+      let fee_rate = match phase {
+        "commit" => &mut fee_rates.commit,
+        "reveal" => &mut fee_rates.reveal,
+        _ => bail!("unknown fee rate phase `{phase}`, phases are `commit` and `reveal`"),
+      };
+
+      ensure!(fee_rate.is_none(), "duplicate fee rate for phase `{phase}`");
+
+      *fee_rate = Some(
+        positive_fee_rate(rate.trim())
+          .with_context(|| format!("invalid fee rate for phase `{phase}`"))?,
+      );
+    }
+
+    Ok(fee_rates)
+  }
+}
+
+fn positive_fee_rate(s: &str) -> Result<FeeRate> {
+  let fee_rate = s.parse::<FeeRate>()?;
+
+  ensure!(fee_rate.n() > 0.0, "fee rate must be positive");
+
+  Ok(fee_rate)
+}
+
+fn annex(s: &str) -> Result<Vec<u8>> {
+  let annex = hex::decode(s)?;
+
+  ensure!(
+    annex.first() == Some(&0x50),
+    "annex must begin with the taproot annex prefix 0x50"
+  );
+
+  Ok(annex)
+}
+
+fn content_type_matches(pattern: &str, content_type: &str) -> bool {
+  let essence = content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_lowercase();
+
+  let pattern = pattern.trim().to_lowercase();
+
+  match pattern.strip_suffix('*') {
+    Some(prefix) => essence.starts_with(prefix),
+    None => essence == pattern,
+  }
+}
+
+fn percentile(s: &str) -> Result<f64> {
+  let percentile = s.parse::<f64>()?;
+
+  ensure!(
+    (0.0..=100.0).contains(&percentile),
+    "percentile must be between 0 and 100"
+  );
+
+  Ok(percentile)
+}
+
+/// Fee rate of the transaction at `percentile` of `entries`, a list of fees and
+/// vsizes, weighted by vsize and ordered by fee rate.
+fn percentile_fee_rate(mut entries: Vec<(u64, u64)>, percentile: f64) -> Option<FeeRate> {
+  entries.retain(|(_fee, vsize)| *vsize > 0);
+
+  entries.sort_by(|(a_fee, a_vsize), (b_fee, b_vsize)| {
+    (u128::from(*a_fee) * u128::from(*b_vsize)).cmp(&(u128::from(*b_fee) * u128::from(*a_vsize)))
+  });
+
+  let total = entries.iter().map(|(_fee, vsize)| vsize).sum::<u64>();
+
+  let target = total as f64 * percentile / 100.0;
+
+  let mut cumulative = 0;
+
+  for (fee, vsize) in entries {
+    cumulative += vsize;
+
+    if cumulative as f64 >= target {
+      return FeeRate::try_from(fee as f64 / vsize as f64).ok();
+    }
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn content_type_patterns() {
+    assert!(content_type_matches("image/png", "image/png"));
+    assert!(content_type_matches("IMAGE/PNG", "image/png"));
+    assert!(content_type_matches("image/*", "image/png"));
+    assert!(content_type_matches("*", "image/png"));
+    assert!(content_type_matches(
+      "text/plain",
+      "text/plain;charset=utf-8"
+    ));
+    assert!(!content_type_matches("image/png", "image/jpeg"));
+    assert!(!content_type_matches("image/*", "text/plain"));
+    assert!(!content_type_matches("image/*", "imagex/png"));
+  }
+
+  #[test]
+  fn fee_rates_must_be_positive() {
+    assert_eq!(positive_fee_rate("2.5").unwrap().n(), 2.5);
+    assert_eq!(
+      positive_fee_rate("0").unwrap_err().to_string(),
+      "fee rate must be positive"
+    );
+    assert!(positive_fee_rate("-1").is_err());
+  }
+
+  #[test]
+  fn percentiles_must_be_in_range() {
+    assert_eq!(percentile("0").unwrap(), 0.0);
+    assert_eq!(percentile("95.5").unwrap(), 95.5);
+    assert_eq!(percentile("100").unwrap(), 100.0);
+    assert_eq!(
+      percentile("101").unwrap_err().to_string(),
+      "percentile must be between 0 and 100"
+    );
+    assert!(percentile("-1").is_err());
+  }
+
+  #[test]
+  fn percentile_fee_rate_is_weighted_by_vsize() {
+    assert_eq!(percentile_fee_rate(Vec::new(), 50.0), None);
+    assert_eq!(percentile_fee_rate(vec![(1000, 0)], 50.0), None);
+
+    let entries = vec![(30_000, 1000), (1000, 1000), (20_000, 2000)];
+
+    assert_eq!(percentile_fee_rate(entries.clone(), 0.0).unwrap().n(), 1.0);
+    assert_eq!(percentile_fee_rate(entries.clone(), 25.0).unwrap().n(), 1.0);
+    assert_eq!(
+      percentile_fee_rate(entries.clone(), 50.0).unwrap().n(),
+      10.0
+    );
+    assert_eq!(
+      percentile_fee_rate(entries.clone(), 75.0).unwrap().n(),
+      10.0
+    );
+    assert_eq!(percentile_fee_rate(entries, 100.0).unwrap().n(), 30.0);
+  }
+
+  #[test]
+  fn fee_rates() {
+    assert_eq!(
+      "commit=5,reveal=10.5".parse::<FeeRates>().unwrap(),
+      FeeRates {
+        commit: Some(FeeRate::try_from(5.0).unwrap()),
+        reveal: Some(FeeRate::try_from(10.5).unwrap()),
+      },
+    );
+
+    assert_eq!(
+      " reveal = 3 ".parse::<FeeRates>().unwrap(),
+      FeeRates {
+        commit: None,
+        reveal: Some(FeeRate::try_from(3.0).unwrap()),
+      },
+    );
+
+    assert_eq!(
+      "commit".parse::<FeeRates>().unwrap_err().to_string(),
+      "fee rate `commit` must be of the form <PHASE>=<RATE>",
+    );
+
+    assert_eq!(
+      "send=7".parse::<FeeRates>().unwrap_err().to_string(),
+      "unknown fee rate phase `send`, phases are `commit` and `reveal`",
+    );
+
+    assert_eq!(
+      "commit=1,commit=2"
+        .parse::<FeeRates>()
+        .unwrap_err()
+        .to_string(),
+      "duplicate fee rate for phase `commit`",
+    );
+
+    assert_eq!(
+      "reveal=0".parse::<FeeRates>().unwrap_err().to_string(),
+      "invalid fee rate for phase `reveal`",
+    );
+  }
+
+  #[test]
+  fn fee_rates_default_to_fee_rate() {
+    let shared = |args: &[&str]| {
+      let arguments = Arguments::try_parse_from(
+        ["ord", "wallet", "inscribe", "--file", "foo.txt"]
+          .iter()
+          .chain(args),
+      )
+      .unwrap();
+
+      match arguments.subcommand {
+        crate::subcommand::Subcommand::Wallet(wallet) => match wallet.subcommand {
+          crate::subcommand::wallet::Subcommand::Inscribe(inscribe) => inscribe.shared,
+          subcommand => panic!("unexpected wallet subcommand: {subcommand:?}"),
+        },
+        subcommand => panic!("unexpected subcommand: {subcommand:?}"),
+      }
+    };
+
+    let fee_rate = FeeRate::try_from(2.0).unwrap();
+
+    let args = shared(&["--fee-rates", "commit=5"]);
+    assert_eq!(args.commit_fee_rate(fee_rate).n(), 5.0);
+    assert_eq!(args.reveal_fee_rate(fee_rate).n(), 2.0);
+
+    let args = shared(&["--fee-rates", "reveal=7"]);
+    assert_eq!(args.commit_fee_rate(fee_rate).n(), 2.0);
+    assert_eq!(args.reveal_fee_rate(fee_rate).n(), 7.0);
+
+    let args = shared(&["--commit-fee-rate", "3"]);
+    assert_eq!(args.commit_fee_rate(fee_rate).n(), 3.0);
+    assert_eq!(args.reveal_fee_rate(fee_rate).n(), 2.0);
+  }
 }
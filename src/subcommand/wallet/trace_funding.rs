@@ -0,0 +1,108 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct TraceFunding {
+  #[arg(help = "Trace commit and reveal transaction outputs of <INSCRIPTION>.")]
+  inscription: InscriptionId,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub commit: Vec<TracedOutput>,
+  pub reveal: Vec<TracedOutput>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TracedOutput {
+  pub inscriptions: Vec<InscriptionId>,
+  pub output: OutPoint,
+  pub sat_ranges: Option<Vec<(u64, u64)>>,
+  pub unavailable: Option<String>,
+  pub value: u64,
+}
+
+impl TraceFunding {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let txid = self.inscription.txid;
+
+    let (reveal, reveal_confirmed) = Self::get_transaction(&wallet, "reveal", txid)?;
+
+    let envelope = ParsedEnvelope::from_transaction(&reveal)
+      .into_iter()
+      .nth(self.inscription.index.try_into().unwrap())
+      .ok_or_else(|| {
+        anyhow!(
+          "transaction {txid} does not contain inscription {}",
+          self.inscription
+        )
+      })?;
+
+    let commit_txid = reveal.input[envelope.input as usize].previous_output.txid;
+
+    let (commit, commit_confirmed) = Self::get_transaction(&wallet, "commit", commit_txid)?;
+
+    Ok(Some(Box::new(Output {
+      commit: Self::trace(&wallet, &commit, commit_confirmed)?,
+      reveal: Self::trace(&wallet, &reveal, reveal_confirmed)?,
+    })))
+  }
+
+  /// Fetch a transaction with `gettransaction`, which, unlike
+  /// `getrawtransaction`, does not require Bitcoin Core's `-txindex`, and
+  /// whether it is confirmed.
+  fn get_transaction(wallet: &Wallet, name: &str, txid: Txid) -> Result<(Transaction, bool)> {
+    let info = wallet
+      .bitcoin_client()
+      .get_transaction(&txid, Some(true))
+      .with_context(|| format!("{name} transaction {txid} not found in wallet"))?;
+
+    Ok((info.transaction()?, info.info.confirmations > 0))
+  }
+
+  fn trace(
+    wallet: &Wallet,
+    transaction: &Transaction,
+    confirmed: bool,
+  ) -> Result<Vec<TracedOutput>> {
+    let txid = transaction.txid();
+
+    let mut outputs = Vec::new();
+
+    for (vout, tx_out) in transaction.output.iter().enumerate() {
+      let output = OutPoint::new(txid, vout.try_into().unwrap());
+
+      let info = wallet.get_output_info(output)?;
+
+      let spent = info.as_ref().map(|info| info.spent).unwrap_or_default();
+
+      let (inscriptions, sat_ranges) = info
+        .map(|info| (info.inscriptions, info.sat_ranges))
+        .unwrap_or_default();
+
+      let unavailable = if sat_ranges.is_some() {
+        None
+      } else if !confirmed {
+        Some(format!("transaction {txid} is not yet confirmed"))
+      } else if !wallet.has_sat_index() {
+        Some("ord server index does not have sat ranges, rebuild it with `--index-sats`".into())
+      } else if spent {
+        Some(
+          "output is spent and ord server index does not keep sat ranges of spent outputs, rebuild it with `--index-spent-sats`"
+            .into(),
+        )
+      } else {
+        Some("output not yet indexed by ord server, index may not be synced".into())
+      };
+
+      outputs.push(TracedOutput {
+        inscriptions,
+        output,
+        sat_ranges,
+        unavailable,
+        value: tx_out.value,
+      });
+    }
+
+    Ok(outputs)
+  }
+}
@@ -0,0 +1,222 @@
+use {
+  super::*,
+  crate::wallet::{batch::Sighash, Wallet},
+  bitcoin::policy::MAX_STANDARD_TX_WEIGHT,
+  is_terminal::IsTerminal,
+};
+
+/// Warnings printed to stderr. Rendered as text when stderr is a terminal and
+/// as a JSON object with `code` and `message` fields otherwise.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Warning {
+  AnyoneCanPay {
+    sighash: Sighash,
+  },
+  CommitBelowMinRelayFee {
+    fee_rate: FeeRate,
+    min_relay_fee_rate: FeeRate,
+  },
+  EmptyMempool {
+    fee_rate: FeeRate,
+  },
+  FallbackFeeRate {
+    confirmation_target: u16,
+    fee_rate: FeeRate,
+  },
+  InscriptionsAndRunes {
+    output: OutPoint,
+  },
+  LargeContent {
+    size: usize,
+  },
+  LargeFundingUtxo {
+    cost: Amount,
+    output: OutPoint,
+    ratio: u64,
+    value: Amount,
+  },
+  LowPostage {
+    postage: Amount,
+  },
+  NoLimitCheck,
+  NoPackageRelay {
+    version: usize,
+  },
+  NonStandardAnnex,
+  PlaintextRecoveryKey {
+    path: PathBuf,
+  },
+  SighashNone {
+    sighash: Sighash,
+  },
+  UnknownRarity {
+    sat_index: bool,
+    satpoint: SatPoint,
+  },
+  UnsyncedIndex {
+    chain: u64,
+    indexed: u64,
+  },
+}
+
+#[derive(Serialize)]
+struct Json {
+  code: &'static str,
+  message: String,
+}
+
+impl Warning {
+  pub(crate) fn code(&self) -> &'static str {
+    match self {
+      Self::AnyoneCanPay { .. } => "anyonecanpay",
+      Self::CommitBelowMinRelayFee { .. } => "commit-below-min-relay-fee",
+      Self::EmptyMempool { .. } => "empty-mempool",
+      Self::FallbackFeeRate { .. } => "fallback-fee-rate",
+      Self::InscriptionsAndRunes { .. } => "inscriptions-and-runes",
+      Self::LargeContent { .. } => "large-content",
+      Self::LargeFundingUtxo { .. } => "large-funding-utxo",
+      Self::LowPostage { .. } => "low-postage",
+      Self::NoLimitCheck => "no-limit-check",
+      Self::NoPackageRelay { .. } => "no-package-relay",
+      Self::NonStandardAnnex => "non-standard-annex",
+      Self::PlaintextRecoveryKey { .. } => "plaintext-recovery-key",
+      Self::SighashNone { .. } => "sighash-none",
+      Self::UnknownRarity { .. } => "unknown-rarity",
+      Self::UnsyncedIndex { .. } => "unsynced-index",
+    }
+  }
+
+  pub(crate) fn emit(&self) {
+    if io::stderr().is_terminal() {
+      eprintln!("warning: {self}");
+    } else {
+      eprintln!("{}", self.json());
+    }
+  }
+
+  fn json(&self) -> String {
+    serde_json::to_string(&Json {
+      code: self.code(),
+      message: self.to_string(),
+    })
+    .unwrap()
+  }
+}
+
+impl Display for Warning {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::AnyoneCanPay { sighash } => write!(
+        f,
+        "reveal signature uses {sighash}, anyone can add inputs to the reveal transaction"
+      ),
+      Self::CommitBelowMinRelayFee {
+        fee_rate,
+        min_relay_fee_rate,
+      } => write!(
+        f,
+        "commit transaction fee rate of {:.2} sats/vB is below node minimum relay fee rate of {:.2} sats/vB, commit transaction may not propagate to nodes without package relay",
+        fee_rate.n(),
+        min_relay_fee_rate.n(),
+      ),
+      Self::EmptyMempool { fee_rate } => write!(
+        f,
+        "mempool is empty, using mempool minimum fee rate of {} sats/vB",
+        fee_rate.n()
+      ),
+      Self::FallbackFeeRate {
+        confirmation_target,
+        fee_rate,
+      } => write!(
+        f,
+        "Bitcoin Core has no fee estimate for confirmation target of {confirmation_target} blocks, using fallback fee rate of {} sats/vB",
+        fee_rate.n()
+      ),
+      Self::InscriptionsAndRunes { output } => {
+        write!(f, "output {output} contains both inscriptions and runes")
+      }
+      Self::LargeContent { size } => write!(
+        f,
+        "inscription content of {size} bytes uses over a quarter of the maximum standard transaction weight of {MAX_STANDARD_TX_WEIGHT} weight units and will be expensive to inscribe"
+      ),
+      Self::LargeFundingUtxo {
+        cost,
+        output,
+        ratio,
+        value,
+      } => write!(
+        f,
+        "commit transaction spends output {output} of {} sat, more than {ratio} times the postage and fees of {} sat, consider consolidating or excluding it with --max-funding-utxo or --exclude-utxo",
+        value.to_sat(),
+        cost.to_sat(),
+      ),
+      Self::LowPostage { postage } => write!(
+        f,
+        "postage of {} sat is below the default of {} sat and may be uneconomical to send at high fee rates",
+        postage.to_sat(),
+        TARGET_POSTAGE.to_sat(),
+      ),
+      Self::NoLimitCheck => write!(
+        f,
+        "transaction weight limit check disabled, transactions may not be relayed"
+      ),
+      Self::NoPackageRelay { version } => write!(
+        f,
+        "Bitcoin Core {} does not support package relay, broadcasting commit and reveal transactions separately",
+        Wallet::format_bitcoin_core_version(*version),
+      ),
+      Self::NonStandardAnnex => write!(
+        f,
+        "reveal transaction has a taproot annex, which is non-standard, so it will be rejected by the mempool check and not relayed by nodes, use --skip-mempool-check and submit it directly to a miner"
+      ),
+      Self::PlaintextRecoveryKey { path } => write!(
+        f,
+        "writing recovery key to `{}` in plaintext, use --recovery-key-passphrase to encrypt it",
+        path.display()
+      ),
+      Self::SighashNone { sighash } => write!(
+        f,
+        "reveal signature uses {sighash}, anyone can change the reveal transaction outputs and where inscriptions are sent"
+      ),
+      Self::UnknownRarity {
+        sat_index: true,
+        satpoint,
+      } => write!(
+        f,
+        "could not determine rarity of sat at {satpoint}, index may not be fully synced"
+      ),
+      Self::UnknownRarity {
+        sat_index: false,
+        satpoint,
+      } => write!(
+        f,
+        "could not determine rarity of sat at {satpoint}, index has no sat index, use --index-sats to check rarity"
+      ),
+      Self::UnsyncedIndex { chain, indexed } => write!(
+        f,
+        "not waiting for `ord server` to synchronize, it has indexed {indexed} of {chain} blocks and results may be stale"
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn json() {
+    assert_eq!(
+      Warning::NoLimitCheck.json(),
+      r#"{"code":"no-limit-check","message":"transaction weight limit check disabled, transactions may not be relayed"}"#,
+    );
+
+    assert_eq!(
+      Warning::LowPostage {
+        postage: Amount::from_sat(777)
+      }
+      .json(),
+      r#"{"code":"low-postage","message":"postage of 777 sat is below the default of 10000 sat and may be uneconomical to send at high fee rates"}"#,
+    );
+  }
+}
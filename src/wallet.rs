@@ -5,6 +5,7 @@ use {
   bitcoin::secp256k1::{All, Secp256k1},
   bitcoin::{
     bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
+    hashes::{sha256, HashEngine},
     psbt::Psbt,
   },
   bitcoincore_rpc::bitcoincore_rpc_json::{Descriptor, ImportDescriptors, Timestamp},
@@ -18,7 +19,11 @@ use {
   transaction_builder::TransactionBuilder,
 };
 
+pub use inscription_request::InscriptionRequest;
+
 pub mod batch;
+mod inscription_request;
+pub mod recovery_key;
 pub mod transaction_builder;
 
 #[derive(Clone)]
@@ -41,6 +46,7 @@ impl OrdClient {
 
 pub(crate) struct Wallet {
   bitcoin_client: Client,
+  deadline: Option<Instant>,
   has_rune_index: bool,
   has_sat_index: bool,
   rpc_url: Url,
@@ -57,9 +63,13 @@ impl Wallet {
   pub(crate) fn build(
     name: String,
     no_sync: bool,
+    no_index_update: bool,
     settings: Settings,
     rpc_url: Url,
+    timeout: Option<Duration>,
   ) -> Result<Self> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
     let mut headers = HeaderMap::new();
 
     headers.insert(
@@ -76,19 +86,26 @@ impl Wallet {
       );
     }
 
-    let ord_client = reqwest::blocking::ClientBuilder::new()
-      .default_headers(headers.clone())
-      .build()?;
+    let ord_client = match timeout {
+      Some(timeout) => reqwest::blocking::ClientBuilder::new().timeout(timeout),
+      None => reqwest::blocking::ClientBuilder::new(),
+    }
+    .default_headers(headers.clone())
+    .build()?;
 
     tokio::runtime::Builder::new_multi_thread()
       .enable_all()
       .build()?
       .block_on(async move {
         let bitcoin_client = {
-          let client = Self::check_version(settings.bitcoin_rpc_client(Some(name.clone()))?)?;
+          let client = Self::check_version(
+            settings.bitcoin_rpc_client_with_timeout(Some(name.clone()), timeout)?,
+          )?;
 
           if !client.list_wallets()?.contains(&name) {
-            client.load_wallet(&name)?;
+            client.load_wallet(&name).with_context(|| {
+              format!("Bitcoin Core wallet `{name}` is not loaded and could not be loaded")
+            })?;
           }
 
           Self::check_descriptors(&name, client.list_descriptors(None)?.descriptors)?;
@@ -98,14 +115,40 @@ impl Wallet {
 
         let async_ord_client = OrdClient {
           url: rpc_url.clone(),
-          client: reqwest::ClientBuilder::new()
-            .default_headers(headers.clone())
-            .build()?,
+          client: match timeout {
+            Some(timeout) => reqwest::ClientBuilder::new().timeout(timeout),
+            None => reqwest::ClientBuilder::new(),
+          }
+          .default_headers(headers.clone())
+          .build()?,
         };
 
-        let chain_block_count = bitcoin_client.get_block_count().unwrap() + 1;
+        let chain_block_count = bitcoin_client.get_block_count()? + 1;
+
+        if no_index_update {
+          let indexed = async_ord_client
+            .get("/blockcount")
+            .await?
+            .text()
+            .await?
+            .parse::<u64>()
+            .context("wallet failed to talk to server. Make sure `ord server` is running.")?;
+
+          ensure!(
+            indexed > 0,
+            "`ord server` has not indexed any blocks, so its index cannot be used without waiting for it to synchronize"
+          );
+
+          if indexed < chain_block_count {
+            Warning::UnsyncedIndex {
+              chain: chain_block_count,
+              indexed,
+            }
+            .emit();
+          }
+        } else if !no_sync {
+          let start = Instant::now();
 
-        if !no_sync {
           for i in 0.. {
             let response = async_ord_client.get("/blockcount").await?;
             if response
@@ -121,6 +164,8 @@ impl Wallet {
             }
             tokio::time::sleep(Duration::from_millis(50)).await;
           }
+
+          log::info!("Synchronized with ord server in {:?}", start.elapsed());
         }
 
         let mut utxos = Self::get_utxos(&bitcoin_client)?;
@@ -178,6 +223,7 @@ impl Wallet {
 
         Ok(Wallet {
           bitcoin_client,
+          deadline,
           has_rune_index: status.rune_index,
           has_sat_index: status.sat_index,
           inscription_info,
@@ -322,10 +368,132 @@ impl Wallet {
     )))
   }
 
+  pub(crate) fn sat_at_satpoint(&self, satpoint: SatPoint) -> Option<Sat> {
+    if !self.has_sat_index {
+      return None;
+    }
+
+    let mut offset = 0;
+    for (start, end) in self
+      .output_info
+      .get(&satpoint.outpoint)?
+      .sat_ranges
+      .as_ref()?
+    {
+      let size = end - start;
+      if satpoint.offset < offset + size {
+        return Some(Sat(start + satpoint.offset - offset));
+      }
+      offset += size;
+    }
+
+    None
+  }
+
   pub(crate) fn bitcoin_client(&self) -> &Client {
     &self.bitcoin_client
   }
 
+  /// Secret for keying the `--idempotency-key` reveal key derivation, hashed
+  /// from the wallet's active private descriptors, so that only this wallet
+  /// can derive reveal keys from an idempotency key.
+  pub(crate) fn idempotency_secret(&self) -> Result<[u8; 32]> {
+    let descriptors = self
+      .bitcoin_client
+      .list_descriptors(Some(true))
+      .context("--idempotency-key requires a wallet with private keys")?
+      .descriptors
+      .into_iter()
+      .filter(|descriptor| descriptor.active && descriptor.desc.starts_with("tr("))
+      .map(|descriptor| descriptor.desc)
+      .collect::<BTreeSet<String>>();
+
+    ensure!(
+      !descriptors.is_empty(),
+      "--idempotency-key requires a wallet with private keys"
+    );
+
+    let mut engine = sha256::Hash::engine();
+
+    engine.input(b"ord/idempotency-secret");
+
+    for descriptor in descriptors {
+      engine.input(&u64::try_from(descriptor.len()).unwrap().to_le_bytes());
+      engine.input(descriptor.as_bytes());
+    }
+
+    Ok(sha256::Hash::from_engine(engine).to_byte_array())
+  }
+
+  /// Returns the wallet address for `role`, such as the destination or a
+  /// change output, of an inscribe run with `idempotency_key`. Addresses are
+  /// labeled by key and role and reused by later runs with the same key, so
+  /// that a retried run builds the same commit and reveal transactions.
+  pub(crate) fn get_idempotent_address(
+    &self,
+    idempotency_key: &str,
+    role: &str,
+  ) -> Result<Address> {
+    const RPC_WALLET_INVALID_LABEL_NAME: i32 = -11;
+
+    let label = format!("ord idempotency key {idempotency_key} {role}");
+
+    match self
+      .bitcoin_client
+      .call::<BTreeMap<Address<NetworkUnchecked>, serde_json::Value>>(
+        "getaddressesbylabel",
+        &[label.clone().into()],
+      ) {
+      Ok(addresses) => {
+        if let Some(address) = addresses.into_keys().next() {
+          return Ok(address.require_network(self.chain().network())?);
+        }
+      }
+      Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(
+        bitcoincore_rpc::jsonrpc::error::RpcError { code, .. },
+      )))
+        if code == RPC_WALLET_INVALID_LABEL_NAME => {}
+      Err(err) => return Err(err).context("could not get labeled addresses from wallet"),
+    }
+
+    Ok(
+      self
+        .bitcoin_client
+        .get_new_address(
+          Some(&label),
+          Some(bitcoincore_rpc::json::AddressType::Bech32m),
+        )
+        .context("could not get new address from wallet")?
+        .require_network(self.chain().network())?,
+    )
+  }
+
+  /// Broadcast `signed_tx`, treating Bitcoin Core rejecting it because it is
+  /// already in the mempool or chain, for example because it was broadcast by
+  /// a previous run, as success.
+  pub(crate) fn send_raw_transaction(&self, signed_tx: &[u8]) -> bitcoincore_rpc::Result<Txid> {
+    const RPC_VERIFY_REJECTED: i32 = -26;
+    const RPC_VERIFY_ALREADY_IN_CHAIN: i32 = -27;
+
+    match self.bitcoin_client.send_raw_transaction(signed_tx) {
+      Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(
+        bitcoincore_rpc::jsonrpc::error::RpcError { code, message, .. },
+      )))
+        if code == RPC_VERIFY_ALREADY_IN_CHAIN
+          || (code == RPC_VERIFY_REJECTED
+            && (message.contains("txn-already-known")
+              || message.contains("txn-already-in-mempool"))) =>
+      {
+        let txid = consensus::encode::deserialize::<Transaction>(signed_tx)?.txid();
+
+        log::info!("Transaction {txid} was already broadcast: {message}");
+
+        Ok(txid)
+      }
+      result => result,
+    }
+  }
+
   pub(crate) fn utxos(&self) -> &BTreeMap<OutPoint, TxOut> {
     &self.utxos
   }
@@ -335,11 +503,7 @@ impl Wallet {
   }
 
   pub(crate) fn lock_non_cardinal_outputs(&self) -> Result {
-    let inscriptions = self
-      .inscriptions()
-      .keys()
-      .map(|satpoint| satpoint.outpoint)
-      .collect::<HashSet<OutPoint>>();
+    let inscriptions = self.inscribed_utxos();
 
     let locked = self
       .locked_utxos()
@@ -367,6 +531,25 @@ impl Wallet {
     &self.inscriptions
   }
 
+  pub(crate) fn inscribed_utxos(&self) -> BTreeSet<OutPoint> {
+    Self::inscribed_outpoints(&self.inscriptions)
+  }
+
+  /// Outputs containing inscriptions, which must not be spent as cardinals.
+  pub(crate) fn inscribed_outpoints(
+    inscriptions: &BTreeMap<SatPoint, Vec<InscriptionId>>,
+  ) -> BTreeSet<OutPoint> {
+    inscriptions
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect()
+  }
+
+  /// Deadline given by `--timeout`, after which inscribing is aborted.
+  pub(crate) fn deadline(&self) -> Option<Instant> {
+    self.deadline
+  }
+
   pub(crate) fn inscription_info(&self) -> BTreeMap<InscriptionId, api::Inscription> {
     self.inscription_info.clone()
   }
@@ -387,6 +570,82 @@ impl Wallet {
     )
   }
 
+  /// Fetch decoded content of `inscription_id`. Content is requested with
+  /// whatever encoding it was inscribed with and decoded locally, so it does
+  /// not depend on `ord server` decompressing it.
+  pub(crate) fn inscription_content(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Vec<u8>>> {
+    let response = self
+      .ord_client
+      .get(
+        self
+          .rpc_url
+          .join(&format!("/content/{inscription_id}"))
+          .unwrap(),
+      )
+      .header(header::ACCEPT_ENCODING, "br, gzip")
+      .send()?;
+
+    if !response.status().is_success() {
+      return Ok(None);
+    }
+
+    let content_encoding = response.headers().get(header::CONTENT_ENCODING).cloned();
+
+    let body = response.bytes()?;
+
+    match content_encoding {
+      Some(encoding) => Ok(Some(Compression::decode(encoding.as_bytes(), &body)?)),
+      None => Ok(Some(body.to_vec())),
+    }
+  }
+
+  pub(crate) fn get_output_info(&self, output: OutPoint) -> Result<Option<api::Output>> {
+    let response = self
+      .ord_client
+      .get(self.rpc_url.join(&format!("/output/{output}")).unwrap())
+      .send()?;
+
+    if response.status().is_client_error() {
+      return Ok(None);
+    }
+
+    if !response.status().is_success() {
+      bail!("could not get output {output}: {}", response.text()?);
+    }
+
+    Ok(Some(serde_json::from_str(&response.text()?)?))
+  }
+
+  pub(crate) fn inscription_number(&self, inscription_id: InscriptionId) -> Result<Option<i32>> {
+    let response = self
+      .ord_client
+      .get(
+        self
+          .rpc_url
+          .join(&format!("/inscription/{inscription_id}"))
+          .unwrap(),
+      )
+      .send()?;
+
+    if response.status().is_client_error() {
+      return Ok(None);
+    }
+
+    if !response.status().is_success() {
+      bail!(
+        "could not get inscription {inscription_id}: {}",
+        response.text()?
+      );
+    }
+
+    Ok(Some(
+      serde_json::from_str::<api::Inscription>(&response.text()?)?.number,
+    ))
+  }
+
   pub(crate) fn get_parent_info(
     &self,
     parent: Option<InscriptionId>,
@@ -493,6 +752,82 @@ impl Wallet {
     )
   }
 
+  /// Returns `N` change addresses. If `reuse` is set, addresses which the
+  /// wallet has handed out but which have never received funds are used
+  /// first, and new addresses are only generated if there are not enough.
+  pub(crate) fn get_change_addresses<const N: usize>(&self, reuse: bool) -> Result<[Address; N]> {
+    let mut addresses = Vec::new();
+
+    if reuse {
+      addresses.extend(self.get_unused_addresses()?.into_iter().take(N));
+    }
+
+    while addresses.len() < N {
+      addresses.push(self.get_change_address()?);
+    }
+
+    Ok(addresses.try_into().unwrap())
+  }
+
+  /// Script pubkeys of wallet outputs holding inscriptions.
+  pub(crate) fn inscribed_script_pubkeys(&self) -> BTreeSet<ScriptBuf> {
+    self
+      .inscribed_utxos()
+      .iter()
+      .filter_map(|outpoint| {
+        self
+          .utxos
+          .get(outpoint)
+          .or_else(|| self.locked_utxos.get(outpoint))
+      })
+      .map(|tx_out| tx_out.script_pubkey.clone())
+      .collect()
+  }
+
+  fn get_unused_addresses(&self) -> Result<Vec<Address>> {
+    #[derive(Deserialize)]
+    struct ReceivedByAddress {
+      address: Address<NetworkUnchecked>,
+      txids: Vec<Txid>,
+    }
+
+    let inscribed = self.inscribed_script_pubkeys();
+
+    let mut addresses = Vec::new();
+
+    for received in self
+      .bitcoin_client
+      .call::<Vec<ReceivedByAddress>>("listreceivedbyaddress", &[0.into(), true.into()])
+      .context("could not list wallet addresses")?
+    {
+      if !received.txids.is_empty() {
+        continue;
+      }
+
+      let address = received.address.require_network(self.chain().network())?;
+
+      if inscribed.contains(&address.script_pubkey()) || addresses.contains(&address) {
+        continue;
+      }
+
+      addresses.push(address);
+    }
+
+    Ok(addresses)
+  }
+
+  pub(crate) fn get_utxos_with_label(&self, label: &str) -> Result<BTreeSet<OutPoint>> {
+    Ok(
+      self
+        .bitcoin_client
+        .list_unspent(None, None, None, None, None)?
+        .into_iter()
+        .filter(|utxo| utxo.label.as_deref() == Some(label))
+        .map(|utxo| OutPoint::new(utxo.txid, utxo.vout))
+        .collect(),
+    )
+  }
+
   pub(crate) fn has_sat_index(&self) -> bool {
     self.has_sat_index
   }
@@ -509,6 +844,10 @@ impl Wallet {
     self.settings.integration_test()
   }
 
+  pub(crate) fn default_fee_rate(&self) -> Option<FeeRate> {
+    self.settings.fee_rate()
+  }
+
   fn check_descriptors(wallet_name: &str, descriptors: Vec<Descriptor>) -> Result<Vec<Descriptor>> {
     let tr = descriptors
       .iter()
@@ -657,7 +996,39 @@ impl Wallet {
     }
   }
 
-  fn format_bitcoin_core_version(version: usize) -> String {
+  /// Check that taproot is active for the next block, since commit and reveal
+  /// transactions spend and create taproot outputs. Bitcoin Core 23 and later
+  /// report soft fork status with `getdeploymentinfo` instead of
+  /// `getblockchaininfo`.
+  pub(crate) fn check_taproot_active(&self) -> Result {
+    #[derive(Deserialize)]
+    struct Deployment {
+      active: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct DeploymentInfo {
+      deployments: BTreeMap<String, Deployment>,
+    }
+
+    let active = self
+      .bitcoin_client
+      .call::<DeploymentInfo>("getdeploymentinfo", &[])?
+      .deployments
+      .get("taproot")
+      .map(|deployment| deployment.active)
+      .unwrap_or_default();
+
+    ensure!(
+      active,
+      "taproot is not active on {}, inscribing requires taproot outputs and schnorr signatures",
+      self.chain(),
+    );
+
+    Ok(())
+  }
+
+  pub(crate) fn format_bitcoin_core_version(version: usize) -> String {
     format!(
       "{}.{}.{}",
       version / 10000,
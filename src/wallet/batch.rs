@@ -5,10 +5,15 @@ use {
     key::PrivateKey,
     key::{TapTweak, TweakedKeyPair, TweakedPublicKey, UntweakedKeyPair},
     policy::MAX_STANDARD_TX_WEIGHT,
-    secp256k1::{self, constants::SCHNORR_SIGNATURE_SIZE, rand, Secp256k1, XOnlyPublicKey},
-    sighash::{Prevouts, SighashCache, TapSighashType},
+    secp256k1::{
+      self,
+      constants::SCHNORR_SIGNATURE_SIZE,
+      rand::{rngs::StdRng, SeedableRng},
+      Secp256k1, XOnlyPublicKey,
+    },
+    sighash::{Annex, Prevouts, SighashCache, TapSighashType},
     taproot::Signature,
-    taproot::{ControlBlock, LeafVersion, TapLeafHash, TaprootBuilder},
+    taproot::{ControlBlock, LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo},
   },
   bitcoincore_rpc::bitcoincore_rpc_json::{ImportDescriptors, SignRawTransactionInput, Timestamp},
   wallet::transaction_builder::Target,
@@ -17,35 +22,80 @@ use {
 pub(crate) use transactions::Transactions;
 
 pub use {
-  entry::Entry, etching::Etching, file::File, mode::Mode, plan::Plan, range::Range, terms::Terms,
+  bundle::Bundle, cold_reveal::ColdReveal, entry::Entry, etching::Etching, file::File, mode::Mode,
+  plan::Plan, range::Range, sighash::Sighash, terms::Terms,
 };
 
+mod bundle;
+mod cold_reveal;
 pub mod entry;
 mod etching;
 pub mod file;
 pub mod mode;
 pub mod plan;
 mod range;
+mod sighash;
 mod terms;
 mod transactions;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Output {
   pub commit: Txid,
+  pub commit_address: Address<NetworkUnchecked>,
+  pub commit_internal_key: String,
   pub commit_psbt: Option<String>,
+  pub commit_vsize: Option<u64>,
+  pub commit_weight: Option<u64>,
   pub inscriptions: Vec<InscriptionInfo>,
+  /// Inscriptions created by the reveal transaction, keyed by the index of the
+  /// reveal output that holds them.
+  pub inscriptions_by_output: BTreeMap<u32, Vec<InscriptionId>>,
+  pub package_msg: Option<String>,
   pub parent: Option<InscriptionId>,
+  pub plan: Option<PlanInfo>,
+  pub rarity: Option<Rarity>,
   pub reveal: Txid,
+  pub reveal_key: Option<String>,
   pub reveal_psbt: Option<String>,
+  pub reveal_vsize: Option<u64>,
+  pub reveal_weight: Option<u64>,
   pub rune: Option<RuneInfo>,
   pub total_fees: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct InscriptionInfo {
+  pub bytes_saved: Option<u64>,
+  pub content_encoding: Option<String>,
   pub destination: Address<NetworkUnchecked>,
   pub id: InscriptionId,
   pub location: SatPoint,
+  pub number: Option<i32>,
+}
+
+/// Details of the transactions that `--plan` would create, for review before
+/// inscribing.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PlanInfo {
+  pub change: Vec<ChangeInfo>,
+  pub commit_fee: u64,
+  pub funding: Vec<FundingInfo>,
+  pub postages: Vec<u64>,
+  pub recovery_key_fingerprint: String,
+  pub reveal_fee: u64,
+  pub satpoint: SatPoint,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ChangeInfo {
+  pub address: Address<NetworkUnchecked>,
+  pub value: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FundingInfo {
+  pub output: OutPoint,
+  pub value: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -71,6 +121,22 @@ mod tests {
     bitcoin::policy::MAX_STANDARD_TX_WEIGHT,
   };
 
+  /// Estimated fee of signed `reveal_tx`, using the reveal script and control
+  /// block from its commit input witness.
+  fn reveal_fee(fee_rate: FeeRate, reveal_tx: &Transaction) -> Amount {
+    let witness = &reveal_tx.input[0].witness;
+
+    batch::Plan::reveal_fee(
+      fee_rate,
+      reveal_tx,
+      0,
+      Script::from_bytes(&witness[1]),
+      &ControlBlock::decode(&witness[2]).unwrap(),
+      Sighash::Default,
+      None,
+    )
+  }
+
   #[test]
   fn reveal_transaction_pays_fee() {
     let utxos = vec![(outpoint(1), tx_out(20000, address()))];
@@ -98,7 +164,7 @@ mod tests {
     }
     .create_batch_transactions(
       BTreeMap::new(),
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -107,9 +173,68 @@ mod tests {
     )
     .unwrap();
 
-    #[allow(clippy::cast_possible_truncation)]
-    #[allow(clippy::cast_sign_loss)]
-    let fee = Amount::from_sat((1.0 * (reveal_tx.vsize() as f64)).ceil() as u64);
+    let fee = reveal_fee(FeeRate::try_from(1.0).unwrap(), &reveal_tx);
+
+    assert_eq!(fee, FeeRate::try_from(1.0).unwrap().fee(reveal_tx.vsize()));
+
+    assert_eq!(
+      reveal_tx.output[0].value,
+      20000 - fee.to_sat() - (20000 - commit_tx.output[0].value),
+    );
+  }
+
+  #[test]
+  fn reveal_fee_includes_annex() {
+    let utxos = vec![(outpoint(1), tx_out(20000, address()))];
+
+    let mut annex = vec![0x50];
+    annex.extend([0; 99]);
+
+    let batch::Transactions {
+      commit_tx,
+      reveal_tx,
+      ..
+    } = batch::Plan {
+      satpoint: Some(satpoint(1, 0)),
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      reveal_annex: Some(annex.clone()),
+      reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      ..default()
+    }
+    .create_batch_transactions(
+      BTreeMap::new(),
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      utxos.into_iter().collect(),
+      [change(0), change(1)],
+      change(2),
+    )
+    .unwrap();
+
+    let witness = &reveal_tx.input[0].witness;
+
+    assert_eq!(witness.len(), 4);
+    assert_eq!(witness.last().unwrap(), annex);
+
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let fee = batch::Plan::reveal_fee(
+      fee_rate,
+      &reveal_tx,
+      0,
+      Script::from_bytes(&witness[1]),
+      &ControlBlock::decode(&witness[2]).unwrap(),
+      Sighash::Default,
+      Some(&annex),
+    );
+
+    assert_eq!(fee, fee_rate.fee(reveal_tx.vsize()));
+    assert!(fee > reveal_fee(fee_rate, &reveal_tx));
 
     assert_eq!(
       reveal_tx.output[0].value,
@@ -117,6 +242,1094 @@ mod tests {
     );
   }
 
+  #[test]
+  fn reveal_annex_must_have_annex_prefix() {
+    pretty_assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        reveal_annex: Some(vec![0x51]),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(20000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      "reveal annex must begin with the taproot annex prefix 0x50",
+    );
+  }
+
+  #[test]
+  fn commit_address_is_spent_by_reveal() {
+    let utxos = vec![(outpoint(1), tx_out(20000, address()))];
+
+    let batch::Transactions {
+      commit_address,
+      commit_tx,
+      reveal_tx,
+      ..
+    } = batch::Plan {
+      satpoint: Some(satpoint(1, 0)),
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      ..default()
+    }
+    .create_batch_transactions(
+      BTreeMap::new(),
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      utxos.into_iter().collect(),
+      [change(0), change(1)],
+      change(2),
+    )
+    .unwrap();
+
+    let commit_output = reveal_tx.input[0].previous_output;
+
+    assert_eq!(commit_output.txid, commit_tx.txid());
+    assert_eq!(
+      commit_tx.output[usize::try_from(commit_output.vout).unwrap()].script_pubkey,
+      commit_address.script_pubkey(),
+    );
+  }
+
+  #[test]
+  fn reveal_can_be_funded_separately() {
+    for fee_rate in [1.0, 10.0] {
+      let utxos = vec![
+        (outpoint(1), tx_out(20_000, address())),
+        (outpoint(2), tx_out(50_000, address())),
+      ];
+
+      let batch::Transactions {
+        commit_tx,
+        reveal_tx,
+        ..
+      } = batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        commit_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
+        reveal_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
+        fund_reveal_separately: true,
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap();
+
+      assert_eq!(commit_tx.output[0].value, TARGET_POSTAGE.to_sat());
+
+      assert_eq!(reveal_tx.input.len(), 2);
+      assert_eq!(reveal_tx.input[0].previous_output.txid, commit_tx.txid());
+      assert_eq!(reveal_tx.input[1].previous_output, outpoint(2));
+
+      assert_eq!(reveal_tx.output.len(), 2);
+      assert_eq!(reveal_tx.output[0].value, TARGET_POSTAGE.to_sat());
+      assert_eq!(reveal_tx.output[1].script_pubkey, change(2).script_pubkey());
+      assert!(reveal_tx.output[1].value < 50_000);
+    }
+  }
+
+  #[test]
+  fn reveal_funded_separately_requires_additional_cardinal() {
+    let utxos = vec![(outpoint(1), tx_out(20_000, address()))];
+
+    assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        fund_reveal_separately: true,
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet contains no cardinal utxo large enough to fund reveal transaction",
+    );
+  }
+
+  #[test]
+  fn key_seed_makes_transactions_deterministic() {
+    let transactions = |key_seed| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        key_seed,
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(20_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap()
+    };
+
+    let a = transactions(Some([1; 32]));
+    let b = transactions(Some([1; 32]));
+
+    assert_eq!(a.commit_address, b.commit_address);
+    assert_eq!(a.commit_tx, b.commit_tx);
+    assert_eq!(a.reveal_tx, b.reveal_tx);
+    assert_eq!(
+      a.recovery_key_pair.unwrap().to_inner().secret_bytes(),
+      b.recovery_key_pair.unwrap().to_inner().secret_bytes()
+    );
+
+    let c = transactions(Some([2; 32]));
+
+    assert_ne!(a.commit_address, c.commit_address);
+
+    let d = transactions(None);
+    let e = transactions(None);
+
+    assert_ne!(d.commit_address, e.commit_address);
+  }
+
+  #[test]
+  fn seeded_key_pair_is_stable() {
+    assert_eq!(
+      batch::Plan::seeded_key_pair(&Secp256k1::new(), [1; 32])
+        .x_only_public_key()
+        .0
+        .to_string(),
+      "393c6c6c5fd512e70e1292bac4f8b9910ec805105c70ed2825a1ed9fcf565703",
+    );
+  }
+
+  #[test]
+  fn idempotency_key_derives_commit_address_from_inscriptions() {
+    let transactions = |idempotency_key: &str, content, utxo| {
+      batch::Plan {
+        satpoint: None,
+        inscriptions: vec![inscription("text/plain", content)],
+        destinations: vec![recipient()],
+        idempotency_key: Some(idempotency_key.into()),
+        idempotency_secret: Some([1; 32]),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(utxo, tx_out(20_000, address()))].into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap()
+    };
+
+    let a = transactions("foo", "ord", outpoint(1));
+    let b = transactions("foo", "ord", outpoint(1));
+
+    assert_eq!(a.commit_tx, b.commit_tx);
+    assert_eq!(a.reveal_tx.txid(), b.reveal_tx.txid());
+
+    let c = transactions("foo", "ord", outpoint(2));
+
+    assert_eq!(a.commit_address, c.commit_address);
+    assert_ne!(a.commit_tx.txid(), c.commit_tx.txid());
+
+    assert_ne!(
+      a.commit_address,
+      transactions("bar", "ord", outpoint(1)).commit_address
+    );
+
+    assert_ne!(
+      a.commit_address,
+      transactions("foo", "bar", outpoint(1)).commit_address
+    );
+  }
+
+  #[test]
+  fn reveal_output_below_min_postage_is_refused() {
+    let transactions = |min_postage| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        min_postage,
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert!(transactions(Some(TARGET_POSTAGE)).is_ok());
+
+    assert_eq!(
+      transactions(Some(Amount::from_sat(20_000)))
+        .unwrap_err()
+        .to_string(),
+      "reveal output 0 value of 10000 sat is below requested minimum postage of 20000 sat",
+    );
+  }
+
+  #[test]
+  fn reveal_output_below_script_dust_is_refused() {
+    assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        min_postage: Some(Amount::from_sat(100)),
+        postages: vec![Amount::from_sat(100)],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(20_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      "reveal output 0 value of 100 sat is below dust value of 294 sat for its script",
+    );
+  }
+
+  #[test]
+  fn reveal_output_dust_value_depends_on_destination_script_type() {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::from_seckey_slice(&secp256k1, &[1; 32]).unwrap();
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let transactions = |destination: &Address, postage: u64| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![destination.clone()],
+        postages: vec![Amount::from_sat(postage)],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(20_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    for (destination, dust_value) in [
+      (
+        Address::p2tr(&secp256k1, public_key, None, Network::Testnet),
+        330,
+      ),
+      (recipient(), 294),
+      (
+        Address::p2sh(&ScriptBuf::new(), Network::Testnet).unwrap(),
+        540,
+      ),
+    ] {
+      let reveal_tx = transactions(&destination, dust_value).unwrap().reveal_tx;
+
+      assert_eq!(
+        reveal_tx.output[0].script_pubkey,
+        destination.script_pubkey()
+      );
+      assert_eq!(reveal_tx.output[0].value, dust_value);
+
+      assert_eq!(
+        transactions(&destination, dust_value - 1)
+          .unwrap_err()
+          .to_string(),
+        format!(
+          "reveal output 0 value of {} sat is below dust value of {dust_value} sat for its script",
+          dust_value - 1,
+        ),
+      );
+    }
+  }
+
+  #[test]
+  fn satpoint_offset_must_be_less_than_output_value() {
+    let transactions = |offset| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, offset)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [
+          (outpoint(1), tx_out(50_000, address())),
+          (outpoint(2), tx_out(50_000, address())),
+        ]
+        .into_iter()
+        .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    transactions(49_999).unwrap();
+
+    for offset in [50_000, u64::MAX] {
+      assert_eq!(
+        transactions(offset).unwrap_err().to_string(),
+        format!(
+          "satpoint {} offset {offset} is not less than output value of 50000 sat",
+          satpoint(1, offset)
+        ),
+      );
+    }
+  }
+
+  #[test]
+  fn commit_hash_is_added_to_reveal_transaction() {
+    let transactions = |commit_hash| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        commit_hash,
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap()
+    };
+
+    let without = transactions(None);
+    let with = transactions(Some(vec![0xab; 32]));
+
+    assert_eq!(without.reveal_tx.output.len(), 1);
+    assert_eq!(with.reveal_tx.output.len(), 2);
+    assert_eq!(with.reveal_tx.output[1].value, 0);
+    assert_eq!(
+      with.reveal_tx.output[1].script_pubkey.as_bytes(),
+      [[0x6a, 32].as_slice(), &[0xab; 32]].concat(),
+    );
+
+    assert_eq!(
+      with.commit_tx.output[0].value - without.commit_tx.output[0].value,
+      43
+    );
+  }
+
+  #[test]
+  fn reveal_output_index_moves_inscription_output() {
+    let transactions = |reveal_output_index, inscriptions: usize| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord"); inscriptions],
+        destinations: vec![recipient(); inscriptions],
+        commit_hash: Some(vec![0xab; 32]),
+        postages: vec![TARGET_POSTAGE; inscriptions],
+        reveal_output_index,
+        mode: batch::Mode::SeparateOutputs,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    let reveal_tx = transactions(Some(0), 1).unwrap().reveal_tx;
+
+    assert_eq!(
+      reveal_tx.output[0].script_pubkey,
+      recipient().script_pubkey()
+    );
+    assert_eq!(reveal_tx.output[1].value, 0);
+
+    let reveal_tx = transactions(Some(1), 1).unwrap().reveal_tx;
+
+    assert_eq!(reveal_tx.output[0].value, 0);
+    assert_eq!(
+      reveal_tx.output[1].script_pubkey,
+      recipient().script_pubkey()
+    );
+
+    assert_eq!(
+      transactions(Some(1), 2).unwrap_err().to_string(),
+      "reveal output index requires a single inscription output",
+    );
+  }
+
+  #[test]
+  fn commit_hash_may_not_exceed_standard_op_return_size() {
+    let transactions = |commit_hash| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        commit_hash: Some(commit_hash),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert!(transactions(vec![0; 80]).is_ok());
+
+    assert_eq!(
+      transactions(vec![0; 81]).unwrap_err().to_string(),
+      "commit hash greater than maximum standard OP_RETURN data size: 81 > 80",
+    );
+  }
+
+  #[test]
+  fn outputs_holding_inscriptions_are_never_selected_for_funding() {
+    for allowed_inscriptions in [BTreeSet::new(), BTreeSet::from([inscription_id(1)])] {
+      assert_eq!(
+        batch::Plan {
+          allowed_inscriptions,
+          inscriptions: vec![inscription("text/plain", "ord")],
+          destinations: vec![recipient()],
+          postages: vec![TARGET_POSTAGE],
+          mode: batch::Mode::SharedOutput,
+          ..default()
+        }
+        .create_batch_transactions(
+          BTreeMap::from([(satpoint(2, 0), vec![inscription_id(1)])]),
+          Chain::Signet,
+          BTreeSet::new(),
+          BTreeSet::new(),
+          [
+            (outpoint(1), tx_out(5_000, address())),
+            (outpoint(2), tx_out(50_000, address())),
+          ]
+          .into_iter()
+          .collect(),
+          [change(0), change(1)],
+          change(2),
+        )
+        .unwrap_err()
+        .to_string(),
+        "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.",
+      );
+    }
+  }
+
+  #[test]
+  fn outputs_holding_inscriptions_are_only_spent_if_allowed() {
+    let transactions = |allowed_inscriptions| {
+      batch::Plan {
+        allowed_inscriptions,
+        reveal_satpoints: vec![(satpoint(1, 0), tx_out(10_000, address()))],
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postages: vec![Amount::from_sat(10_000)],
+        mode: batch::Mode::SatPoints,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::from([(satpoint(1, 0), vec![inscription_id(1)])]),
+        Chain::Signet,
+        BTreeSet::from([outpoint(1)]),
+        BTreeSet::new(),
+        [
+          (outpoint(1), tx_out(10_000, address())),
+          (outpoint(2), tx_out(50_000, address())),
+        ]
+        .into_iter()
+        .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert_eq!(
+      transactions(BTreeSet::new()).unwrap_err().to_string(),
+      format!(
+        "transactions would spend output {} holding inscription {}, pass --allow-spending-inscription {} to allow",
+        outpoint(1),
+        inscription_id(1),
+        inscription_id(1),
+      ),
+    );
+
+    let reveal_tx = transactions(BTreeSet::from([inscription_id(1)]))
+      .unwrap()
+      .reveal_tx;
+
+    assert_eq!(reveal_tx.input[0].previous_output, outpoint(1));
+  }
+
+  #[test]
+  fn inscribed_sat_at_nonzero_offset_is_aligned_to_start_of_reveal_output() {
+    let batch::Transactions {
+      commit_address,
+      commit_tx,
+      reveal_tx,
+      ..
+    } = batch::Plan {
+      satpoint: Some(satpoint(1, 5_000)),
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      ..default()
+    }
+    .create_batch_transactions(
+      BTreeMap::new(),
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      [(outpoint(1), tx_out(50_000, address()))]
+        .into_iter()
+        .collect(),
+      [change(0), change(1)],
+      change(2),
+    )
+    .unwrap();
+
+    assert_eq!(commit_tx.input[0].previous_output, outpoint(1));
+
+    assert_eq!(commit_tx.output[0].value, 5_000);
+    assert_ne!(
+      commit_tx.output[0].script_pubkey,
+      commit_address.script_pubkey()
+    );
+    assert_eq!(
+      commit_tx.output[1].script_pubkey,
+      commit_address.script_pubkey()
+    );
+
+    assert_eq!(
+      reveal_tx.input[0].previous_output,
+      OutPoint {
+        txid: commit_tx.txid(),
+        vout: 1,
+      }
+    );
+
+    assert_eq!(reveal_tx.output[0].value, TARGET_POSTAGE.to_sat());
+    assert_eq!(
+      reveal_tx.output[0].script_pubkey,
+      recipient().script_pubkey()
+    );
+  }
+
+  #[test]
+  fn excluded_utxos_are_not_spent() {
+    let transactions = |exclude_utxos: &[OutPoint]| {
+      batch::Plan {
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        exclude_utxos: exclude_utxos.iter().copied().collect(),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [
+          (outpoint(1), tx_out(50_000, address())),
+          (outpoint(2), tx_out(50_000, address())),
+        ]
+        .into_iter()
+        .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert_eq!(
+      transactions(&[]).unwrap().commit_tx.input[0].previous_output,
+      outpoint(1),
+    );
+
+    let commit_tx = transactions(&[outpoint(1)]).unwrap().commit_tx;
+
+    assert_eq!(commit_tx.input.len(), 1);
+    assert_eq!(commit_tx.input[0].previous_output, outpoint(2));
+
+    assert_eq!(
+      transactions(&[outpoint(1), outpoint(2)])
+        .unwrap_err()
+        .to_string(),
+      "wallet contains no cardinal utxos",
+    );
+  }
+
+  #[test]
+  fn utxos_over_max_funding_utxo_are_not_spent() {
+    let transactions = |max_funding_utxo: Option<u64>, satpoint: Option<SatPoint>| {
+      batch::Plan {
+        satpoint,
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        max_funding_utxo: max_funding_utxo.map(Amount::from_sat),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [
+          (outpoint(1), tx_out(100_000_000, address())),
+          (outpoint(2), tx_out(50_000, address())),
+        ]
+        .into_iter()
+        .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert_eq!(
+      transactions(None, None).unwrap().commit_tx.input[0].previous_output,
+      outpoint(1),
+    );
+
+    let commit_tx = transactions(Some(100_000), None).unwrap().commit_tx;
+
+    assert_eq!(commit_tx.input.len(), 1);
+    assert_eq!(commit_tx.input[0].previous_output, outpoint(2));
+
+    assert_eq!(
+      transactions(Some(100_000), Some(satpoint(1, 0)))
+        .unwrap()
+        .commit_tx
+        .input[0]
+        .previous_output,
+      outpoint(1),
+    );
+
+    assert_eq!(
+      transactions(Some(10_000), None).unwrap_err().to_string(),
+      "wallet contains no cardinal utxos, 2 cardinal utxos are worth more than --max-funding-utxo \
+      0.0001 BTC",
+    );
+  }
+
+  #[test]
+  fn satpoint_in_excluded_utxo_is_refused() {
+    assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        exclude_utxos: [outpoint(1)].into_iter().collect(),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "satpoint {} is in excluded utxo {}",
+        satpoint(1, 0),
+        outpoint(1)
+      ),
+    );
+  }
+
+  #[test]
+  fn satpoint_not_in_utxos_is_refused() {
+    assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(2), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "satpoint {} outpoint {} is no longer unspent",
+        satpoint(1, 0),
+        outpoint(1)
+      ),
+    );
+  }
+
+  #[test]
+  fn reveal_tx_version_is_configurable() {
+    let plan = |reveal_tx_version, etching| batch::Plan {
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      etching,
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      reveal_tx_version,
+      ..default()
+    };
+
+    let transactions = |plan: batch::Plan| {
+      plan.create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    assert_eq!(transactions(plan(2, None)).unwrap().reveal_tx.version, 2);
+
+    assert_eq!(transactions(plan(1, None)).unwrap().reveal_tx.version, 1);
+
+    assert_eq!(
+      transactions(plan(3, None)).unwrap_err().to_string(),
+      "reveal transaction version must be 1 or 2, not 3",
+    );
+
+    assert_eq!(
+      transactions(plan(1, Some(Etching::default())))
+        .unwrap_err()
+        .to_string(),
+      "etching reveal transaction uses a relative locktime and requires --reveal-tx-version 2",
+    );
+  }
+
+  #[test]
+  fn estimated_vsizes_match_built_transactions() {
+    let taproot = "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked();
+
+    let change = "bc1pdqrcrxa8vx6gy75mfdfj84puhxffh4fq46h3gkp6jxdd0vjcsdyspfxcv6"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked();
+
+    for inscription in [
+      inscription("text/plain", "ord"),
+      inscription("image/png", [1; 2000]),
+    ] {
+      let fee_rate = FeeRate::try_from(5.0).unwrap();
+
+      let Transactions {
+        commit_tx,
+        reveal_tx,
+        total_fees,
+        ..
+      } = batch::Plan {
+        commit_fee_rate: fee_rate,
+        inscriptions: vec![inscription.clone()],
+        destinations: vec![taproot.clone()],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SeparateOutputs,
+        reveal_fee_rate: fee_rate,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Mainnet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, taproot.clone()))]
+          .into_iter()
+          .collect(),
+        [taproot.clone(), change.clone()],
+        taproot.clone(),
+      )
+      .unwrap();
+
+      let mut signed_commit_tx = commit_tx.clone();
+
+      for input in &mut signed_commit_tx.input {
+        input.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+      }
+
+      assert_eq!(commit_tx.output.len(), 2);
+
+      assert_eq!(
+        batch::Plan::estimate_vsizes(&inscription, fee_rate),
+        (signed_commit_tx.vsize(), reveal_tx.vsize(), total_fees),
+      );
+    }
+  }
+
+  #[test]
+  fn commit_output_can_be_pinned_to_first_vout() {
+    let transactions = |satpoint| {
+      batch::Plan {
+        satpoint,
+        commit_vout_first: true,
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SeparateOutputs,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+    };
+
+    let Transactions {
+      commit_address,
+      commit_tx,
+      reveal_tx,
+      ..
+    } = transactions(None).unwrap();
+
+    assert_eq!(
+      commit_tx.output[0].script_pubkey,
+      commit_address.script_pubkey()
+    );
+    assert_eq!(reveal_tx.input[0].previous_output.vout, 0);
+
+    assert_eq!(
+      transactions(Some(satpoint(1, 1_000))).unwrap_err().to_string(),
+      format!(
+        "cannot place recipient output first because outgoing satpoint {} is not at the start of its output",
+        satpoint(1, 1_000)
+      ),
+    );
+  }
+
+  #[test]
+  fn reveal_signature_uses_requested_sighash() {
+    for sighash in [
+      batch::Sighash::Default,
+      batch::Sighash::All,
+      batch::Sighash::AllAnyonecanpay,
+    ] {
+      let Transactions {
+        commit_tx,
+        reveal_tx,
+        ..
+      } = batch::Plan {
+        sighash,
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SeparateOutputs,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap();
+
+      let witness = reveal_tx.input[0].witness.to_vec();
+
+      let signature = Signature::from_slice(&witness[0]).unwrap();
+
+      assert_eq!(signature.hash_ty, TapSighashType::from(sighash));
+
+      let reveal_script = Script::from_bytes(&witness[1]);
+
+      let prevouts = [commit_tx.output
+        [usize::try_from(reveal_tx.input[0].previous_output.vout).unwrap()]
+      .clone()];
+
+      let hash = SighashCache::new(&reveal_tx)
+        .taproot_script_spend_signature_hash(
+          0,
+          &Prevouts::All(&prevouts),
+          TapLeafHash::from_script(reveal_script, LeafVersion::TapScript),
+          signature.hash_ty,
+        )
+        .unwrap();
+
+      let public_key = XOnlyPublicKey::from_slice(&reveal_script.as_bytes()[1..33]).unwrap();
+
+      Secp256k1::new()
+        .verify_schnorr(
+          &signature.sig,
+          &secp256k1::Message::from_slice(hash.as_ref()).unwrap(),
+          &public_key,
+        )
+        .unwrap();
+    }
+  }
+
+  #[test]
+  fn addresses_must_be_valid_for_chain() {
+    let transactions = |destination, change_1| {
+      batch::Plan {
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![destination],
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SeparateOutputs,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(50_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change_1],
+        change(2),
+      )
+    };
+
+    assert!(transactions(recipient(), change(1)).is_ok());
+
+    assert_eq!(
+      transactions(address(), change(1)).unwrap_err().to_string(),
+      format!("destination address {} is not valid for signet", address()),
+    );
+
+    assert_eq!(
+      transactions(recipient(), address())
+        .unwrap_err()
+        .to_string(),
+      format!("change address {} is not valid for signet", address()),
+    );
+  }
+
   #[test]
   fn inscribe_transactions_opt_in_to_rbf() {
     let utxos = vec![(outpoint(1), tx_out(20000, address()))];
@@ -132,7 +1345,7 @@ mod tests {
     } = batch::Plan {
       satpoint: Some(satpoint(1, 0)),
       parent_info: None,
-      inscriptions: vec![inscription],
+      inscriptions: vec![inscription.clone()],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
       reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -144,7 +1357,7 @@ mod tests {
     }
     .create_batch_transactions(
       BTreeMap::new(),
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -155,6 +1368,90 @@ mod tests {
 
     assert!(commit_tx.is_explicitly_rbf());
     assert!(reveal_tx.is_explicitly_rbf());
+
+    let batch::Transactions {
+      commit_tx,
+      reveal_tx,
+      ..
+    } = batch::Plan {
+      satpoint: Some(satpoint(1, 0)),
+      inscriptions: vec![inscription],
+      destinations: vec![recipient()],
+      commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      no_rbf: true,
+      ..default()
+    }
+    .create_batch_transactions(
+      BTreeMap::new(),
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      vec![(outpoint(1), tx_out(20000, address()))]
+        .into_iter()
+        .collect(),
+      [change(0), change(1)],
+      change(2),
+    )
+    .unwrap();
+
+    assert!(!commit_tx.is_explicitly_rbf());
+    assert!(!reveal_tx.is_explicitly_rbf());
+    assert!(commit_tx
+      .input
+      .iter()
+      .all(|input| input.sequence == Sequence::MAX));
+    assert!(reveal_tx
+      .input
+      .iter()
+      .all(|input| input.sequence == Sequence::MAX));
+  }
+
+  #[test]
+  fn commit_locktime() {
+    let commit_tx = |no_rbf| {
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        commit_locktime: LockTime::from_height(800_000).unwrap(),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        no_rbf,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), tx_out(20_000, address()))]
+          .into_iter()
+          .collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap()
+      .commit_tx
+    };
+
+    let rbf = commit_tx(false);
+    assert_eq!(rbf.lock_time, LockTime::from_height(800_000).unwrap());
+    assert!(rbf.is_explicitly_rbf());
+    assert!(rbf
+      .input
+      .iter()
+      .all(|input| input.sequence.enables_absolute_lock_time()));
+
+    let no_rbf = commit_tx(true);
+    assert_eq!(no_rbf.lock_time, LockTime::from_height(800_000).unwrap());
+    assert!(!no_rbf.is_explicitly_rbf());
+    assert!(no_rbf
+      .input
+      .iter()
+      .all(|input| input.sequence == Sequence::ENABLE_LOCKTIME_NO_RBF));
   }
 
   #[test]
@@ -189,7 +1486,7 @@ mod tests {
     }
     .create_batch_transactions(
       inscriptions,
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -241,7 +1538,7 @@ mod tests {
     }
     .create_batch_transactions(
       inscriptions,
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -315,14 +1612,16 @@ mod tests {
 
     assert_eq!(reveal_value, 20_000 - fee);
 
-    let fee = FeeRate::try_from(fee_rate)
-      .unwrap()
-      .fee(reveal_tx.vsize())
-      .to_sat();
+    let fee = reveal_fee(FeeRate::try_from(fee_rate).unwrap(), &reveal_tx);
+
+    assert_eq!(
+      fee,
+      FeeRate::try_from(fee_rate).unwrap().fee(reveal_tx.vsize())
+    );
 
     assert_eq!(
       reveal_tx.output[0].value,
-      20_000 - fee - (20_000 - commit_tx.output[0].value),
+      20_000 - fee.to_sat() - (20_000 - commit_tx.output[0].value),
     );
   }
 
@@ -525,7 +1824,7 @@ mod tests {
     }
     .create_batch_transactions(
       BTreeMap::new(),
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -566,7 +1865,7 @@ mod tests {
     }
     .create_batch_transactions(
       BTreeMap::new(),
-      Chain::Mainnet,
+      Chain::Signet,
       BTreeSet::new(),
       BTreeSet::new(),
       utxos.into_iter().collect(),
@@ -1179,4 +2478,151 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn reveal_csv_sets_relative_locktime() {
+    let utxos = vec![(outpoint(1), tx_out(20_000, address()))];
+
+    let plan = |reveal_csv| batch::Plan {
+      satpoint: Some(satpoint(1, 0)),
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      key_seed: Some([1; 32]),
+      reveal_csv,
+      ..default()
+    };
+
+    let batch::Transactions {
+      commit_tx,
+      reveal_tx,
+      ..
+    } = plan(Some(10))
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.clone().into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap();
+
+    let sequence = reveal_tx.input[0].sequence;
+
+    assert_eq!(sequence, Sequence::from_height(10));
+    assert!(sequence.is_relative_lock_time());
+    assert!(sequence.is_height_locked());
+    assert!(sequence.is_rbf());
+    assert_eq!(reveal_tx.version, 2);
+
+    let batch::Transactions {
+      commit_tx: unlocked_commit_tx,
+      ..
+    } = plan(None)
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap();
+
+    assert_eq!(commit_tx.output, unlocked_commit_tx.output);
+  }
+
+  #[test]
+  fn reveal_csv_requires_version_two() {
+    let utxos = vec![(outpoint(1), tx_out(20_000, address()))];
+
+    assert_eq!(
+      batch::Plan {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+        reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+        postages: vec![TARGET_POSTAGE],
+        mode: batch::Mode::SharedOutput,
+        reveal_csv: Some(10),
+        reveal_tx_version: 1,
+        ..default()
+      }
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap_err()
+      .to_string(),
+      "--reveal-csv uses a relative locktime and requires --reveal-tx-version 2",
+    );
+  }
+
+  #[test]
+  fn emit_reveal_key_is_refused_on_mainnet() {
+    let utxos = vec![(outpoint(1), tx_out(20_000, address()))];
+
+    let plan = batch::Plan {
+      satpoint: Some(satpoint(1, 0)),
+      inscriptions: vec![inscription("text/plain", "ord")],
+      destinations: vec![recipient()],
+      commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      reveal_fee_rate: FeeRate::try_from(1.0).unwrap(),
+      postages: vec![TARGET_POSTAGE],
+      mode: batch::Mode::SharedOutput,
+      emit_reveal_key: true,
+      ..default()
+    };
+
+    assert_eq!(
+      plan
+        .create_batch_transactions(
+          BTreeMap::new(),
+          Chain::Mainnet,
+          BTreeSet::new(),
+          BTreeSet::new(),
+          utxos.clone().into_iter().collect(),
+          [change(0), change(1)],
+          change(2),
+        )
+        .unwrap_err()
+        .to_string(),
+      "--emit-reveal-key exposes the reveal transaction key and may only be used on regtest or signet, not mainnet",
+    );
+
+    let batch::Transactions {
+      reveal_tx,
+      reveal_key_pair,
+      ..
+    } = plan
+      .create_batch_transactions(
+        BTreeMap::new(),
+        Chain::Signet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        [change(0), change(1)],
+        change(2),
+      )
+      .unwrap();
+
+    let (public_key, _parity) = reveal_key_pair.unwrap().x_only_public_key();
+
+    assert_eq!(
+      &reveal_tx.input[0].witness.nth(1).unwrap()[1..33],
+      public_key.serialize(),
+    );
+  }
 }
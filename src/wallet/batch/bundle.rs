@@ -0,0 +1,75 @@
+use super::*;
+
+/// Signed commit and reveal transactions written by `--prepare-only`, to be
+/// broadcast later with `ord wallet broadcast-bundle`, or when broadcasting
+/// the reveal transaction fails, to be broadcast with `ord wallet
+/// resume-reveal`. The recovery key is only included in the latter if it is
+/// encrypted.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+  pub commit: Txid,
+  pub commit_tx: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub recovery_key: Option<String>,
+  pub reveal: Txid,
+  pub reveal_tx: String,
+}
+
+impl Bundle {
+  pub(crate) fn commit_tx(&self) -> Result<Transaction> {
+    Self::decode(&self.commit_tx, self.commit).context("invalid commit transaction")
+  }
+
+  pub(crate) fn reveal_tx(&self) -> Result<Transaction> {
+    Self::decode(&self.reveal_tx, self.reveal).context("invalid reveal transaction")
+  }
+
+  fn decode(hex: &str, txid: Txid) -> Result<Transaction> {
+    let transaction: Transaction = consensus::encode::deserialize(&hex::decode(hex)?)?;
+
+    ensure!(
+      transaction.txid() == txid,
+      "transaction ID {} does not match {txid}",
+      transaction.txid(),
+    );
+
+    Ok(transaction)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decode_checks_txid() {
+    let transaction = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![TxOut {
+        value: 1,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    let bundle = Bundle {
+      commit: transaction.txid(),
+      commit_tx: hex::encode(consensus::encode::serialize(&transaction)),
+      recovery_key: None,
+      reveal: txid(1),
+      reveal_tx: hex::encode(consensus::encode::serialize(&transaction)),
+    };
+
+    assert_eq!(bundle.commit_tx().unwrap(), transaction);
+
+    assert_eq!(
+      format!("{:#}", bundle.reveal_tx().unwrap_err()),
+      format!(
+        "invalid reveal transaction: transaction ID {} does not match {}",
+        transaction.txid(),
+        txid(1)
+      ),
+    );
+  }
+}
@@ -0,0 +1,255 @@
+use {super::*, bitcoin::taproot::TapNodeHash};
+
+/// Unsigned reveal transaction written by `--cold-reveal-file`, to be signed
+/// on an offline machine with `ord cold-reveal sign` using the key created by
+/// `ord cold-reveal key`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColdReveal {
+  pub commit: Txid,
+  pub commit_output: String,
+  pub control_block: String,
+  pub public_key: String,
+  pub reveal: Txid,
+  pub reveal_script: String,
+  pub reveal_tx: String,
+  pub sighash: u8,
+}
+
+impl ColdReveal {
+  pub(crate) fn new(
+    commit_output: &TxOut,
+    control_block: &ControlBlock,
+    public_key: XOnlyPublicKey,
+    reveal_script: &Script,
+    reveal_tx: &Transaction,
+    sighash: Sighash,
+  ) -> Self {
+    Self {
+      commit: reveal_tx.input[0].previous_output.txid,
+      commit_output: hex::encode(consensus::encode::serialize(commit_output)),
+      control_block: hex::encode(control_block.serialize()),
+      public_key: public_key.to_string(),
+      reveal: reveal_tx.txid(),
+      reveal_script: hex::encode(reveal_script.as_bytes()),
+      reveal_tx: hex::encode(consensus::encode::serialize(reveal_tx)),
+      sighash: TapSighashType::from(sighash) as u8,
+    }
+  }
+
+  /// Sign the commit input of the reveal transaction with `secret_key`,
+  /// after checking that the commit output commits to the reveal script and
+  /// the public key of `secret_key`.
+  pub fn sign(&self, secret_key: &secp256k1::SecretKey) -> Result<Transaction> {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::from_secret_key(&secp256k1, secret_key);
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    ensure!(
+      public_key.to_string() == self.public_key,
+      "key has public key {public_key} but reveal transaction is for {}",
+      self.public_key,
+    );
+
+    let mut reveal_tx: Transaction = consensus::encode::deserialize(&hex::decode(&self.reveal_tx)?)
+      .context("invalid reveal transaction")?;
+
+    ensure!(
+      reveal_tx.txid() == self.reveal,
+      "transaction ID {} does not match {}",
+      reveal_tx.txid(),
+      self.reveal,
+    );
+
+    ensure!(
+      reveal_tx.input.len() == 1 && reveal_tx.input[0].previous_output.txid == self.commit,
+      "reveal transaction must only spend commit transaction {}",
+      self.commit,
+    );
+
+    let commit_output: TxOut = consensus::encode::deserialize(&hex::decode(&self.commit_output)?)
+      .context("invalid commit output")?;
+
+    let reveal_script = ScriptBuf::from(hex::decode(&self.reveal_script)?);
+
+    let control_block = ControlBlock::decode(&hex::decode(&self.control_block)?)
+      .map_err(|err| anyhow!("invalid control block: {err}"))?;
+
+    let hash_ty = TapSighashType::from_consensus_u8(self.sighash)
+      .map_err(|err| anyhow!("invalid sighash: {err}"))?;
+
+    ensure!(
+      commit_output.script_pubkey
+        == ScriptBuf::new_v1_p2tr(
+          &secp256k1,
+          public_key,
+          Some(TapNodeHash::from_script(
+            &reveal_script,
+            LeafVersion::TapScript
+          )),
+        ),
+      "commit output does not commit to reveal script and public key {public_key}"
+    );
+
+    let mut sighash_cache = SighashCache::new(&mut reveal_tx);
+
+    let sighash = sighash_cache
+      .taproot_script_spend_signature_hash(
+        0,
+        &Prevouts::All(&[commit_output]),
+        TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+        hash_ty,
+      )
+      .expect("signature hash should compute");
+
+    let sig = secp256k1.sign_schnorr_with_rng(
+      &secp256k1::Message::from_slice(sighash.as_ref())
+        .expect("should be cryptographically secure hash"),
+      &key_pair,
+      &mut StdRng::from_entropy(),
+    );
+
+    let witness = sighash_cache
+      .witness_mut(0)
+      .expect("getting mutable witness reference should work");
+
+    witness.push(Signature { sig, hash_ty }.to_vec());
+    witness.push(reveal_script);
+    witness.push(control_block.serialize());
+
+    Ok(reveal_tx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cold_reveal(public_key: XOnlyPublicKey) -> ColdReveal {
+    let secp256k1 = Secp256k1::new();
+
+    let reveal_script = ScriptBuf::builder()
+      .push_slice(public_key.serialize())
+      .push_opcode(opcodes::all::OP_CHECKSIG)
+      .into_script();
+
+    let (_taproot_spend_info, control_block, commit_address) =
+      Plan::commit_taproot(&secp256k1, public_key, &reveal_script, Network::Bitcoin);
+
+    let commit_output = TxOut {
+      value: 20_000,
+      script_pubkey: commit_address.script_pubkey(),
+    };
+
+    let reveal_tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![tx_in(outpoint(1))],
+      output: vec![tx_out(10_000, recipient())],
+    };
+
+    ColdReveal::new(
+      &commit_output,
+      &control_block,
+      public_key,
+      &reveal_script,
+      &reveal_tx,
+      Sighash::Default,
+    )
+  }
+
+  #[test]
+  fn sign() {
+    let secp256k1 = Secp256k1::new();
+
+    let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+
+    let key_pair = UntweakedKeyPair::from_secret_key(&secp256k1, &secret_key);
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let cold_reveal = cold_reveal(public_key);
+
+    let reveal_tx = cold_reveal.sign(&secret_key).unwrap();
+
+    assert_eq!(reveal_tx.txid(), cold_reveal.reveal);
+
+    let witness = &reveal_tx.input[0].witness;
+
+    assert_eq!(witness.len(), 3);
+    assert_eq!(
+      hex::encode(witness.nth(1).unwrap()),
+      cold_reveal.reveal_script
+    );
+    assert_eq!(
+      hex::encode(witness.nth(2).unwrap()),
+      cold_reveal.control_block
+    );
+
+    let commit_output: TxOut =
+      consensus::encode::deserialize(&hex::decode(&cold_reveal.commit_output).unwrap()).unwrap();
+
+    let sighash = SighashCache::new(&reveal_tx)
+      .taproot_script_spend_signature_hash(
+        0,
+        &Prevouts::All(&[commit_output]),
+        TapLeafHash::from_script(
+          &ScriptBuf::from(hex::decode(&cold_reveal.reveal_script).unwrap()),
+          LeafVersion::TapScript,
+        ),
+        TapSighashType::Default,
+      )
+      .unwrap();
+
+    secp256k1
+      .verify_schnorr(
+        &secp256k1::schnorr::Signature::from_slice(witness.nth(0).unwrap()).unwrap(),
+        &secp256k1::Message::from_slice(sighash.as_ref()).unwrap(),
+        &public_key,
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn sign_with_wrong_key_fails() {
+    let secp256k1 = Secp256k1::new();
+
+    let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+
+    let (public_key, _parity) =
+      UntweakedKeyPair::from_secret_key(&secp256k1, &secret_key).x_only_public_key();
+
+    let cold_reveal = cold_reveal(public_key);
+
+    let wrong_key = secp256k1::SecretKey::from_slice(&[2; 32]).unwrap();
+
+    let (wrong_public_key, _parity) =
+      UntweakedKeyPair::from_secret_key(&secp256k1, &wrong_key).x_only_public_key();
+
+    assert_eq!(
+      cold_reveal.sign(&wrong_key).unwrap_err().to_string(),
+      format!("key has public key {wrong_public_key} but reveal transaction is for {public_key}"),
+    );
+  }
+
+  #[test]
+  fn sign_checks_commit_output() {
+    let secp256k1 = Secp256k1::new();
+
+    let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+
+    let (public_key, _parity) =
+      UntweakedKeyPair::from_secret_key(&secp256k1, &secret_key).x_only_public_key();
+
+    let mut cold_reveal = cold_reveal(public_key);
+
+    cold_reveal.commit_output =
+      hex::encode(consensus::encode::serialize(&tx_out(20_000, recipient())));
+
+    assert_eq!(
+      cold_reveal.sign(&secret_key).unwrap_err().to_string(),
+      format!("commit output does not commit to reveal script and public key {public_key}"),
+    );
+  }
+}
@@ -3,6 +3,7 @@ use super::*;
 #[derive(Serialize, Deserialize, Default, PartialEq, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Entry {
+  pub content_type: Option<String>,
   pub delegate: Option<InscriptionId>,
   pub destination: Option<Address<NetworkUnchecked>>,
   pub file: PathBuf,
@@ -108,7 +108,9 @@ impl File {
     wallet: &Wallet,
     utxos: &BTreeMap<OutPoint, TxOut>,
     parent_value: Option<u64>,
-    compress: bool,
+    compression: Compression,
+    validate_content: bool,
+    idempotency_key: Option<&str>,
   ) -> Result<(
     Vec<Inscription>,
     Vec<(SatPoint, TxOut)>,
@@ -131,7 +133,8 @@ impl File {
 
       inscriptions.push(Inscription::from_file(
         wallet.chain(),
-        compress,
+        compression,
+        entry.content_type.clone(),
         entry.delegate,
         entry.metadata()?,
         entry.metaprotocol.clone(),
@@ -141,6 +144,7 @@ impl File {
         self
           .etching
           .and_then(|etch| (i == 0).then_some(etch.rune.rune)),
+        validate_content,
       )?);
 
       let postage = if self.mode == Mode::SatPoints {
@@ -172,14 +176,22 @@ impl File {
       }
     }
 
+    let default_destination = |i: usize| match idempotency_key {
+      Some(idempotency_key) => {
+        wallet.get_idempotent_address(idempotency_key, &format!("destination {i}"))
+      }
+      None => wallet.get_change_address(),
+    };
+
     let destinations = match self.mode {
-      Mode::SharedOutput | Mode::SameSat => vec![wallet.get_change_address()?],
+      Mode::SharedOutput | Mode::SameSat => vec![default_destination(0)?],
       Mode::SeparateOutputs | Mode::SatPoints => self
         .inscriptions
         .iter()
-        .map(|entry| {
+        .enumerate()
+        .map(|(i, entry)| {
           entry.destination.as_ref().map_or_else(
-            || wallet.get_change_address(),
+            || default_destination(i),
             |address| {
               address
                 .clone()
@@ -424,6 +436,7 @@ inscriptions:
             ..default()
           },
           batch::Entry {
+            content_type: Some("application/json".into()),
             file: "token.json".into(),
             metaprotocol: Some("DOPEPROTOCOL-42069".into()),
             ..default()
@@ -1,185 +1,1530 @@
-use super::*;
+use {
+  super::*,
+  bitcoin::hashes::{
+    hmac::{Hmac, HmacEngine},
+    sha256, HashEngine,
+  },
+  std::io::Write,
+};
 
 pub struct Plan {
+  pub(crate) allow_duplicate: bool,
+  pub(crate) allow_rare: bool,
+  pub(crate) allowed_inscriptions: BTreeSet<InscriptionId>,
+  pub(crate) await_commit_confirmations: u16,
+  pub(crate) await_number: bool,
+  pub(crate) change_addresses: Option<Vec<Address>>,
+  pub(crate) change_split: Vec<Amount>,
+  pub(crate) check_rarity: bool,
+  pub(crate) cold_reveal_file: Option<PathBuf>,
+  pub(crate) cold_reveal_key: Option<XOnlyPublicKey>,
   pub(crate) commit_fee_rate: FeeRate,
+  pub(crate) commit_hash: Option<Vec<u8>>,
+  pub(crate) commit_locktime: LockTime,
+  pub(crate) commit_vout_first: bool,
+  pub(crate) confirm: bool,
+  pub(crate) consolidate: usize,
+  pub(crate) consolidate_max_fee: Option<Amount>,
+  pub(crate) deadline: Option<Instant>,
+  pub(crate) debug_sigs: bool,
   pub(crate) destinations: Vec<Address>,
   pub(crate) dry_run: bool,
+  pub(crate) dust_change_to_fee: Option<Amount>,
+  pub(crate) emit_reveal_key: bool,
   pub(crate) etching: Option<Etching>,
+  pub(crate) exclude_utxos: BTreeSet<OutPoint>,
+  pub(crate) from_label: Option<String>,
+  pub(crate) fund_reveal_separately: bool,
+  pub(crate) idempotency_key: Option<String>,
+  pub(crate) idempotency_secret: Option<[u8; 32]>,
   pub(crate) inscriptions: Vec<Inscription>,
+  pub(crate) key_seed: Option<[u8; 32]>,
+  pub(crate) label: Option<String>,
+  pub(crate) max_funding_utxo: Option<Amount>,
+  pub(crate) min_postage: Option<Amount>,
   pub(crate) mode: Mode,
   pub(crate) no_backup: bool,
   pub(crate) no_limit: bool,
+  pub(crate) no_rbf: bool,
   pub(crate) parent_info: Option<ParentInfo>,
+  pub(crate) plan: bool,
   pub(crate) postages: Vec<Amount>,
+  pub(crate) prepare_only: Option<PathBuf>,
+  pub(crate) psbt: bool,
+  pub(crate) recovery_key_file: Option<PathBuf>,
+  pub(crate) recovery_key_passphrase: Option<String>,
   pub(crate) reinscribe: bool,
+  pub(crate) reuse_change: bool,
+  pub(crate) reveal_annex: Option<Vec<u8>>,
+  pub(crate) reveal_csv: Option<u16>,
   pub(crate) reveal_fee_rate: FeeRate,
+  pub(crate) reveal_output_index: Option<u32>,
   pub(crate) reveal_satpoints: Vec<(SatPoint, TxOut)>,
+  pub(crate) reveal_tx_version: i32,
   pub(crate) satpoint: Option<SatPoint>,
+  pub(crate) sighash: Sighash,
+  pub(crate) signer_command: Option<String>,
+  pub(crate) skip_mempool_check: bool,
+  pub(crate) use_package_relay: bool,
+  pub(crate) warn_funding_utxo_ratio: u64,
 }
 
 impl Default for Plan {
   fn default() -> Self {
     Self {
+      allow_duplicate: false,
+      allow_rare: false,
+      allowed_inscriptions: BTreeSet::new(),
+      await_commit_confirmations: 0,
+      await_number: false,
+      change_addresses: None,
+      change_split: Vec::new(),
+      check_rarity: false,
+      cold_reveal_file: None,
+      cold_reveal_key: None,
       commit_fee_rate: 1.0.try_into().unwrap(),
+      commit_hash: None,
+      commit_locktime: LockTime::ZERO,
+      commit_vout_first: false,
+      confirm: false,
+      consolidate: 0,
+      consolidate_max_fee: None,
+      deadline: None,
+      debug_sigs: false,
       destinations: Vec::new(),
       dry_run: false,
+      dust_change_to_fee: None,
+      emit_reveal_key: false,
       etching: None,
+      exclude_utxos: BTreeSet::new(),
+      from_label: None,
+      fund_reveal_separately: false,
+      idempotency_key: None,
+      idempotency_secret: None,
       inscriptions: Vec::new(),
+      key_seed: None,
+      label: None,
+      max_funding_utxo: None,
+      min_postage: None,
       mode: Mode::SharedOutput,
       no_backup: false,
       no_limit: false,
+      no_rbf: false,
       parent_info: None,
+      plan: false,
       postages: vec![Amount::from_sat(10_000)],
+      prepare_only: None,
+      psbt: false,
+      recovery_key_file: None,
+      recovery_key_passphrase: None,
       reinscribe: false,
+      reuse_change: false,
+      reveal_annex: None,
+      reveal_csv: None,
       reveal_fee_rate: 1.0.try_into().unwrap(),
+      reveal_output_index: None,
       reveal_satpoints: Vec::new(),
+      reveal_tx_version: 2,
       satpoint: None,
+      sighash: Sighash::Default,
+      signer_command: None,
+      skip_mempool_check: false,
+      use_package_relay: false,
+      warn_funding_utxo_ratio: 1_000_000,
     }
   }
 }
 
 impl Plan {
+  /// Prefix of the labels of recovery descriptors imported into the wallet.
+  pub(crate) const RECOVERY_KEY_LABEL: &'static str = "commit tx recovery key";
+
   pub(crate) fn inscribe(
     &self,
-    locked_utxos: &BTreeSet<OutPoint>,
-    runic_utxos: BTreeSet<OutPoint>,
+    locked_utxos: &BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    wallet: &Wallet,
+  ) -> Result<Output> {
+    let start = Instant::now();
+
+    wallet.check_taproot_active()?;
+
+    self.check_commit_locktime(wallet)?;
+
+    // the transaction builder uses change addresses from last to first, so
+    // reverse the commit change addresses to use reused or given addresses
+    // first
+    let [commit_change_1, commit_change_0, reveal_change] = match &self.change_addresses {
+      Some(addresses) => [
+        addresses[0].clone(),
+        addresses[1].clone(),
+        match addresses.get(2) {
+          Some(address) => address.clone(),
+          None => wallet.get_change_address()?,
+        },
+      ],
+      None => match &self.idempotency_key {
+        Some(idempotency_key) => [
+          wallet.get_idempotent_address(idempotency_key, "commit change 1")?,
+          wallet.get_idempotent_address(idempotency_key, "commit change 0")?,
+          wallet.get_idempotent_address(idempotency_key, "reveal change")?,
+        ],
+        None => wallet.get_change_addresses(self.reuse_change)?,
+      },
+    };
+
+    let utxos: BTreeMap<OutPoint, TxOut> = if let Some(label) = &self.from_label {
+      let labeled = wallet.get_utxos_with_label(label)?;
+
+      ensure!(
+        !labeled.is_empty(),
+        "no unspent outputs with label `{label}` found in wallet"
+      );
+
+      utxos
+        .iter()
+        .filter(|(outpoint, _txout)| labeled.contains(outpoint))
+        .map(|(outpoint, txout)| (*outpoint, txout.clone()))
+        .collect()
+    } else {
+      utxos.clone()
+    };
+
+    let Transactions {
+      commit_address,
+      commit_tx,
+      cold_reveal,
+      internal_key,
+      reveal_tx,
+      reveal_key_pair,
+      recovery_key_pair,
+      total_fees,
+      rune,
+      satpoint,
+    } = self.create_batch_transactions(
+      wallet.inscriptions().clone(),
+      wallet.chain(),
+      locked_utxos.clone(),
+      runic_utxos,
+      utxos.clone(),
+      [commit_change_0, commit_change_1],
+      reveal_change,
+    )?;
+
+    let reveal_key = reveal_key_pair
+      .filter(|_| self.emit_reveal_key)
+      .map(|key_pair| PrivateKey::new(key_pair.secret_key(), wallet.chain().network()).to_wif());
+
+    log::info!(
+      "Built commit transaction {} and reveal transaction {} in {:?}",
+      commit_tx.txid(),
+      reveal_tx.txid(),
+      start.elapsed(),
+    );
+
+    if !self.allow_rare && self.mode != Mode::SatPoints {
+      self.check_rarity(wallet, satpoint)?;
+    }
+
+    let rarity = Self::rarity(wallet, satpoint);
+
+    if self.sighash.anyonecanpay() {
+      Warning::AnyoneCanPay {
+        sighash: self.sighash,
+      }
+      .emit();
+    }
+
+    if self.sighash.none() {
+      Warning::SighashNone {
+        sighash: self.sighash,
+      }
+      .emit();
+    }
+
+    if self.reveal_annex.is_some() {
+      Warning::NonStandardAnnex.emit();
+    }
+
+    if self.mode != Mode::SatPoints {
+      for postage in self
+        .postages
+        .iter()
+        .filter(|postage| **postage < TARGET_POSTAGE)
+        .collect::<BTreeSet<&Amount>>()
+      {
+        Warning::LowPostage { postage: *postage }.emit();
+      }
+    }
+
+    // content is witness data, so each byte weighs one weight unit
+    for size in self
+      .inscriptions
+      .iter()
+      .filter_map(Inscription::content_length)
+      .filter(|size| *size > MAX_STANDARD_TX_WEIGHT as usize / 4)
+    {
+      Warning::LargeContent { size }.emit();
+    }
+
+    self.check_funding_utxos(&commit_tx, &utxos, total_fees);
+
+    let package_relay = self.package_relay(wallet)?;
+
+    if !package_relay {
+      Self::check_commit_relay_fee(wallet, &commit_tx, &utxos)?;
+    }
+
+    self.check_idempotency(wallet, &commit_address, &commit_tx)?;
+
+    if !self.allow_duplicate {
+      self.check_duplicates(wallet)?;
+    }
+
+    self.check_deadline("building transactions")?;
+
+    if self.plan {
+      let plan = self.plan_info(
+        &commit_tx,
+        &commit_address,
+        &utxos,
+        total_fees,
+        satpoint,
+        recovery_key_pair.expect("recovery key pair is only missing for cold reveals"),
+        wallet.chain(),
+      );
+
+      let mut output = self.output(
+        commit_tx.txid(),
+        &commit_address,
+        internal_key,
+        reveal_key,
+        None,
+        reveal_tx.txid(),
+        None,
+        total_fees,
+        self.inscriptions.clone(),
+        rune,
+        rarity,
+      );
+
+      output.plan = Some(plan);
+
+      return Ok(output);
+    }
+
+    if self.dry_run {
+      return Ok(self.output(
+        commit_tx.txid(),
+        &commit_address,
+        internal_key,
+        reveal_key,
+        Some(Self::commit_psbt(wallet, &commit_tx)?),
+        reveal_tx.txid(),
+        Some(Self::reveal_psbt(&commit_tx, &reveal_tx)?),
+        total_fees,
+        self.inscriptions.clone(),
+        rune,
+        rarity,
+      ));
+    }
+
+    if let Some(cold_reveal) = cold_reveal {
+      return self.commit_cold_reveal(
+        wallet,
+        &cold_reveal,
+        &commit_address,
+        internal_key,
+        &commit_tx,
+        &reveal_tx,
+        satpoint,
+        &utxos,
+        total_fees,
+        rarity,
+      );
+    }
+
+    let recovery_key_pair =
+      recovery_key_pair.expect("recovery key pair is only missing for cold reveals");
+
+    if self.psbt {
+      if !self.no_backup {
+        self.backup_recovery_key(wallet, recovery_key_pair, &commit_address, reveal_tx.txid())?;
+      }
+
+      self.write_recovery_key(wallet, recovery_key_pair)?;
+
+      return Ok(self.output(
+        commit_tx.txid(),
+        &commit_address,
+        internal_key,
+        reveal_key,
+        Some(Self::commit_psbt(wallet, &commit_tx)?),
+        reveal_tx.txid(),
+        Some(Self::reveal_psbt(&commit_tx, &reveal_tx)?),
+        total_fees,
+        self.inscriptions.clone(),
+        rune,
+        rarity,
+      ));
+    }
+
+    if self.confirm {
+      self.confirm_broadcast(&commit_tx, &reveal_tx, satpoint, &utxos, total_fees)?;
+    }
+
+    let start = Instant::now();
+
+    let signed_commit_tx = self.sign_commit_transaction(wallet, &commit_tx, &utxos)?;
+
+    let result = wallet.bitcoin_client().sign_raw_transaction_with_wallet(
+      &reveal_tx,
+      Some(
+        &commit_tx
+          .output
+          .iter()
+          .enumerate()
+          .map(|(vout, output)| SignRawTransactionInput {
+            txid: commit_tx.txid(),
+            vout: vout.try_into().unwrap(),
+            script_pub_key: output.script_pubkey.clone(),
+            redeem_script: None,
+            amount: Some(Amount::from_sat(output.value)),
+          })
+          .collect::<Vec<SignRawTransactionInput>>(),
+      ),
+      None,
+    )?;
+
+    ensure!(
+      result.complete,
+      format!("Failed to sign reveal transaction: {:?}", result.errors)
+    );
+
+    let signed_reveal_tx = result.hex;
+
+    log::info!(
+      "Signed commit and reveal transactions in {:?}",
+      start.elapsed()
+    );
+
+    self.check_deadline("signing transactions")?;
+
+    // testmempoolaccept doesn't consider package fee rates, so would reject
+    // a commit transaction which relies on the reveal transaction's fee
+    if !self.skip_mempool_check && !package_relay {
+      self.check_mempool_acceptance(wallet, &signed_commit_tx, &signed_reveal_tx)?;
+      self.check_deadline("checking mempool acceptance")?;
+    }
+
+    if !self.no_backup {
+      self.backup_recovery_key(wallet, recovery_key_pair, &commit_address, reveal_tx.txid())?;
+    }
+
+    self.write_recovery_key(wallet, recovery_key_pair)?;
+
+    self.check_deadline("backing up recovery key")?;
+
+    if let Some(path) = &self.prepare_only {
+      ensure!(
+        self.etching.is_none(),
+        "--prepare-only cannot be used when etching a rune"
+      );
+
+      self.write_bundle(
+        wallet,
+        path,
+        &commit_tx,
+        &reveal_tx,
+        &signed_commit_tx,
+        &signed_reveal_tx,
+        recovery_key_pair,
+      )?;
+
+      let mut output = self.output(
+        commit_tx.txid(),
+        &commit_address,
+        internal_key,
+        reveal_key,
+        None,
+        reveal_tx.txid(),
+        None,
+        total_fees,
+        self.inscriptions.clone(),
+        rune,
+        rarity,
+      );
+
+      Self::record_sizes(&mut output, &signed_commit_tx, &signed_reveal_tx)?;
+
+      return Ok(output);
+    }
+
+    let (commit, reveal, package_msg) = if package_relay {
+      let start = Instant::now();
+
+      let (commit, reveal, package_msg) = self.broadcast_package(
+        wallet,
+        &commit_tx,
+        &reveal_tx,
+        &signed_commit_tx,
+        &signed_reveal_tx,
+        recovery_key_pair,
+      )?;
+
+      log::info!(
+        "Broadcast commit and reveal transactions as package in {:?}",
+        start.elapsed()
+      );
+
+      (commit, reveal, Some(package_msg))
+    } else {
+      let (commit, reveal) = self.broadcast_separately(
+        wallet,
+        &commit_tx,
+        &reveal_tx,
+        &signed_commit_tx,
+        &signed_reveal_tx,
+        recovery_key_pair,
+      )?;
+
+      (commit, reveal, None)
+    };
+
+    let mut output = self.output(
+      commit,
+      &commit_address,
+      internal_key,
+      reveal_key,
+      None,
+      reveal,
+      None,
+      total_fees,
+      self.inscriptions.clone(),
+      rune,
+      rarity,
+    );
+
+    output.package_msg = package_msg;
+
+    Self::record_sizes(&mut output, &signed_commit_tx, &signed_reveal_tx)?;
+
+    if self.await_number {
+      for inscription in &mut output.inscriptions {
+        inscription.number = Some(self.await_number(wallet, inscription.id, reveal)?);
+      }
+    }
+
+    Ok(output)
+  }
+
+  /// Refuse to broadcast a commit transaction whose locktime has not passed,
+  /// since the mempool would reject it as non-final.
+  fn check_commit_locktime(&self, wallet: &Wallet) -> Result {
+    if self.commit_locktime == LockTime::ZERO
+      || self.dry_run
+      || self.plan
+      || self.psbt
+      || self.prepare_only.is_some()
+    {
+      return Ok(());
+    }
+
+    let passed = match self.commit_locktime {
+      LockTime::Blocks(height) => {
+        u64::from(height.to_consensus_u32()) <= wallet.bitcoin_client().get_block_count()?
+      }
+      LockTime::Seconds(time) => {
+        u64::from(time.to_consensus_u32())
+          < wallet.bitcoin_client().get_blockchain_info()?.median_time
+      }
+    };
+
+    ensure!(
+      passed,
+      "commit locktime {} has not passed, so the commit transaction would be rejected by the \
+      mempool, use --prepare-only, --psbt, or --dry-run to create it without broadcasting",
+      self.commit_locktime.to_consensus_u32(),
+    );
+
+    Ok(())
+  }
+
+  /// Sign the commit transaction with the Bitcoin Core wallet, or with
+  /// `--signer-command` if given.
+  fn sign_commit_transaction(
+    &self,
+    wallet: &Wallet,
+    commit_tx: &Transaction,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<Vec<u8>> {
+    match &self.signer_command {
+      Some(command) => Self::sign_with_command(wallet, command, commit_tx, utxos),
+      None => Ok(
+        wallet
+          .bitcoin_client()
+          .sign_raw_transaction_with_wallet(commit_tx, None, None)?
+          .hex,
+      ),
+    }
+  }
+
+  /// Run `command` with the shell, write the base64-encoded unsigned commit
+  /// PSBT to its stdin, and read the signed commit transaction from its
+  /// stdout, either as a base64-encoded finalized PSBT or as hex.
+  fn sign_with_command(
+    wallet: &Wallet,
+    command: &str,
+    commit_tx: &Transaction,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<Vec<u8>> {
+    let mut psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?;
+
+    for (input, txin) in psbt.inputs.iter_mut().zip(&commit_tx.input) {
+      input.witness_utxo = Some(
+        utxos
+          .get(&txin.previous_output)
+          .ok_or_else(|| anyhow!("commit input {} not found in wallet", txin.previous_output))?
+          .clone(),
+      );
+    }
+
+    let (shell, flag) = if cfg!(windows) {
+      ("cmd", "/C")
+    } else {
+      ("sh", "-c")
+    };
+
+    let mut child = Command::new(shell)
+      .arg(flag)
+      .arg(command)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .with_context(|| format!("failed to run signer command `{command}`"))?;
+
+    // a command that fails without reading its input can't be written to, so
+    // check its exit status before reporting write errors
+    let write = writeln!(
+      child.stdin.take().unwrap(),
+      "{}",
+      base64::engine::general_purpose::STANDARD.encode(psbt.serialize())
+    );
+
+    let output = child
+      .wait_with_output()
+      .with_context(|| format!("failed to run signer command `{command}`"))?;
+
+    ensure!(
+      output.status.success(),
+      "signer command `{command}` failed with {}: {}",
+      output.status,
+      String::from_utf8_lossy(&output.stderr).trim(),
+    );
+
+    write.with_context(|| {
+      format!("failed to write commit transaction to signer command `{command}`")
+    })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = stdout.trim();
+
+    let signed: Transaction = if let Ok(hex) = hex::decode(stdout) {
+      consensus::encode::deserialize(&hex)
+        .with_context(|| format!("signer command `{command}` did not output a valid transaction"))?
+    } else {
+      base64::engine::general_purpose::STANDARD
+        .decode(stdout)
+        .ok()
+        .and_then(|psbt| Psbt::deserialize(&psbt).ok())
+        .with_context(|| {
+          format!("signer command `{command}` did not output a hex transaction or base64 PSBT")
+        })?
+        .extract_tx()
+    };
+
+    ensure!(
+      signed.txid() == commit_tx.txid(),
+      "signer command `{command}` returned transaction {} instead of commit transaction {}",
+      signed.txid(),
+      commit_tx.txid(),
+    );
+
+    if let Some(input) = signed.input.iter().position(|txin| txin.witness.is_empty()) {
+      bail!("signer command `{command}` did not sign commit transaction input {input}");
+    }
+
+    let signed_commit_tx = consensus::encode::serialize(&signed);
+
+    // fee rejections are left to the mempool check, since with package relay
+    // the commit transaction may pay less than the minimum relay fee
+    let result = wallet
+      .bitcoin_client()
+      .test_mempool_accept(&[signed_commit_tx.as_slice()])?
+      .into_iter()
+      .next()
+      .context("testmempoolaccept returned no result")?;
+
+    if let Some(reason) = result
+      .reject_reason
+      .filter(|reason| reason.contains("script-verify-flag-failed"))
+    {
+      bail!("signer command `{command}` returned invalid commit transaction: {reason}");
+    }
+
+    Ok(signed_commit_tx)
+  }
+
+  fn broadcast_separately(
+    &self,
+    wallet: &Wallet,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    recovery_key_pair: TweakedKeyPair,
+  ) -> Result<(Txid, Txid)> {
+    let start = Instant::now();
+
+    let commit = wallet.send_raw_transaction(signed_commit_tx)?;
+
+    log::info!(
+      "Broadcast commit transaction {commit} in {:?}",
+      start.elapsed()
+    );
+
+    let commit_output = reveal_tx
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .find(|outpoint| outpoint.txid == commit)
+      .expect("reveal transaction must spend commit output");
+
+    if let Some(maturity) = self.commit_confirmations() {
+      if self.etching.is_some() && maturity == Runestone::COMMIT_INTERVAL {
+        eprintln!("Waiting for rune commitment to mature…");
+      } else {
+        eprintln!("Waiting for commit transaction to reach {maturity} confirmations…");
+      }
+
+      // requests may time out when --timeout is given, so errors report the
+      // unbroadcast reveal transaction
+      let unbroadcast = || {
+        format!(
+          "commit transaction {commit} was broadcast but reveal transaction was not, broadcast \
+          it once the commit transaction matures: {}",
+          hex::encode(signed_reveal_tx)
+        )
+      };
+
+      let mut reported = 0;
+
+      loop {
+        Self::check_commit_output(wallet, commit_output)?;
+
+        if self.timed_out() {
+          bail!(
+            "timed out waiting for commit transaction to reach {maturity} confirmations: {}",
+            unbroadcast()
+          );
+        }
+
+        let transaction = wallet
+          .bitcoin_client()
+          .get_transaction(&commit_tx.txid(), Some(true))
+          .into_option()
+          .with_context(unbroadcast)?;
+
+        if let Some(transaction) = transaction {
+          // negative confirmations mean the commit transaction conflicts with
+          // a transaction in the chain
+          let Ok(confirmations) = u16::try_from(transaction.info.confirmations) else {
+            bail!(
+              "commit transaction {commit} conflicts with a confirmed transaction and was \
+              replaced: rebuild the reveal transaction against the replacement commit transaction"
+            );
+          };
+
+          if confirmations > reported && confirmations < maturity {
+            eprintln!("Commit transaction has {confirmations} of {maturity} confirmations…");
+            reported = confirmations;
+          }
+        }
+
+        let tx_out = wallet
+          .bitcoin_client()
+          .get_tx_out(&commit_output.txid, commit_output.vout, Some(true))
+          .with_context(unbroadcast)?;
+
+        if let Some(tx_out) = tx_out {
+          if tx_out.confirmations >= maturity.into() {
+            break;
+          }
+        }
+
+        if !wallet.integration_test() {
+          thread::sleep(Duration::from_secs(5));
+        }
+      }
+    }
+
+    Self::check_commit_output(wallet, commit_output)?;
+
+    let start = Instant::now();
+
+    let reveal = match wallet.send_raw_transaction(signed_reveal_tx) {
+      Ok(txid) => txid,
+      Err(err) => {
+        return Err(self.reveal_failed(
+          wallet,
+          err,
+          commit_tx,
+          reveal_tx,
+          signed_commit_tx,
+          signed_reveal_tx,
+          recovery_key_pair,
+        ))
+      }
+    };
+
+    log::info!(
+      "Broadcast reveal transaction {reveal} in {:?}",
+      start.elapsed()
+    );
+
+    Ok((commit, reveal))
+  }
+
+  /// Broadcast commit and reveal transactions as a package with
+  /// `submitpackage`, so the reveal transaction's fee can pay for the commit
+  /// transaction, and return the package acceptance message.
+  fn broadcast_package(
+    &self,
+    wallet: &Wallet,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    recovery_key_pair: TweakedKeyPair,
+  ) -> Result<(Txid, Txid, String)> {
+    #[derive(Deserialize)]
+    struct TxResult {
+      txid: Txid,
+      error: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct SubmitPackage {
+      package_msg: String,
+      #[serde(rename = "tx-results")]
+      tx_results: BTreeMap<String, TxResult>,
+    }
+
+    let package = wallet.bitcoin_client().call::<SubmitPackage>(
+      "submitpackage",
+      &[serde_json::to_value([
+        hex::encode(signed_commit_tx),
+        hex::encode(signed_reveal_tx),
+      ])?],
+    )?;
+
+    let commit = commit_tx.txid();
+    let reveal = reveal_tx.txid();
+
+    let accepted = |txid: Txid| {
+      package
+        .tx_results
+        .values()
+        .any(|result| result.txid == txid && result.error.is_none())
+    };
+
+    if package.package_msg == "success" {
+      return Ok((commit, reveal, package.package_msg));
+    }
+
+    let mut err = format!("package rejected: {}", package.package_msg);
+
+    for result in package.tx_results.values() {
+      if let Some(error) = &result.error {
+        err.push_str(&format!(", {}: {error}", result.txid));
+      }
+    }
+
+    if accepted(commit) {
+      return Err(self.reveal_failed(
+        wallet,
+        err,
+        commit_tx,
+        reveal_tx,
+        signed_commit_tx,
+        signed_reveal_tx,
+        recovery_key_pair,
+      ));
+    }
+
+    Err(anyhow!(err))
+  }
+
+  /// Save signed reveal transaction after commit transaction was broadcast
+  /// but reveal transaction was not, and return an error explaining how to
+  /// resume.
+  fn reveal_failed(
+    &self,
+    wallet: &Wallet,
+    err: impl Display,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    recovery_key_pair: TweakedKeyPair,
+  ) -> Error {
+    let commit = commit_tx.txid();
+
+    let path = PathBuf::from(format!("reveal-{}.json", reveal_tx.txid()));
+
+    // this file is written to the current directory without being asked for,
+    // so only include the recovery key if it can be encrypted
+    match self.save_bundle(
+      wallet,
+      &path,
+      commit_tx,
+      reveal_tx,
+      signed_commit_tx,
+      signed_reveal_tx,
+      self
+        .recovery_key_passphrase
+        .is_some()
+        .then_some(recovery_key_pair),
+    ) {
+      Ok(()) => anyhow!(
+        "Failed to send reveal transaction: {err}\nCommit tx {commit} was broadcast, signed \
+        reveal transaction was saved to `{}`, broadcast reveal transaction with `ord wallet \
+        resume-reveal {}`",
+        path.display(),
+        path.display(),
+      ),
+      Err(save_err) => anyhow!(
+        "Failed to send reveal transaction: {err}\nCommit tx {commit} will be recovered \
+        once mined\nFailed to save reveal transaction: {save_err}\nSigned reveal \
+        transaction: {}",
+        hex::encode(signed_reveal_tx),
+      ),
+    }
+  }
+
+  /// Whether to broadcast commit and reveal transactions as a package, which
+  /// requires --use-package-relay and Bitcoin Core 28.0 or newer.
+  fn package_relay(&self, wallet: &Wallet) -> Result<bool> {
+    const MIN_VERSION: usize = 280000;
+
+    if !self.use_package_relay {
+      return Ok(false);
+    }
+
+    ensure!(
+      self.etching.is_none(),
+      "--use-package-relay cannot be used when etching a rune"
+    );
+
+    let version = wallet.bitcoin_client().version()?;
+
+    if version < MIN_VERSION {
+      Warning::NoPackageRelay { version }.emit();
+      return Ok(false);
+    }
+
+    Ok(true)
+  }
+
+  fn commit_cold_reveal(
+    &self,
+    wallet: &Wallet,
+    cold_reveal: &ColdReveal,
+    commit_address: &Address,
+    internal_key: XOnlyPublicKey,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    satpoint: SatPoint,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    total_fees: u64,
+    rarity: Option<Rarity>,
+  ) -> Result<Output> {
+    let path = self
+      .cold_reveal_file
+      .as_ref()
+      .ok_or_else(|| anyhow!("--cold-reveal-key requires --cold-reveal-file"))?;
+
+    if self.confirm {
+      self.confirm_broadcast(commit_tx, reveal_tx, satpoint, utxos, total_fees)?;
+    }
+
+    let signed_commit_tx = self.sign_commit_transaction(wallet, commit_tx, utxos)?;
+
+    self.check_deadline("signing commit transaction")?;
+
+    // write the reveal transaction before broadcasting the commit transaction,
+    // since the commit output can only be spent by signing it offline
+    fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(path)
+      .and_then(|mut file| {
+        writeln!(
+          file,
+          "{}",
+          serde_json::to_string_pretty(cold_reveal).unwrap()
+        )
+      })
+      .with_context(|| format!("failed to write cold reveal file to `{}`", path.display()))?;
+
+    let commit = wallet.send_raw_transaction(&signed_commit_tx)?;
+
+    eprintln!(
+      "Commit transaction {commit} was broadcast, sign reveal transaction {} in `{}` offline with `ord cold-reveal sign` and broadcast it",
+      cold_reveal.reveal,
+      path.display(),
+    );
+
+    Ok(self.output(
+      commit,
+      commit_address,
+      internal_key,
+      None,
+      None,
+      cold_reveal.reveal,
+      None,
+      total_fees,
+      self.inscriptions.clone(),
+      None,
+      rarity,
+    ))
+  }
+
+  fn await_number(
+    &self,
+    wallet: &Wallet,
+    inscription_id: InscriptionId,
+    reveal: Txid,
+  ) -> Result<i32> {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+    eprintln!("Waiting for inscription {inscription_id} to be indexed…");
+
+    let deadline = self
+      .deadline
+      .unwrap_or_else(|| Instant::now() + DEFAULT_TIMEOUT);
+
+    loop {
+      if let Some(number) = wallet.inscription_number(inscription_id)? {
+        return Ok(number);
+      }
+
+      ensure!(
+        Instant::now() < deadline,
+        "timed out waiting for inscription {inscription_id} to be indexed: \
+        reveal transaction {reveal} was broadcast"
+      );
+
+      if !wallet.integration_test() {
+        thread::sleep(Duration::from_secs(5));
+      }
+    }
+  }
+
+  pub(crate) fn commit_taproot<C: secp256k1::Verification>(
+    secp256k1: &Secp256k1<C>,
+    public_key: XOnlyPublicKey,
+    reveal_script: &ScriptBuf,
+    network: Network,
+  ) -> (TaprootSpendInfo, ControlBlock, Address) {
+    let taproot_spend_info = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .expect("adding leaf should work")
+      .finalize(secp256k1, public_key)
+      .expect("finalizing taproot builder should work");
+
+    let control_block = taproot_spend_info
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .expect("should compute control block");
+
+    let address = Address::p2tr_tweaked(taproot_spend_info.output_key(), network);
+
+    (taproot_spend_info, control_block, address)
+  }
+
+  fn timed_out(&self) -> bool {
+    matches!(self.deadline, Some(deadline) if Instant::now() >= deadline)
+  }
+
+  fn check_deadline(&self, phase: &str) -> Result {
+    ensure!(
+      !self.timed_out(),
+      "timed out {phase}, nothing was broadcast"
+    );
+    Ok(())
+  }
+
+  fn check_commit_output(wallet: &Wallet, commit_output: OutPoint) -> Result {
+    ensure!(
+      wallet
+        .bitcoin_client()
+        .get_tx_out(&commit_output.txid, commit_output.vout, Some(true))?
+        .is_some(),
+      "commit output {commit_output} no longer exists, commit transaction was likely replaced: \
+      rebuild the reveal transaction against the replacement commit transaction"
+    );
+
+    Ok(())
+  }
+
+  /// Number of confirmations the commit transaction needs before the reveal
+  /// transaction's relative locktime expires, if it has one.
+  fn reveal_maturity(&self) -> Option<u16> {
+    self
+      .etching
+      .is_some()
+      .then_some(Runestone::COMMIT_INTERVAL)
+      .max(self.reveal_csv)
+  }
+
+  /// Number of confirmations to wait for before broadcasting the reveal
+  /// transaction, either because it is timelocked or because
+  /// `--await-commit-confirmations` was given.
+  fn commit_confirmations(&self) -> Option<u16> {
+    self
+      .reveal_maturity()
+      .max((self.await_commit_confirmations > 0).then_some(self.await_commit_confirmations))
+  }
+
+  fn check_mempool_acceptance(
+    &self,
+    wallet: &Wallet,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+  ) -> Result {
+    let mut transactions = vec![("commit", signed_commit_tx)];
+
+    // etching and --reveal-csv reveal transactions are timelocked until the
+    // commit transaction matures
+    if self.reveal_maturity().is_none() {
+      transactions.push(("reveal", signed_reveal_tx));
+    }
+
+    let results = wallet.bitcoin_client().test_mempool_accept(
+      &transactions
+        .iter()
+        .map(|(_name, transaction)| *transaction)
+        .collect::<Vec<&[u8]>>(),
+    )?;
+
+    for ((name, _transaction), result) in transactions.into_iter().zip(results) {
+      ensure!(
+        result.allowed,
+        "{name} transaction {} rejected by mempool: {}, use --skip-mempool-check to broadcast anyway",
+        result.txid,
+        result.reject_reason.unwrap_or_default(),
+      );
+    }
+
+    Ok(())
+  }
+
+  fn commit_psbt(wallet: &Wallet, commit_tx: &Transaction) -> Result<String> {
+    Ok(
+      wallet
+        .bitcoin_client()
+        .wallet_process_psbt(
+          &base64::engine::general_purpose::STANDARD
+            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
+          Some(false),
+          None,
+          None,
+        )?
+        .psbt,
+    )
+  }
+
+  fn reveal_psbt(commit_tx: &Transaction, reveal_tx: &Transaction) -> Result<String> {
+    let mut reveal_psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(reveal_tx.clone()))?;
+
+    for (input, txin) in reveal_psbt.inputs.iter_mut().zip(&reveal_tx.input) {
+      if txin.previous_output.txid == commit_tx.txid() {
+        input.witness_utxo =
+          Some(commit_tx.output[usize::try_from(txin.previous_output.vout).unwrap()].clone());
+      }
+
+      if !txin.witness.is_empty() {
+        input.final_script_witness = Some(txin.witness.clone());
+      }
+    }
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(reveal_psbt.serialize()))
+  }
+
+  fn rarity(wallet: &Wallet, satpoint: SatPoint) -> Option<Rarity> {
+    if !wallet.has_sat_index() {
+      return None;
+    }
+
+    let rarity = wallet.sat_at_satpoint(satpoint).map(Sat::rarity);
+
+    if rarity.is_none() {
+      Warning::UnknownRarity {
+        sat_index: true,
+        satpoint,
+      }
+      .emit();
+    }
+
+    rarity
+  }
+
+  fn check_rarity(&self, wallet: &Wallet, satpoint: SatPoint) -> Result {
+    if !wallet.has_sat_index() {
+      if self.check_rarity {
+        Warning::UnknownRarity {
+          sat_index: false,
+          satpoint,
+        }
+        .emit();
+      }
+
+      return Ok(());
+    }
+
+    if let Some(sat) = wallet.sat_at_satpoint(satpoint) {
+      let rarity = sat.rarity();
+
+      ensure!(
+        rarity == Rarity::Common,
+        "sat {sat} at {satpoint} is {rarity}, use --allow-rare to inscribe on it anyway"
+      );
+    }
+
+    Ok(())
+  }
+
+  fn confirm_broadcast(
+    &self,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    satpoint: SatPoint,
     utxos: &BTreeMap<OutPoint, TxOut>,
+    total_fees: u64,
+  ) -> Result {
+    let commit_fee = Self::calculate_fee(commit_tx, utxos);
+
+    let postage = reveal_tx
+      .output
+      .iter()
+      .filter(|output| {
+        self
+          .destinations
+          .iter()
+          .any(|destination| destination.script_pubkey() == output.script_pubkey)
+      })
+      .map(|output| output.value)
+      .sum::<u64>();
+
+    eprintln!("Inscribing at satpoint {satpoint}:");
+
+    for (inscription, destination) in self
+      .inscriptions
+      .iter()
+      .zip(self.destinations.iter().cycle())
+    {
+      eprintln!(
+        "  {} bytes of {} to {destination}",
+        inscription.body.as_ref().map(Vec::len).unwrap_or_default(),
+        inscription.content_type().unwrap_or("unknown content type"),
+      );
+    }
+
+    eprintln!("Commit fee: {commit_fee} sat");
+    eprintln!("Reveal fee: {} sat", total_fees - commit_fee);
+    eprintln!("Postage: {postage} sat");
+    eprintln!("Total cost: {} sat", total_fees + postage);
+    eprint!("Broadcast commit and reveal transactions? Type `yes` to continue: ");
+
+    let mut buffer = String::new();
+    io::stdin().read_line(&mut buffer)?;
+
+    ensure!(buffer.trim() == "yes", "aborted by user");
+
+    Ok(())
+  }
+
+  /// Warn if the commit transaction's fee rate is below the node's minimum
+  /// relay fee rate, since nodes without package relay will not accept it,
+  /// regardless of the reveal transaction's fee rate.
+  fn check_commit_relay_fee(
     wallet: &Wallet,
-  ) -> SubcommandResult {
-    let Transactions {
-      commit_tx,
-      reveal_tx,
-      recovery_key_pair,
-      total_fees,
-      rune,
-    } = self.create_batch_transactions(
-      wallet.inscriptions().clone(),
-      wallet.chain(),
-      locked_utxos.clone(),
-      runic_utxos,
-      utxos.clone(),
-      [wallet.get_change_address()?, wallet.get_change_address()?],
-      wallet.get_change_address()?,
+    commit_tx: &Transaction,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result {
+    #[derive(Deserialize)]
+    struct MempoolInfo {
+      minrelaytxfee: f64,
+    }
+
+    let min_relay_fee_rate = FeeRate::try_from(
+      Amount::from_btc(
+        wallet
+          .bitcoin_client()
+          .call::<MempoolInfo>("getmempoolinfo", &[])?
+          .minrelaytxfee,
+      )?
+      .to_sat() as f64
+        / 1000.0,
     )?;
 
-    if self.dry_run {
-      let commit_psbt = wallet
-        .bitcoin_client()
-        .wallet_process_psbt(
-          &base64::engine::general_purpose::STANDARD
-            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
-          Some(false),
-          None,
-          None,
-        )?
-        .psbt;
+    let mut signed_commit_tx = commit_tx.clone();
 
-      let reveal_psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(reveal_tx.clone()))?;
+    for txin in &mut signed_commit_tx.input {
+      txin.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+    }
 
-      return Ok(Some(Box::new(self.output(
-        commit_tx.txid(),
-        Some(commit_psbt),
-        reveal_tx.txid(),
-        Some(base64::engine::general_purpose::STANDARD.encode(reveal_psbt.serialize())),
-        total_fees,
-        self.inscriptions.clone(),
-        rune,
-      ))));
+    let fee_rate = FeeRate::try_from(
+      Self::calculate_fee(commit_tx, utxos) as f64 / signed_commit_tx.vsize() as f64,
+    )?;
+
+    if fee_rate.n() < min_relay_fee_rate.n() {
+      Warning::CommitBelowMinRelayFee {
+        fee_rate,
+        min_relay_fee_rate,
+      }
+      .emit();
     }
 
-    let signed_commit_tx = wallet
-      .bitcoin_client()
-      .sign_raw_transaction_with_wallet(&commit_tx, None, None)?
-      .hex;
+    Ok(())
+  }
 
-    let result = wallet.bitcoin_client().sign_raw_transaction_with_wallet(
-      &reveal_tx,
-      Some(
-        &commit_tx
-          .output
-          .iter()
-          .enumerate()
-          .map(|(vout, output)| SignRawTransactionInput {
-            txid: commit_tx.txid(),
-            vout: vout.try_into().unwrap(),
-            script_pub_key: output.script_pubkey.clone(),
-            redeem_script: None,
-            amount: Some(Amount::from_sat(output.value)),
-          })
-          .collect::<Vec<SignRawTransactionInput>>(),
-      ),
-      None,
-    )?;
+  /// Refuse to spend outputs holding inscriptions, other than the parent,
+  /// reinscribed sats, and inscriptions in `allowed_inscriptions`.
+  fn check_inscribed_inputs(
+    &self,
+    wallet_inscriptions: &BTreeMap<SatPoint, Vec<InscriptionId>>,
+    satpoint: SatPoint,
+    transactions: &[&Transaction],
+  ) -> Result {
+    let inputs = transactions
+      .iter()
+      .flat_map(|tx| tx.input.iter().map(|txin| txin.previous_output))
+      .collect::<BTreeSet<OutPoint>>();
 
-    ensure!(
-      result.complete,
-      format!("Failed to sign reveal transaction: {:?}", result.errors)
-    );
+    for (inscribed_satpoint, inscription_ids) in wallet_inscriptions {
+      if !inputs.contains(&inscribed_satpoint.outpoint) {
+        continue;
+      }
 
-    let signed_reveal_tx = result.hex;
+      if self.reinscribe
+        && (*inscribed_satpoint == satpoint
+          || self
+            .reveal_satpoints
+            .iter()
+            .any(|(reveal_satpoint, _txout)| reveal_satpoint == inscribed_satpoint))
+      {
+        continue;
+      }
 
-    if !self.no_backup {
-      Self::backup_recovery_key(wallet, recovery_key_pair)?;
+      for inscription_id in inscription_ids {
+        ensure!(
+          self.allowed_inscriptions.contains(inscription_id)
+            || self.parent_info.as_ref().map(|info| info.id) == Some(*inscription_id),
+          "transactions would spend output {} holding inscription {inscription_id}, pass --allow-spending-inscription {inscription_id} to allow",
+          inscribed_satpoint.outpoint,
+        );
+      }
     }
 
-    let commit = wallet
-      .bitcoin_client()
-      .send_raw_transaction(&signed_commit_tx)?;
+    Ok(())
+  }
 
-    if self.etching.is_some() {
-      eprintln!("Waiting for rune commitment to mature…");
+  /// Warn about commit transaction inputs worth more than
+  /// `--warn-funding-utxo-ratio` times the postage plus fees.
+  fn check_funding_utxos(
+    &self,
+    commit_tx: &Transaction,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    total_fees: u64,
+  ) {
+    let ratio = self.warn_funding_utxo_ratio;
 
-      loop {
-        let transaction = wallet
-          .bitcoin_client()
-          .get_transaction(&commit_tx.txid(), Some(true))
-          .into_option()?;
+    let cost = Amount::from_sat(
+      self
+        .postages
+        .iter()
+        .map(|postage| postage.to_sat())
+        .sum::<u64>()
+        + total_fees,
+    );
 
-        if let Some(transaction) = transaction {
-          if u32::try_from(transaction.info.confirmations).unwrap()
-            < Runestone::COMMIT_INTERVAL.into()
-          {
-            continue;
-          }
+    for input in &commit_tx.input {
+      let Some(txout) = utxos.get(&input.previous_output) else {
+        continue;
+      };
+
+      if txout.value > cost.to_sat().saturating_mul(ratio) {
+        Warning::LargeFundingUtxo {
+          cost,
+          output: input.previous_output,
+          ratio,
+          value: Amount::from_sat(txout.value),
         }
+        .emit();
+      }
+    }
+  }
 
-        let tx_out = wallet
-          .bitcoin_client()
-          .get_tx_out(&commit_tx.txid(), 0, Some(true))?;
+  /// Refuse to inscribe content identical to an inscription already in the
+  /// wallet, by comparing content hashes reported by the index. Both the
+  /// encoded and decoded body are compared, so compressing only the new
+  /// inscription does not hide a duplicate.
+  fn check_duplicates(&self, wallet: &Wallet) -> Result {
+    for inscription in &self.inscriptions {
+      let Some(content_hash) = inscription.content_hash() else {
+        continue;
+      };
 
-        if let Some(tx_out) = tx_out {
-          if tx_out.confirmations >= Runestone::COMMIT_INTERVAL.into() {
-            break;
-          }
-        }
+      let decoded_hash = inscription
+        .content_encoding
+        .as_ref()
+        .zip(inscription.body())
+        .and_then(|(encoding, body)| Compression::decode(encoding, body).ok())
+        .map(|body| sha256::Hash::hash(&body));
 
-        if !wallet.integration_test() {
-          thread::sleep(Duration::from_secs(5));
+      for (id, info) in wallet.inscription_info() {
+        if info.content_type.as_deref() != inscription.content_type() {
+          continue;
         }
+
+        let Some(hash) = info.content_hash else {
+          continue;
+        };
+
+        ensure!(
+          hash != content_hash && Some(hash) != decoded_hash,
+          "inscription content is identical to inscription {id} already in wallet, use --allow-duplicate to inscribe anyway"
+        );
       }
     }
 
-    let reveal = match wallet
-      .bitcoin_client()
-      .send_raw_transaction(&signed_reveal_tx)
-    {
-      Ok(txid) => txid,
-      Err(err) => {
-        return Err(anyhow!(
-        "Failed to send reveal transaction: {err}\nCommit tx {commit} will be recovered once mined"
-      ))
+    Ok(())
+  }
+
+  /// Derive reveal key pair from `seed` by hashing, rather than with a seeded
+  /// RNG, whose output is not guaranteed to be stable across `rand` versions.
+  pub(crate) fn seeded_key_pair(
+    secp256k1: &Secp256k1<secp256k1::All>,
+    seed: [u8; 32],
+  ) -> UntweakedKeyPair {
+    for counter in 0u32.. {
+      let mut engine = sha256::Hash::engine();
+      engine.input(b"ord/reveal-key");
+      engine.input(&seed);
+      engine.input(&counter.to_le_bytes());
+
+      // fails with negligible probability, if the hash is zero or not less
+      // than the curve order
+      if let Ok(key_pair) = UntweakedKeyPair::from_seckey_slice(
+        secp256k1,
+        sha256::Hash::from_engine(engine).as_byte_array(),
+      ) {
+        return key_pair;
       }
+    }
+
+    unreachable!()
+  }
+
+  /// Derive the reveal key seed for `--idempotency-key`. The seed depends on
+  /// what is being inscribed and where it is sent, but not on wallet UTXOs or
+  /// fee rates, so a retried run derives the same commit address even if the
+  /// commit transaction spends different outputs. It is keyed by the wallet's
+  /// idempotency secret, so knowing the idempotency key and the inscription is
+  /// not enough to derive the reveal key.
+  fn idempotent_key_seed(&self, idempotency_key: &str, chain: Chain) -> [u8; 32] {
+    let mut engine = HmacEngine::<sha256::Hash>::new(
+      &self
+        .idempotency_secret
+        .expect("idempotency secret is set with idempotency key"),
+    );
+
+    let mut input = |bytes: &[u8]| {
+      engine.input(&u64::try_from(bytes.len()).unwrap().to_le_bytes());
+      engine.input(bytes);
     };
 
-    Ok(Some(Box::new(self.output(
-      commit,
-      None,
-      reveal,
-      None,
-      total_fees,
-      self.inscriptions.clone(),
-      rune,
-    ))))
+    input(b"ord/idempotency-key");
+    input(idempotency_key.as_bytes());
+    input(chain.to_string().as_bytes());
+    input(serde_json::to_string(&self.mode).unwrap().as_bytes());
+    input(
+      Inscription::append_batch_reveal_script(&self.inscriptions, ScriptBuf::builder()).as_bytes(),
+    );
+
+    for destination in &self.destinations {
+      input(destination.script_pubkey().as_bytes());
+    }
+
+    for postage in &self.postages {
+      input(&postage.to_sat().to_le_bytes());
+    }
+
+    if let Some(parent_info) = &self.parent_info {
+      input(parent_info.id.to_string().as_bytes());
+    }
+
+    if let Some(satpoint) = self.satpoint {
+      input(satpoint.to_string().as_bytes());
+    }
+
+    Hmac::from_engine(engine).to_byte_array()
+  }
+
+  /// With `--idempotency-key`, a wallet transaction paying the commit address
+  /// was made by an earlier run with the same key. Rebroadcasting the same
+  /// commit transaction is harmless, but a different one would inscribe twice.
+  /// Earlier runs import the commit address recovery descriptor into the
+  /// wallet before broadcasting, so the wallet tracks payments to it.
+  fn check_idempotency(
+    &self,
+    wallet: &Wallet,
+    commit_address: &Address,
+    commit_tx: &Transaction,
+  ) -> Result {
+    #[derive(Deserialize)]
+    struct ReceivedByAddress {
+      txids: Vec<Txid>,
+    }
+
+    let Some(idempotency_key) = &self.idempotency_key else {
+      return Ok(());
+    };
+
+    for txid in wallet
+      .bitcoin_client()
+      .call::<Vec<ReceivedByAddress>>(
+        "listreceivedbyaddress",
+        &[
+          0.into(),
+          true.into(),
+          true.into(),
+          commit_address.to_string().into(),
+        ],
+      )?
+      .into_iter()
+      .flat_map(|received| received.txids)
+    {
+      ensure!(
+        txid == commit_tx.txid(),
+        "commit address {commit_address} for idempotency key `{idempotency_key}` was already funded by transaction {txid}, refusing to fund it again",
+      );
+    }
+
+    Ok(())
   }
 
   fn remove_witnesses(mut transaction: Transaction) -> Transaction {
@@ -190,15 +1535,47 @@ impl Plan {
     transaction
   }
 
+  /// Reveal output and offset of the sat at `pointer`, if it falls within
+  /// the parent or inscription outputs.
+  fn pointer_location(&self, pointer: u64) -> Option<(u32, u64)> {
+    let mut values = Vec::new();
+
+    if let Some(parent_info) = &self.parent_info {
+      values.push(parent_info.tx_out.value);
+    }
+
+    match self.mode {
+      Mode::SeparateOutputs => values.extend(self.postages.iter().map(|postage| postage.to_sat())),
+      Mode::SharedOutput => values.push(self.postages.iter().map(|postage| postage.to_sat()).sum()),
+      Mode::SameSat | Mode::SatPoints => return None,
+    }
+
+    let mut start = 0;
+
+    for (vout, value) in values.into_iter().enumerate() {
+      if pointer < start + value {
+        return Some((u32::try_from(vout).unwrap(), pointer - start));
+      }
+
+      start += value;
+    }
+
+    None
+  }
+
   fn output(
     &self,
     commit: Txid,
+    commit_address: &Address,
+    internal_key: XOnlyPublicKey,
+    reveal_key: Option<String>,
     commit_psbt: Option<String>,
     reveal: Txid,
     reveal_psbt: Option<String>,
     total_fees: u64,
     inscriptions: Vec<Inscription>,
     rune: Option<RuneInfo>,
+    rarity: Option<Rarity>,
   ) -> Output {
     let mut inscriptions_output = Vec::new();
     for i in 0..inscriptions.len() {
@@ -221,6 +1598,8 @@ impl Plan {
         }
       };
 
+      let vout = self.reveal_output_index.unwrap_or(vout);
+
       let offset = match self.mode {
         Mode::SharedOutput => self.postages[0..i]
           .iter()
@@ -229,12 +1608,29 @@ impl Plan {
         Mode::SeparateOutputs | Mode::SameSat | Mode::SatPoints => 0,
       };
 
+      let (vout, offset) = inscriptions[i]
+        .pointer()
+        .and_then(|pointer| self.pointer_location(pointer))
+        .unwrap_or((vout, offset));
+
       let destination = match self.mode {
         Mode::SameSat | Mode::SharedOutput => &self.destinations[0],
         Mode::SatPoints | Mode::SeparateOutputs => &self.destinations[i],
       };
 
+      let content_encoding = inscriptions[i].content_encoding.as_deref();
+
+      let bytes_saved = content_encoding
+        .zip(inscriptions[i].body.as_deref())
+        .and_then(|(encoding, body)| {
+          let decoded = Compression::decode(encoding, body).ok()?;
+          Some(u64::try_from(decoded.len().saturating_sub(body.len())).unwrap())
+        });
+
       inscriptions_output.push(InscriptionInfo {
+        bytes_saved,
+        content_encoding: content_encoding
+          .map(|encoding| String::from_utf8_lossy(encoding).into_owned()),
         id: InscriptionId {
           txid: reveal,
           index,
@@ -244,21 +1640,144 @@ impl Plan {
           outpoint: OutPoint { txid: reveal, vout },
           offset,
         },
+        number: None,
       });
     }
 
+    let mut inscriptions_by_output = BTreeMap::<u32, Vec<InscriptionId>>::new();
+
+    for inscription in &inscriptions_output {
+      inscriptions_by_output
+        .entry(inscription.location.outpoint.vout)
+        .or_default()
+        .push(inscription.id);
+    }
+
     Output {
       commit,
+      commit_address: uncheck(commit_address),
+      commit_internal_key: internal_key.to_string(),
       commit_psbt,
+      commit_vsize: None,
+      commit_weight: None,
       inscriptions: inscriptions_output,
+      inscriptions_by_output,
+      package_msg: None,
       parent: self.parent_info.clone().map(|info| info.id),
+      plan: None,
+      rarity,
       reveal,
+      reveal_key,
       reveal_psbt,
+      reveal_vsize: None,
+      reveal_weight: None,
       rune,
       total_fees,
     }
   }
 
+  /// Funding and change outputs, fees, and the recovery key fingerprint of
+  /// the unsigned commit transaction, for `--plan`. The fingerprint is the
+  /// first four bytes of the HASH160 of the recovery public key, like a BIP 32
+  /// key fingerprint.
+  fn plan_info(
+    &self,
+    commit_tx: &Transaction,
+    commit_address: &Address,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    total_fees: u64,
+    satpoint: SatPoint,
+    recovery_key_pair: TweakedKeyPair,
+    chain: Chain,
+  ) -> PlanInfo {
+    let commit_fee = Self::calculate_fee(commit_tx, utxos);
+
+    let fingerprint =
+      bitcoin::hashes::hash160::Hash::hash(&recovery_key_pair.to_inner().public_key().serialize());
+
+    PlanInfo {
+      change: commit_tx
+        .output
+        .iter()
+        .filter(|tx_out| tx_out.script_pubkey != commit_address.script_pubkey())
+        .map(|tx_out| ChangeInfo {
+          address: uncheck(
+            &chain
+              .address_from_script(&tx_out.script_pubkey)
+              .expect("change output should have an address"),
+          ),
+          value: tx_out.value,
+        })
+        .collect(),
+      commit_fee,
+      funding: commit_tx
+        .input
+        .iter()
+        .map(|txin| FundingInfo {
+          output: txin.previous_output,
+          value: utxos[&txin.previous_output].value,
+        })
+        .collect(),
+      postages: self
+        .postages
+        .iter()
+        .map(|postage| postage.to_sat())
+        .collect(),
+      recovery_key_fingerprint: hex::encode(&fingerprint[..4]),
+      reveal_fee: total_fees - commit_fee,
+      satpoint,
+    }
+  }
+
+  /// Record the vsize and weight of the signed commit and reveal transactions
+  /// in `output`.
+  fn record_sizes(output: &mut Output, signed_commit_tx: &[u8], signed_reveal_tx: &[u8]) -> Result {
+    let commit_tx: Transaction = consensus::encode::deserialize(signed_commit_tx)?;
+    let reveal_tx: Transaction = consensus::encode::deserialize(signed_reveal_tx)?;
+
+    output.commit_vsize = Some(commit_tx.vsize().try_into().unwrap());
+    output.commit_weight = Some(commit_tx.weight().to_wu());
+    output.reveal_vsize = Some(reveal_tx.vsize().try_into().unwrap());
+    output.reveal_weight = Some(reveal_tx.weight().to_wu());
+
+    Ok(())
+  }
+
+  /// Move the inscription output from after the parent output to reveal
+  /// output `index`. The inscribed sat is the first sat of the commit input,
+  /// so it only lands on the inscription output if every output moved ahead
+  /// of it is zero-value.
+  fn move_inscription_output(&self, reveal_outputs: &mut Vec<TxOut>, index: usize) -> Result {
+    let inscription_output = usize::from(self.parent_info.is_some());
+
+    ensure!(
+      self.destinations.len() == 1,
+      "reveal output index requires a single inscription output"
+    );
+
+    ensure!(
+      index >= inscription_output,
+      "reveal output index {index} would place inscription before parent output"
+    );
+
+    ensure!(
+      index < reveal_outputs.len(),
+      "reveal output index {index} out of range, reveal transaction has {} outputs",
+      reveal_outputs.len(),
+    );
+
+    let output = reveal_outputs.remove(inscription_output);
+    reveal_outputs.insert(index, output);
+
+    if let Some(vout) = (inscription_output..index).find(|vout| reveal_outputs[*vout].value > 0) {
+      bail!(
+        "reveal output {vout} would come before inscription output at index {index} but has nonzero value, so would receive the inscribed sat"
+      );
+    }
+
+    Ok(())
+  }
+
   pub(crate) fn create_batch_transactions(
     &self,
     wallet_inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
@@ -275,6 +1794,57 @@ impl Plan {
       }
     }
 
+    ensure!(
+      !self.emit_reveal_key || matches!(chain, Chain::Regtest | Chain::Signet),
+      "--emit-reveal-key exposes the reveal transaction key and may only be used on regtest or signet, not {chain}"
+    );
+
+    for (kind, address) in self
+      .destinations
+      .iter()
+      .map(|destination| ("destination", destination))
+      .chain(commit_change.iter().map(|change| ("change", change)))
+      .chain([("change", &reveal_change)])
+    {
+      ensure!(
+        uncheck(address).is_valid_for_network(chain.network()),
+        "{kind} address {address} is not valid for {chain}"
+      );
+    }
+
+    ensure!(
+      matches!(self.reveal_tx_version, 1 | 2),
+      "reveal transaction version must be 1 or 2, not {}",
+      self.reveal_tx_version,
+    );
+
+    ensure!(
+      self.etching.is_none() || self.reveal_tx_version >= 2,
+      "etching reveal transaction uses a relative locktime and requires --reveal-tx-version 2"
+    );
+
+    ensure!(
+      self.reveal_csv.is_none() || self.reveal_tx_version >= 2,
+      "--reveal-csv uses a relative locktime and requires --reveal-tx-version 2"
+    );
+
+    if let Some(annex) = &self.reveal_annex {
+      ensure!(
+        Annex::new(annex).is_ok(),
+        "reveal annex must begin with the taproot annex prefix 0x50"
+      );
+
+      ensure!(
+        self.cold_reveal_key.is_none(),
+        "reveal annex cannot be used with --cold-reveal-key"
+      );
+    }
+
+    ensure!(
+      self.etching.is_none() || !self.no_rbf,
+      "etching reveal transaction uses a relative locktime, which always signals replaceability, and cannot be used with --no-rbf"
+    );
+
     match self.mode {
       Mode::SameSat => {
         assert_eq!(
@@ -314,14 +1884,67 @@ impl Plan {
       }
     }
 
+    if let Some(satpoint) = self.satpoint {
+      ensure!(
+        !self.exclude_utxos.contains(&satpoint.outpoint),
+        "satpoint {satpoint} is in excluded utxo {}",
+        satpoint.outpoint,
+      );
+
+      ensure!(
+        utxos.contains_key(&satpoint.outpoint),
+        "satpoint {satpoint} outpoint {} is no longer unspent",
+        satpoint.outpoint,
+      );
+
+      let value = utxos[&satpoint.outpoint].value;
+
+      ensure!(
+        satpoint.offset < value,
+        "satpoint {satpoint} offset {} is not less than output value of {value} sat",
+        satpoint.offset,
+      );
+    }
+
+    utxos.retain(|outpoint, _txout| !self.exclude_utxos.contains(outpoint));
+
+    // an explicitly given satpoint may be in an output over the maximum, but
+    // no other output over the maximum may be used for funding
+    let over_max_funding_utxo = self
+      .max_funding_utxo
+      .map(|max_funding_utxo| {
+        utxos
+          .iter()
+          .filter(|(outpoint, txout)| {
+            txout.value > max_funding_utxo.to_sat()
+              && Some(**outpoint) != self.satpoint.map(|satpoint| satpoint.outpoint)
+          })
+          .map(|(outpoint, _txout)| *outpoint)
+          .collect::<BTreeSet<OutPoint>>()
+      })
+      .unwrap_or_default();
+
+    let mut locked_utxos = locked_utxos;
+
+    locked_utxos.extend(&over_max_funding_utxo);
+
+    let insufficient_funds = |message: &str| {
+      if over_max_funding_utxo.is_empty() {
+        InsufficientFunds(message.into())
+      } else {
+        InsufficientFunds(format!(
+          "{message}, {} cardinal utxos are worth more than --max-funding-utxo {}",
+          over_max_funding_utxo.len(),
+          self.max_funding_utxo.unwrap(),
+        ))
+      }
+    };
+
+    let inscribed_utxos = Wallet::inscribed_outpoints(&wallet_inscriptions);
+
     let satpoint = if let Some(satpoint) = self.satpoint {
       satpoint
     } else {
-      let inscribed_utxos = wallet_inscriptions
-        .keys()
-        .map(|satpoint| satpoint.outpoint)
-        .collect::<BTreeSet<OutPoint>>();
-
       utxos
         .iter()
         .find(|(outpoint, txout)| {
@@ -334,7 +1957,7 @@ impl Plan {
           outpoint: *outpoint,
           offset: 0,
         })
-        .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?
+        .ok_or_else(|| insufficient_funds("wallet contains no cardinal utxos"))?
     };
 
     let mut reinscription = false;
@@ -367,8 +1990,18 @@ impl Plan {
     }
 
     let secp256k1 = Secp256k1::new();
-    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
-    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+    let key_seed = match &self.idempotency_key {
+      Some(idempotency_key) => Some(self.idempotent_key_seed(idempotency_key, chain)),
+      None => self.key_seed,
+    };
+
+    let key_pair = match key_seed {
+      Some(seed) => Self::seeded_key_pair(&secp256k1, seed),
+      None => UntweakedKeyPair::new(&secp256k1, &mut StdRng::from_entropy()),
+    };
+    let public_key = self
+      .cold_reveal_key
+      .unwrap_or_else(|| XOnlyPublicKey::from_keypair(&key_pair).0);
 
     let reveal_script = Inscription::append_batch_reveal_script(
       &self.inscriptions,
@@ -377,17 +2010,8 @@ impl Plan {
         .push_opcode(opcodes::all::OP_CHECKSIG),
     );
 
-    let taproot_spend_info = TaprootBuilder::new()
-      .add_leaf(0, reveal_script.clone())
-      .expect("adding leaf should work")
-      .finalize(&secp256k1, public_key)
-      .expect("finalizing taproot builder should work");
-
-    let control_block = taproot_spend_info
-      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
-      .expect("should compute control block");
-
-    let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), chain.network());
+    let (taproot_spend_info, control_block, commit_tx_address) =
+      Self::commit_taproot(&secp256k1, public_key, &reveal_script, chain.network());
 
     let total_postage = self.postages.iter().map(|amount| amount.to_sat()).sum();
 
@@ -442,7 +2066,7 @@ impl Plan {
         destination = Some(reveal_change.clone());
 
         reveal_outputs.push(TxOut {
-          script_pubkey: reveal_change.into(),
+          script_pubkey: reveal_change.script_pubkey(),
           value: TARGET_POSTAGE.to_sat(),
         });
 
@@ -511,19 +2135,61 @@ impl Plan {
       runestone = None;
     }
 
+    if let Some(commit_hash) = &self.commit_hash {
+      ensure!(
+        runestone.is_none(),
+        "cannot add commit hash OP_RETURN output to reveal transaction with runestone"
+      );
+
+      ensure!(
+        self.no_limit || commit_hash.len() <= MAX_STANDARD_OP_RETURN_DATA,
+        "commit hash greater than maximum standard OP_RETURN data size: {} > {MAX_STANDARD_OP_RETURN_DATA}",
+        commit_hash.len(),
+      );
+
+      reveal_outputs.push(TxOut {
+        script_pubkey: ScriptBuf::new_op_return(
+          &<&script::PushBytes>::try_from(commit_hash.as_slice())
+            .context("commit hash too large")?,
+        ),
+        value: 0,
+      });
+    }
+
+    if let Some(index) = self.reveal_output_index {
+      self.move_inscription_output(&mut reveal_outputs, index.try_into().unwrap())?;
+    }
+
     let commit_input = usize::from(self.parent_info.is_some()) + self.reveal_satpoints.len();
 
-    let (_reveal_tx, reveal_fee) = Self::build_reveal_transaction(
-      commit_input,
-      &control_block,
+    let reveal_sequence = if let Some(maturity) = self.reveal_maturity() {
+      Sequence::from_height(maturity)
+    } else if self.no_rbf {
+      Sequence::MAX
+    } else {
+      Sequence::ENABLE_RBF_NO_LOCKTIME
+    };
+
+    let reveal_fee = Self::reveal_fee(
       self.reveal_fee_rate,
-      reveal_outputs.clone(),
-      reveal_inputs.clone(),
+      &Self::build_reveal_transaction(
+        reveal_outputs.clone(),
+        reveal_inputs.clone(),
+        reveal_sequence,
+        self.reveal_tx_version,
+      ),
+      commit_input,
       &reveal_script,
-      rune.is_some(),
+      &control_block,
+      self.sighash,
+      self.reveal_annex.as_deref(),
     );
 
-    let mut target_value = reveal_fee;
+    let mut target_value = if self.fund_reveal_separately {
+      Amount::ZERO
+    } else {
+      reveal_fee
+    };
 
     if self.mode != Mode::SatPoints {
       target_value += Amount::from_sat(total_postage);
@@ -533,18 +2199,44 @@ impl Plan {
       target_value += TARGET_POSTAGE;
     }
 
-    let unsigned_commit_tx = TransactionBuilder::new(
+    target_value = target_value.max(commit_tx_address.script_pubkey().dust_value());
+
+    let mut unsigned_commit_tx = TransactionBuilder::new(
       satpoint,
-      wallet_inscriptions,
+      wallet_inscriptions.clone(),
       utxos.clone(),
       locked_utxos.clone(),
-      runic_utxos,
+      runic_utxos.clone(),
       commit_tx_address.clone(),
       commit_change,
       self.commit_fee_rate,
       Target::Value(target_value),
     )
-    .build_transaction()?;
+    .change_split(self.change_split.clone())
+    .consolidate(self.consolidate, self.consolidate_max_fee)
+    .dust_change_to_fee(self.dust_change_to_fee)
+    .recipient_first(self.commit_vout_first)
+    .lock_time(self.commit_locktime)
+    .build_transaction()
+    .map_err(|err| match err {
+      wallet::transaction_builder::Error::NotEnoughCardinalUtxos
+        if !over_max_funding_utxo.is_empty() =>
+      {
+        insufficient_funds("wallet does not contain enough cardinal utxos").into()
+      }
+      err => anyhow::Error::from(err),
+    })?;
+
+    if self.no_rbf {
+      for input in &mut unsigned_commit_tx.input {
+        // a final sequence number would disable the commit locktime
+        input.sequence = if self.commit_locktime == LockTime::ZERO {
+          Sequence::MAX
+        } else {
+          Sequence::ENABLE_LOCKTIME_NO_RBF
+        };
+      }
+    }
 
     let (vout, _commit_output) = unsigned_commit_tx
       .output
@@ -558,21 +2250,119 @@ impl Plan {
       vout: vout.try_into().unwrap(),
     };
 
-    let (mut reveal_tx, _fee) = Self::build_reveal_transaction(
-      commit_input,
-      &control_block,
-      self.reveal_fee_rate,
+    let reveal_funding = if self.fund_reveal_separately {
+      let commit_inputs = unsigned_commit_tx
+        .input
+        .iter()
+        .map(|txin| txin.previous_output)
+        .collect::<BTreeSet<OutPoint>>();
+
+      let mut cardinals = utxos
+        .iter()
+        .filter(|(outpoint, txout)| {
+          txout.value > 0
+            && !commit_inputs.contains(outpoint)
+            && !inscribed_utxos.contains(outpoint)
+            && !locked_utxos.contains(outpoint)
+            && !runic_utxos.contains(outpoint)
+        })
+        .collect::<Vec<(&OutPoint, &TxOut)>>();
+
+      cardinals.sort_by_key(|(_outpoint, txout)| txout.value);
+
+      reveal_outputs.push(TxOut {
+        script_pubkey: reveal_change.script_pubkey(),
+        value: 0,
+      });
+
+      let dust_value = reveal_change.script_pubkey().dust_value().to_sat();
+
+      let mut funding = None;
+
+      for (outpoint, txout) in cardinals {
+        let mut inputs = reveal_inputs.clone();
+        inputs.push(*outpoint);
+
+        let fee = Self::reveal_fee(
+          self.reveal_fee_rate,
+          &Self::build_reveal_transaction(
+            reveal_outputs.clone(),
+            inputs,
+            reveal_sequence,
+            self.reveal_tx_version,
+          ),
+          commit_input,
+          &reveal_script,
+          &control_block,
+          self.sighash,
+          self.reveal_annex.as_deref(),
+        );
+
+        if let Some(change) = txout
+          .value
+          .checked_sub(fee.to_sat())
+          .filter(|change| *change >= dust_value)
+        {
+          funding = Some((*outpoint, txout.clone(), change));
+          break;
+        }
+      }
+
+      let Some((outpoint, txout, change)) = funding else {
+        return Err(
+          insufficient_funds(
+            "wallet contains no cardinal utxo large enough to fund reveal transaction",
+          )
+          .into(),
+        );
+      };
+
+      reveal_inputs.push(outpoint);
+      reveal_outputs.last_mut().unwrap().value = change;
+
+      Some(txout)
+    } else {
+      None
+    };
+
+    let mut reveal_tx = Self::build_reveal_transaction(
       reveal_outputs.clone(),
       reveal_inputs,
-      &reveal_script,
-      rune.is_some(),
+      reveal_sequence,
+      self.reveal_tx_version,
     );
 
-    for output in reveal_tx.output.iter() {
+    self.check_inscribed_inputs(
+      &wallet_inscriptions,
+      satpoint,
+      &[&unsigned_commit_tx, &reveal_tx],
+    )?;
+
+    let first_inscription_output = match self.reveal_output_index {
+      Some(index) => index.try_into().unwrap(),
+      None => usize::from(self.parent_info.is_some()),
+    };
+
+    let inscription_outputs =
+      first_inscription_output..first_inscription_output + self.destinations.len();
+
+    for (vout, output) in reveal_tx.output.iter().enumerate() {
+      let dust_value = output.script_pubkey.dust_value().to_sat();
+
       ensure!(
-        output.value >= output.script_pubkey.dust_value().to_sat(),
-        "commit transaction output would be dust"
+        output.value >= dust_value,
+        "reveal output {vout} value of {} sat is below dust value of {dust_value} sat for its script",
+        output.value,
       );
+
+      if let Some(min_postage) = self.min_postage {
+        ensure!(
+          !inscription_outputs.contains(&vout) || output.value >= min_postage.to_sat(),
+          "reveal output {vout} value of {} sat is below requested minimum postage of {} sat",
+          output.value,
+          min_postage.to_sat(),
+        );
+      }
     }
 
     let mut prevouts = Vec::new();
@@ -589,22 +2379,108 @@ impl Plan {
 
     prevouts.push(unsigned_commit_tx.output[vout].clone());
 
+    if let Some(txout) = reveal_funding {
+      prevouts.push(txout);
+    }
+
+    ensure!(
+      !self.sighash.single() || commit_input < reveal_tx.output.len(),
+      "{} requires a reveal output at index {commit_input} to match the commit input",
+      self.sighash,
+    );
+
+    if let Some(public_key) = self.cold_reveal_key {
+      ensure!(
+        reveal_tx.input.len() == 1 && self.etching.is_none(),
+        "--cold-reveal-key cannot be used with a parent, reveal satpoints, a separately funded reveal transaction, or when etching a rune"
+      );
+
+      // the reveal transaction is unsigned, so use its estimated vsize
+      let reveal_weight = bitcoin::Weight::from_wu(
+        u64::try_from(Self::reveal_vsize(
+          &reveal_tx,
+          commit_input,
+          &control_block,
+          &reveal_script,
+          self.sighash,
+          None,
+        ))
+        .unwrap()
+          * 4,
+      );
+
+      if !self.no_limit && reveal_weight > bitcoin::Weight::from_wu(MAX_STANDARD_TX_WEIGHT.into()) {
+        bail!(
+          "reveal transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT): {reveal_weight}"
+        );
+      }
+
+      let cold_reveal = ColdReveal::new(
+        &prevouts[commit_input],
+        &control_block,
+        public_key,
+        &reveal_script,
+        &reveal_tx,
+        self.sighash,
+      );
+
+      utxos.insert(
+        reveal_tx.input[commit_input].previous_output,
+        prevouts[commit_input].clone(),
+      );
+
+      return Ok(Transactions {
+        commit_address: commit_tx_address,
+        total_fees: Self::calculate_fee(&unsigned_commit_tx, &utxos)
+          + Self::calculate_fee(&reveal_tx, &utxos),
+        commit_tx: unsigned_commit_tx,
+        cold_reveal: Some(cold_reveal),
+        internal_key: public_key,
+        recovery_key_pair: None,
+        reveal_key_pair: None,
+        reveal_tx,
+        rune: None,
+        satpoint,
+      });
+    }
+
     let mut sighash_cache = SighashCache::new(&mut reveal_tx);
 
+    // the annex, if any, is committed to by the signature
     let sighash = sighash_cache
-      .taproot_script_spend_signature_hash(
+      .taproot_signature_hash(
         commit_input,
         &Prevouts::All(&prevouts),
-        TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
-        TapSighashType::Default,
+        self
+          .reveal_annex
+          .as_deref()
+          .map(|annex| Annex::new(annex).expect("annex should have taproot annex prefix")),
+        Some((
+          TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+          u32::MAX,
+        )),
+        self.sighash.into(),
       )
       .expect("signature hash should compute");
 
-    let sig = secp256k1.sign_schnorr(
-      &secp256k1::Message::from_slice(sighash.as_ref())
-        .expect("should be cryptographically secure hash"),
-      &key_pair,
-    );
+    let message = secp256k1::Message::from_slice(sighash.as_ref())
+      .expect("should be cryptographically secure hash");
+
+    // seeded reveals are signed without auxiliary randomness so that the
+    // reveal transaction is reproducible
+    let sig = match key_seed {
+      Some(_) => secp256k1.sign_schnorr_no_aux_rand(&message, &key_pair),
+      None => secp256k1.sign_schnorr_with_rng(&message, &key_pair, &mut StdRng::from_entropy()),
+    };
+
+    if self.debug_sigs {
+      eprintln!("reveal input {commit_input} public key: {public_key}");
+      eprintln!(
+        "reveal input {commit_input} sighash: {}",
+        hex::encode(sighash)
+      );
+      eprintln!("reveal input {commit_input} signature: {sig}");
+    }
 
     let witness = sighash_cache
       .witness_mut(commit_input)
@@ -613,7 +2489,7 @@ impl Plan {
     witness.push(
       Signature {
         sig,
-        hash_ty: TapSighashType::Default,
+        hash_ty: self.sighash.into(),
       }
       .to_vec(),
     );
@@ -621,15 +2497,22 @@ impl Plan {
     witness.push(reveal_script);
     witness.push(&control_block.serialize());
 
+    if let Some(annex) = &self.reveal_annex {
+      witness.push(annex);
+    }
+
     let recovery_key_pair = key_pair.tap_tweak(&secp256k1, taproot_spend_info.merkle_root());
 
     let (x_only_pub_key, _parity) = recovery_key_pair.to_inner().x_only_public_key();
-    assert_eq!(
-      Address::p2tr_tweaked(
-        TweakedPublicKey::dangerous_assume_tweaked(x_only_pub_key),
-        chain.network(),
-      ),
-      commit_tx_address
+
+    let recovery_address = Address::p2tr_tweaked(
+      TweakedPublicKey::dangerous_assume_tweaked(x_only_pub_key),
+      chain.network(),
+    );
+
+    ensure!(
+      recovery_address == commit_tx_address,
+      "recovery key derives address {recovery_address} instead of commit address {commit_tx_address}"
     );
 
     let reveal_weight = reveal_tx.weight();
@@ -671,15 +2554,26 @@ impl Plan {
     });
 
     Ok(Transactions {
+      commit_address: commit_tx_address,
       commit_tx: unsigned_commit_tx,
-      recovery_key_pair,
+      cold_reveal: None,
+      internal_key: public_key,
+      recovery_key_pair: Some(recovery_key_pair),
+      reveal_key_pair: Some(key_pair),
       reveal_tx,
       rune,
+      satpoint,
       total_fees,
     })
   }
 
-  fn backup_recovery_key(wallet: &Wallet, recovery_key_pair: TweakedKeyPair) -> Result {
+  fn backup_recovery_key(
+    &self,
+    wallet: &Wallet,
+    recovery_key_pair: TweakedKeyPair,
+    commit_address: &Address,
+    reveal: Txid,
+  ) -> Result {
     let recovery_private_key = PrivateKey::new(
       recovery_key_pair.to_inner().secret_key(),
       wallet.chain().network(),
@@ -689,16 +2583,33 @@ impl Plan {
       .bitcoin_client()
       .get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
 
+    let descriptor = format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum);
+
+    let addresses = wallet
+      .bitcoin_client()
+      .call::<Vec<Address<NetworkUnchecked>>>("deriveaddresses", &[descriptor.clone().into()])?;
+
+    ensure!(
+      addresses.len() == 1
+        && addresses[0].clone().assume_checked().script_pubkey() == commit_address.script_pubkey(),
+      "recovery descriptor derives {} instead of commit address {commit_address}, refusing to import it",
+      addresses
+        .iter()
+        .map(|address| address.clone().assume_checked().to_string())
+        .collect::<Vec<String>>()
+        .join(", "),
+    );
+
     let response = wallet
       .bitcoin_client()
       .import_descriptors(vec![ImportDescriptors {
-        descriptor: format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
+        descriptor,
         timestamp: Timestamp::Now,
         active: Some(false),
         range: None,
         next_index: None,
         internal: Some(false),
-        label: Some("commit tx recovery key".to_string()),
+        label: Some(self.recovery_key_label(reveal)),
       }])?;
 
     for result in response {
@@ -710,56 +2621,277 @@ impl Plan {
     Ok(())
   }
 
+  fn recovery_key_label(&self, reveal: Txid) -> String {
+    let id = InscriptionId {
+      txid: reveal,
+      index: 0,
+    };
+
+    match &self.label {
+      Some(label) => format!("{} {label} for {id}", Self::RECOVERY_KEY_LABEL),
+      None => format!("{} for {id}", Self::RECOVERY_KEY_LABEL),
+    }
+  }
+
+  fn write_recovery_key(&self, wallet: &Wallet, recovery_key_pair: TweakedKeyPair) -> Result {
+    let Some(path) = &self.recovery_key_file else {
+      return Ok(());
+    };
+
+    let wif = PrivateKey::new(
+      recovery_key_pair.to_inner().secret_key(),
+      wallet.chain().network(),
+    )
+    .to_wif();
+
+    let contents = match &self.recovery_key_passphrase {
+      Some(passphrase) => recovery_key::encrypt(&wif, passphrase)?,
+      None => {
+        Warning::PlaintextRecoveryKey { path: path.clone() }.emit();
+        wif
+      }
+    };
+
+    fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(path)
+      .and_then(|mut file| writeln!(file, "{contents}"))
+      .with_context(|| format!("failed to write recovery key to `{}`", path.display()))?;
+
+    Ok(())
+  }
+
+  fn write_bundle(
+    &self,
+    wallet: &Wallet,
+    path: &Path,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    recovery_key_pair: TweakedKeyPair,
+  ) -> Result {
+    self.save_bundle(
+      wallet,
+      path,
+      commit_tx,
+      reveal_tx,
+      signed_commit_tx,
+      signed_reveal_tx,
+      Some(recovery_key_pair),
+    )?;
+
+    // lock commit inputs so the wallet doesn't spend them before the bundle is
+    // broadcast
+    let inputs = commit_tx
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>();
+
+    ensure!(
+      wallet.bitcoin_client().lock_unspent(&inputs)?,
+      "failed to lock commit transaction inputs"
+    );
+
+    Ok(())
+  }
+
+  fn save_bundle(
+    &self,
+    wallet: &Wallet,
+    path: &Path,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    recovery_key_pair: Option<TweakedKeyPair>,
+  ) -> Result {
+    let recovery_key = match recovery_key_pair {
+      Some(recovery_key_pair) => {
+        let wif = PrivateKey::new(
+          recovery_key_pair.to_inner().secret_key(),
+          wallet.chain().network(),
+        )
+        .to_wif();
+
+        Some(match &self.recovery_key_passphrase {
+          Some(passphrase) => recovery_key::encrypt(&wif, passphrase)?,
+          None => {
+            Warning::PlaintextRecoveryKey {
+              path: path.to_path_buf(),
+            }
+            .emit();
+            wif
+          }
+        })
+      }
+      None => None,
+    };
+
+    let bundle = Bundle {
+      commit: commit_tx.txid(),
+      commit_tx: hex::encode(signed_commit_tx),
+      recovery_key,
+      reveal: reveal_tx.txid(),
+      reveal_tx: hex::encode(signed_reveal_tx),
+    };
+
+    fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(path)
+      .and_then(|mut file| writeln!(file, "{}", serde_json::to_string_pretty(&bundle).unwrap()))
+      .with_context(|| format!("failed to write bundle to `{}`", path.display()))?;
+
+    Ok(())
+  }
+
   fn build_reveal_transaction(
-    commit_input_index: usize,
-    control_block: &ControlBlock,
-    fee_rate: FeeRate,
     output: Vec<TxOut>,
     input: Vec<OutPoint>,
-    script: &Script,
-    etching: bool,
-  ) -> (Transaction, Amount) {
-    let reveal_tx = Transaction {
+    sequence: Sequence,
+    version: i32,
+  ) -> Transaction {
+    Transaction {
       input: input
         .into_iter()
         .map(|previous_output| TxIn {
           previous_output,
           script_sig: script::Builder::new().into_script(),
           witness: Witness::new(),
-          sequence: if etching {
-            Sequence::from_height(Runestone::COMMIT_INTERVAL)
-          } else {
-            Sequence::ENABLE_RBF_NO_LOCKTIME
-          },
+          sequence,
         })
         .collect(),
       output,
       lock_time: LockTime::ZERO,
-      version: 2,
-    };
+      version,
+    }
+  }
 
-    let fee = {
-      let mut reveal_tx = reveal_tx.clone();
+  /// Fee paid by `reveal_tx` at `fee_rate`. The fee is computed from the size
+  /// of `reveal_tx` once signed, with the commit input at `commit_input_index`
+  /// spending `reveal_script` with `control_block` and a `sighash` signature,
+  /// and the remaining inputs spent with key path signatures.
+  pub(crate) fn reveal_fee(
+    fee_rate: FeeRate,
+    reveal_tx: &Transaction,
+    commit_input_index: usize,
+    reveal_script: &Script,
+    control_block: &ControlBlock,
+    sighash: Sighash,
+    annex: Option<&[u8]>,
+  ) -> Amount {
+    fee_rate.fee(Self::reveal_vsize(
+      reveal_tx,
+      commit_input_index,
+      control_block,
+      reveal_script,
+      sighash,
+      annex,
+    ))
+  }
 
-      for (current_index, txin) in reveal_tx.input.iter_mut().enumerate() {
-        // add dummy inscription witness for reveal input/commit output
-        if current_index == commit_input_index {
-          txin.witness.push(
-            Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
-              .unwrap()
-              .to_vec(),
-          );
-          txin.witness.push(script);
-          txin.witness.push(&control_block.serialize());
-        } else {
-          txin.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
-        }
+  fn reveal_vsize(
+    reveal_tx: &Transaction,
+    commit_input_index: usize,
+    control_block: &ControlBlock,
+    script: &Script,
+    sighash: Sighash,
+    annex: Option<&[u8]>,
+  ) -> usize {
+    let mut reveal_tx = reveal_tx.clone();
+
+    for (current_index, txin) in reveal_tx.input.iter_mut().enumerate() {
+      // replace any existing witness with a dummy witness of the same size as
+      // the signed witness
+      if current_index == commit_input_index {
+        let mut witness = vec![
+          Signature {
+            sig: secp256k1::schnorr::Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE]).unwrap(),
+            hash_ty: sighash.into(),
+          }
+          .to_vec(),
+          script.to_bytes(),
+          control_block.serialize(),
+        ];
+
+        // the annex is the last witness element and counts toward the vsize
+        witness.extend(annex.map(<[u8]>::to_vec));
+
+        txin.witness = Witness::from_slice(&witness);
+      } else {
+        txin.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
       }
+    }
+
+    reveal_tx.vsize()
+  }
+
+  /// Estimate the vsizes of the commit and reveal transactions for
+  /// `inscription` without a wallet, and their total fees at `fee_rate`. The
+  /// commit transaction is assumed to spend a single taproot input and create
+  /// the commit output and a taproot change output, and the reveal
+  /// transaction to send the inscription to a taproot output.
+  pub fn estimate_vsizes(inscription: &Inscription, fee_rate: FeeRate) -> (usize, usize, u64) {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair = UntweakedKeyPair::from_seckey_slice(&secp256k1, &[1; 32])
+      .expect("dummy secret key should be valid");
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = Inscription::append_batch_reveal_script(
+      std::slice::from_ref(inscription),
+      ScriptBuf::builder()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let (_taproot_spend_info, control_block, commit_address) =
+      Self::commit_taproot(&secp256k1, public_key, &reveal_script, Network::Bitcoin);
+
+    let taproot_output = TxOut {
+      value: 0,
+      script_pubkey: commit_address.script_pubkey(),
+    };
 
-      fee_rate.fee(reveal_tx.vsize())
+    let commit_tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]),
+      }],
+      output: vec![taproot_output.clone(), taproot_output.clone()],
     };
 
-    (reveal_tx, fee)
+    let reveal_tx = Self::build_reveal_transaction(
+      vec![taproot_output],
+      vec![OutPoint::null()],
+      Sequence::ENABLE_RBF_NO_LOCKTIME,
+      2,
+    );
+
+    let commit_vsize = commit_tx.vsize();
+
+    let reveal_vsize = Self::reveal_vsize(
+      &reveal_tx,
+      0,
+      &control_block,
+      &reveal_script,
+      Sighash::Default,
+      None,
+    );
+
+    (
+      commit_vsize,
+      reveal_vsize,
+      (fee_rate.fee(commit_vsize) + fee_rate.fee(reveal_vsize)).to_sat(),
+    )
   }
 
   fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, TxOut>) -> u64 {
@@ -0,0 +1,58 @@
+use super::*;
+
+#[derive(clap::ValueEnum, Debug, Default, PartialEq, Copy, Clone)]
+pub enum Sighash {
+  #[default]
+  Default,
+  All,
+  None,
+  Single,
+  AllAnyonecanpay,
+  NoneAnyonecanpay,
+  SingleAnyonecanpay,
+}
+
+impl Sighash {
+  pub(crate) fn anyonecanpay(self) -> bool {
+    matches!(
+      self,
+      Self::AllAnyonecanpay | Self::NoneAnyonecanpay | Self::SingleAnyonecanpay
+    )
+  }
+
+  pub(crate) fn single(self) -> bool {
+    matches!(self, Self::Single | Self::SingleAnyonecanpay)
+  }
+
+  pub(crate) fn none(self) -> bool {
+    matches!(self, Self::None | Self::NoneAnyonecanpay)
+  }
+}
+
+impl From<Sighash> for TapSighashType {
+  fn from(sighash: Sighash) -> Self {
+    match sighash {
+      Sighash::Default => Self::Default,
+      Sighash::All => Self::All,
+      Sighash::None => Self::None,
+      Sighash::Single => Self::Single,
+      Sighash::AllAnyonecanpay => Self::AllPlusAnyoneCanPay,
+      Sighash::NoneAnyonecanpay => Self::NonePlusAnyoneCanPay,
+      Sighash::SingleAnyonecanpay => Self::SinglePlusAnyoneCanPay,
+    }
+  }
+}
+
+impl Display for Sighash {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Self::Default => "SIGHASH_DEFAULT",
+      Self::All => "SIGHASH_ALL",
+      Self::None => "SIGHASH_NONE",
+      Self::Single => "SIGHASH_SINGLE",
+      Self::AllAnyonecanpay => "SIGHASH_ALL|SIGHASH_ANYONECANPAY",
+      Self::NoneAnyonecanpay => "SIGHASH_NONE|SIGHASH_ANYONECANPAY",
+      Self::SingleAnyonecanpay => "SIGHASH_SINGLE|SIGHASH_ANYONECANPAY",
+    })
+  }
+}
@@ -3,8 +3,13 @@ use super::*;
 #[derive(Debug)]
 pub(crate) struct Transactions {
   pub(crate) rune: Option<RuneInfo>,
+  pub(crate) commit_address: Address,
   pub(crate) commit_tx: Transaction,
-  pub(crate) recovery_key_pair: TweakedKeyPair,
+  pub(crate) cold_reveal: Option<ColdReveal>,
+  pub(crate) internal_key: XOnlyPublicKey,
+  pub(crate) recovery_key_pair: Option<TweakedKeyPair>,
+  pub(crate) reveal_key_pair: Option<UntweakedKeyPair>,
   pub(crate) reveal_tx: Transaction,
+  pub(crate) satpoint: SatPoint,
   pub(crate) total_fees: u64,
 }
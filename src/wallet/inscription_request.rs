@@ -0,0 +1,169 @@
+use super::*;
+
+/// Builder for inscribing a file from library code, without going through
+/// `ord wallet inscribe` argument parsing or printing JSON output.
+///
+/// The request is funded and signed by a Bitcoin Core wallet, and requires a
+/// running `ord server`, just like `ord wallet inscribe`:
+///
+/// ```no_run
+/// use {
+///   clap::Parser,
+///   ord::{wallet::InscriptionRequest, Options},
+/// };
+///
+/// let options = Options::try_parse_from(["ord", "--regtest"])?;
+///
+/// let output = InscriptionRequest::new("hello.txt")
+///   .fee_rate("2.5".parse()?)
+///   .destination("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw".parse()?)
+///   .satpoint("1111111111111111111111111111111111111111111111111111111111111111:0:0".parse()?)
+///   .build(&options)?;
+///
+/// println!("commit: {}", output.commit);
+/// println!("reveal: {}", output.reveal);
+/// println!("inscription: {}", output.inscriptions[0].id);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct InscriptionRequest {
+  destination: Option<Address<NetworkUnchecked>>,
+  fee_rate: FeeRate,
+  file: PathBuf,
+  postage: Amount,
+  recovery_key_file: Option<PathBuf>,
+  satpoint: Option<SatPoint>,
+  server_url: Option<Url>,
+  wallet: String,
+}
+
+impl InscriptionRequest {
+  /// Request to inscribe the contents of `file`, at a fee rate of 1 sat/vB,
+  /// with the default postage, funded by the wallet named `ord`.
+  pub fn new(file: impl Into<PathBuf>) -> Self {
+    Self {
+      destination: None,
+      fee_rate: 1.0.try_into().unwrap(),
+      file: file.into(),
+      postage: TARGET_POSTAGE,
+      recovery_key_file: None,
+      satpoint: None,
+      server_url: None,
+      wallet: "ord".into(),
+    }
+  }
+
+  /// Send the inscription to `destination` instead of a wallet change address.
+  pub fn destination(self, destination: Address<NetworkUnchecked>) -> Self {
+    Self {
+      destination: Some(destination),
+      ..self
+    }
+  }
+
+  /// Use `fee_rate` for both commit and reveal transactions.
+  pub fn fee_rate(self, fee_rate: FeeRate) -> Self {
+    Self { fee_rate, ..self }
+  }
+
+  /// Include `postage` in the inscription output.
+  pub fn postage(self, postage: Amount) -> Self {
+    Self { postage, ..self }
+  }
+
+  /// Write the commit recovery key to `path`, in addition to backing it up to
+  /// the Bitcoin Core wallet.
+  pub fn recovery_key_file(self, path: impl Into<PathBuf>) -> Self {
+    Self {
+      recovery_key_file: Some(path.into()),
+      ..self
+    }
+  }
+
+  /// Inscribe the sat at `satpoint`, which must be in the wallet.
+  pub fn satpoint(self, satpoint: SatPoint) -> Self {
+    Self {
+      satpoint: Some(satpoint),
+      ..self
+    }
+  }
+
+  /// Use `ord server` at `server_url`, instead of the configured or default
+  /// server URL.
+  pub fn server_url(self, server_url: Url) -> Self {
+    Self {
+      server_url: Some(server_url),
+      ..self
+    }
+  }
+
+  /// Fund and sign with the Bitcoin Core wallet named `wallet`.
+  pub fn wallet(self, wallet: impl Into<String>) -> Self {
+    Self {
+      wallet: wallet.into(),
+      ..self
+    }
+  }
+
+  /// Create, sign, and broadcast the commit and reveal transactions, returning
+  /// the same output that `ord wallet inscribe` prints.
+  pub fn build(&self, options: &Options) -> Result<batch::Output> {
+    let settings = Settings::load(options.clone())?;
+
+    let server_url = match &self.server_url {
+      Some(server_url) => server_url.clone(),
+      None => settings
+        .server_url()
+        .unwrap_or("http://127.0.0.1:80")
+        .parse::<Url>()
+        .context("invalid server URL")?,
+    };
+
+    let wallet = Wallet::build(
+      self.wallet.clone(),
+      false,
+      false,
+      settings,
+      server_url,
+      None,
+    )?;
+
+    let chain = wallet.chain();
+
+    let inscription = Inscription::from_file(
+      chain,
+      Compression::None,
+      None,
+      None,
+      None,
+      None,
+      Vec::new(),
+      &self.file,
+      None,
+      None,
+      false,
+    )?;
+
+    let destination = match self.destination.clone() {
+      Some(destination) => destination.require_network(chain.network())?,
+      None => wallet.get_change_address()?,
+    };
+
+    let locked_utxos = wallet.locked_utxos().clone().into_keys().collect();
+
+    let runic_utxos = wallet.get_runic_outputs()?;
+
+    batch::Plan {
+      commit_fee_rate: self.fee_rate,
+      destinations: vec![destination],
+      inscriptions: vec![inscription],
+      mode: batch::Mode::SeparateOutputs,
+      postages: vec![self.postage],
+      recovery_key_file: self.recovery_key_file.clone(),
+      reveal_fee_rate: self.fee_rate,
+      satpoint: self.satpoint,
+      ..default()
+    }
+    .inscribe(&locked_utxos, runic_utxos, wallet.utxos(), &wallet)
+  }
+}
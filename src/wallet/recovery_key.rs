@@ -0,0 +1,176 @@
+use {
+  super::*,
+  bitcoin::PrivateKey,
+  ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2::{self, PBKDF2_HMAC_SHA256},
+    rand::{SecureRandom, SystemRandom},
+  },
+  std::num::NonZeroU32,
+};
+
+const PREFIX: &str = "ord-encrypted-recovery-key-v1:";
+const ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+fn key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey> {
+  let mut bytes = [0; KEY_LEN];
+
+  pbkdf2::derive(
+    PBKDF2_HMAC_SHA256,
+    NonZeroU32::new(ITERATIONS).unwrap(),
+    salt,
+    passphrase.as_bytes(),
+    &mut bytes,
+  );
+
+  Ok(LessSafeKey::new(
+    UnboundKey::new(&CHACHA20_POLY1305, &bytes)
+      .map_err(|_| anyhow!("failed to create recovery key encryption key"))?,
+  ))
+}
+
+pub(crate) fn is_encrypted(recovery_key: &str) -> bool {
+  recovery_key.starts_with(PREFIX)
+}
+
+/// Encrypts recovery key with ChaCha20-Poly1305, using a key derived from
+/// `passphrase` with PBKDF2-HMAC-SHA256 and a random salt.
+pub(crate) fn encrypt(recovery_key: &str, passphrase: &str) -> Result<String> {
+  let rng = SystemRandom::new();
+
+  let mut salt = [0; SALT_LEN];
+  let mut nonce = [0; NONCE_LEN];
+
+  rng
+    .fill(&mut salt)
+    .and_then(|()| rng.fill(&mut nonce))
+    .map_err(|_| anyhow!("failed to generate random salt and nonce"))?;
+
+  let mut ciphertext = recovery_key.as_bytes().to_vec();
+
+  key(passphrase, &salt)?
+    .seal_in_place_append_tag(
+      Nonce::assume_unique_for_key(nonce),
+      Aad::empty(),
+      &mut ciphertext,
+    )
+    .map_err(|_| anyhow!("failed to encrypt recovery key"))?;
+
+  Ok(format!(
+    "{PREFIX}{}{}{}",
+    hex::encode(salt),
+    hex::encode(nonce),
+    hex::encode(ciphertext),
+  ))
+}
+
+pub(crate) fn decrypt(encrypted: &str, passphrase: &str) -> Result<String> {
+  let data = encrypted
+    .strip_prefix(PREFIX)
+    .ok_or_else(|| anyhow!("recovery key is not encrypted"))?;
+
+  let data = hex::decode(data).context("invalid encrypted recovery key")?;
+
+  ensure!(
+    data.len() > SALT_LEN + NONCE_LEN,
+    "encrypted recovery key is truncated"
+  );
+
+  let (salt, data) = data.split_at(SALT_LEN);
+  let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+  let mut ciphertext = ciphertext.to_vec();
+
+  let plaintext = key(passphrase, salt)?
+    .open_in_place(
+      Nonce::try_assume_unique_for_key(nonce).unwrap(),
+      Aad::empty(),
+      &mut ciphertext,
+    )
+    .map_err(|_| anyhow!("failed to decrypt recovery key, passphrase may be incorrect"))?;
+
+  Ok(String::from_utf8(plaintext.to_vec())?)
+}
+
+/// Read recovery key in WIF format from `recovery_key_file` or stdin,
+/// decrypting it with `passphrase` if it is encrypted.
+pub(crate) fn load(
+  recovery_key_file: Option<PathBuf>,
+  passphrase: Option<&str>,
+) -> Result<PrivateKey> {
+  let wif: String = match recovery_key_file {
+    Some(path) => fs::read_to_string(&path)
+      .with_context(|| format!("I/O error reading `{}`", path.display()))?
+      .trim()
+      .into(),
+    None => {
+      let mut buffer = String::new();
+      io::stdin().read_line(&mut buffer)?;
+      buffer.trim().into()
+    }
+  };
+
+  let wif = if is_encrypted(&wif) {
+    let passphrase = passphrase.ok_or_else(|| {
+      anyhow!("recovery key is encrypted, use --recovery-key-passphrase to decrypt it")
+    })?;
+
+    decrypt(&wif, passphrase)?
+  } else {
+    wif
+  };
+
+  PrivateKey::from_wif(&wif).context("invalid recovery key")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const WIF: &str = "cVt4o7BGAig1UXywgGSmARhxMdzP5qvQsxKkSsc1XEkw3tDTQFpy";
+
+  #[test]
+  fn round_trip() {
+    let encrypted = encrypt(WIF, "foo").unwrap();
+
+    assert!(is_encrypted(&encrypted));
+    assert!(!encrypted.contains(WIF));
+    assert_eq!(decrypt(&encrypted, "foo").unwrap(), WIF);
+  }
+
+  #[test]
+  fn salt_and_nonce_are_random() {
+    assert_ne!(encrypt(WIF, "foo").unwrap(), encrypt(WIF, "foo").unwrap());
+  }
+
+  #[test]
+  fn wrong_passphrase_fails() {
+    assert_eq!(
+      decrypt(&encrypt(WIF, "foo").unwrap(), "bar")
+        .unwrap_err()
+        .to_string(),
+      "failed to decrypt recovery key, passphrase may be incorrect",
+    );
+  }
+
+  #[test]
+  fn tampered_ciphertext_fails() {
+    let mut encrypted = encrypt(WIF, "foo").unwrap();
+
+    let last = encrypted.pop().unwrap();
+    encrypted.push(if last == '0' { '1' } else { '0' });
+
+    assert!(decrypt(&encrypted, "foo").is_err());
+  }
+
+  #[test]
+  fn plaintext_is_not_encrypted() {
+    assert!(!is_encrypted(WIF));
+    assert_eq!(
+      decrypt(WIF, "foo").unwrap_err().to_string(),
+      "recovery key is not encrypted",
+    );
+  }
+}
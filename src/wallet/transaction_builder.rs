@@ -38,14 +38,20 @@ use {
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
+  ChangeSplitExceedsChange {
+    requested: Amount,
+    available: Amount,
+  },
   DuplicateAddress(Address),
   Dust {
     output_value: Amount,
     dust_value: Amount,
   },
   NotEnoughCardinalUtxos,
+  NotEnoughChangeAddresses,
   NotInWallet(SatPoint),
   OutOfRange(SatPoint, u64),
+  RecipientNotFirst(SatPoint),
   UtxoContainsAdditionalInscriptions {
     outgoing_satpoint: SatPoint,
     inscribed_satpoint: SatPoint,
@@ -64,6 +70,10 @@ pub enum Target {
 impl Display for Error {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
     match self {
+      Error::ChangeSplitExceedsChange {
+        requested,
+        available,
+      } => write!(f, "requested change split of {requested} exceeds available change of {available}"),
       Error::Dust {
         output_value,
         dust_value,
@@ -74,6 +84,8 @@ impl Display for Error {
         f,
         "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet."
       ),
+      Error::NotEnoughChangeAddresses => write!(f, "not enough change addresses to split change"),
+      Error::RecipientNotFirst(outgoing_satpoint) => write!(f, "cannot place recipient output first because outgoing satpoint {outgoing_satpoint} is not at the start of its output"),
       Error::UtxoContainsAdditionalInscriptions {
         outgoing_satpoint,
         inscribed_satpoint,
@@ -95,13 +107,19 @@ impl std::error::Error for Error {}
 pub struct TransactionBuilder {
   amounts: BTreeMap<OutPoint, TxOut>,
   change_addresses: BTreeSet<Address>,
+  change_split: Vec<Amount>,
+  consolidate: usize,
+  consolidate_max_fee: Option<Amount>,
+  dust_change_to_fee: Option<Amount>,
   fee_rate: FeeRate,
   inputs: Vec<OutPoint>,
   inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
+  lock_time: LockTime,
   locked_utxos: BTreeSet<OutPoint>,
   outgoing: SatPoint,
   outputs: Vec<(Address, Amount)>,
   recipient: Address,
+  recipient_first: bool,
   runic_utxos: BTreeSet<OutPoint>,
   target: Target,
   unused_change_addresses: Vec<Address>,
@@ -131,19 +149,69 @@ impl TransactionBuilder {
       utxos: amounts.keys().cloned().collect(),
       amounts,
       change_addresses: change.iter().cloned().collect(),
+      change_split: Vec::new(),
+      consolidate: 0,
+      consolidate_max_fee: None,
+      dust_change_to_fee: None,
       fee_rate,
       inputs: Vec::new(),
       inscriptions,
+      lock_time: LockTime::ZERO,
       locked_utxos,
       outgoing,
       outputs: Vec::new(),
       recipient,
+      recipient_first: false,
       runic_utxos,
       target,
       unused_change_addresses: change.to_vec(),
     }
   }
 
+  /// Split change into outputs of the given amounts, the last of which also
+  /// receives any remaining change.
+  pub fn change_split(self, change_split: Vec<Amount>) -> Self {
+    Self {
+      change_split,
+      ..self
+    }
+  }
+
+  /// Sweep up to `max_inputs` additional cardinal UTXOs, smallest first, into
+  /// the change output, skipping UTXOs worth less than the fee to spend them,
+  /// and stopping before the fee would exceed `max_fee`.
+  pub fn consolidate(self, max_inputs: usize, max_fee: Option<Amount>) -> Self {
+    Self {
+      consolidate: max_inputs,
+      consolidate_max_fee: max_fee,
+      ..self
+    }
+  }
+
+  /// Add change of less than the given threshold, after deducting the fee for
+  /// the change output, to the fee instead of creating a change output.
+  pub fn dust_change_to_fee(self, dust_change_to_fee: Option<Amount>) -> Self {
+    Self {
+      dust_change_to_fee,
+      ..self
+    }
+  }
+
+  /// Set the transaction's locktime. Inputs always signal replaceability,
+  /// which also enables the locktime.
+  pub fn lock_time(self, lock_time: LockTime) -> Self {
+    Self { lock_time, ..self }
+  }
+
+  /// Require the recipient output to be the first output, which fails if the
+  /// outgoing sat is not at the start of its output.
+  pub fn recipient_first(self, recipient_first: bool) -> Self {
+    Self {
+      recipient_first,
+      ..self
+    }
+  }
+
   pub fn build_transaction(self) -> Result<Transaction> {
     if self.change_addresses.len() < 2 {
       return Err(Error::DuplicateAddress(
@@ -171,10 +239,12 @@ impl TransactionBuilder {
 
     self
       .select_outgoing()?
-      .align_outgoing()
+      .align_outgoing()?
       .pad_alignment_output()?
       .add_value()?
       .strip_value()
+      .consolidate_utxos()
+      .split_change()?
       .deduct_fee()
       .build()
   }
@@ -226,7 +296,7 @@ impl TransactionBuilder {
     Ok(self)
   }
 
-  fn align_outgoing(mut self) -> Self {
+  fn align_outgoing(mut self) -> Result<Self> {
     assert_eq!(self.outputs.len(), 1, "invariant: only one output");
 
     assert_eq!(
@@ -238,6 +308,8 @@ impl TransactionBuilder {
 
     if sat_offset == 0 {
       tprintln!("outgoing is aligned");
+    } else if self.recipient_first {
+      return Err(Error::RecipientNotFirst(self.outgoing));
     } else {
       tprintln!("aligned outgoing with {sat_offset} sat padding output");
       self.outputs.insert(
@@ -253,7 +325,7 @@ impl TransactionBuilder {
       self.outputs.last_mut().expect("no output").1 -= Amount::from_sat(sat_offset);
     }
 
-    self
+    Ok(self)
   }
 
   fn pad_alignment_output(mut self) -> Result<Self> {
@@ -376,12 +448,139 @@ impl TransactionBuilder {
             .expect("not enough change addresses"),
           value - target,
         ));
+
+        if let Some(threshold) = self.dust_change_to_fee {
+          let change = (value - target)
+            .checked_sub(self.estimate_fee())
+            .unwrap_or_default();
+
+          if change < threshold {
+            tprintln!("added {} sat change to fee", change.to_sat());
+            let (address, _amount) = self.outputs.pop().expect("no change output found");
+            self.unused_change_addresses.push(address);
+            self.outputs.last_mut().expect("no outputs found").1 = target + self.estimate_fee();
+          }
+        }
       }
     }
 
     self
   }
 
+  fn consolidate_utxos(mut self) -> Self {
+    if self.consolidate == 0 {
+      return self;
+    }
+
+    if self.outputs.last().unwrap().0 == self.recipient {
+      tprintln!("no change output to consolidate into");
+      return self;
+    }
+
+    let additional_fee = self.fee_rate.fee(Self::ADDITIONAL_INPUT_VBYTES);
+
+    for _ in 0..self.consolidate {
+      if let Some(max_fee) = self.consolidate_max_fee {
+        let fee = self.fee_rate.fee(Self::estimate_vbytes_with(
+          self.inputs.len() + 1,
+          self
+            .outputs
+            .iter()
+            .map(|(address, _amount)| address)
+            .cloned()
+            .collect(),
+        ));
+
+        if fee > max_fee {
+          tprintln!("stopped consolidating at {max_fee} fee cap");
+          break;
+        }
+      }
+
+      let Ok((utxo, value)) = self.select_cardinal_utxo(additional_fee.to_sat() + 1, false) else {
+        break;
+      };
+
+      if value <= additional_fee {
+        tprintln!("remaining cardinals are not worth consolidating");
+        break;
+      }
+
+      self.inputs.push(utxo);
+      self.outputs.last_mut().unwrap().1 += value;
+
+      tprintln!("consolidated {value} sat input into change");
+    }
+
+    self
+  }
+
+  fn split_change(mut self) -> Result<Self> {
+    if self.change_split.is_empty() {
+      return Ok(self);
+    }
+
+    let requested = self
+      .change_split
+      .iter()
+      .try_fold(Amount::ZERO, |sum, amount| sum.checked_add(*amount))
+      .ok_or(Error::ValueOverflow)?;
+
+    let (address, change) = self.outputs.last().unwrap().clone();
+
+    if address == self.recipient {
+      return Err(Error::ChangeSplitExceedsChange {
+        requested,
+        available: Amount::ZERO,
+      });
+    }
+
+    self.outputs.pop();
+
+    let mut addresses = vec![address];
+
+    while addresses.len() < self.change_split.len() {
+      addresses.push(
+        self
+          .unused_change_addresses
+          .pop()
+          .ok_or(Error::NotEnoughChangeAddresses)?,
+      );
+    }
+
+    for (address, amount) in addresses.into_iter().zip(self.change_split.iter().copied()) {
+      let dust_value = address.script_pubkey().dust_value();
+
+      if amount < dust_value {
+        return Err(Error::Dust {
+          output_value: amount,
+          dust_value,
+        });
+      }
+
+      self.outputs.push((address, amount));
+    }
+
+    let available = change.checked_sub(self.estimate_fee()).unwrap_or_default();
+
+    if requested > available {
+      return Err(Error::ChangeSplitExceedsChange {
+        requested,
+        available,
+      });
+    }
+
+    tprintln!(
+      "split {} sat change into {} outputs",
+      change.to_sat(),
+      self.change_split.len()
+    );
+
+    self.outputs.last_mut().unwrap().1 += change - requested;
+
+    Ok(self)
+  }
+
   fn deduct_fee(mut self) -> Self {
     let sat_offset = self.calculate_sat_offset();
 
@@ -462,7 +661,7 @@ impl TransactionBuilder {
     let recipient = self.recipient.script_pubkey();
     let transaction = Transaction {
       version: 2,
-      lock_time: LockTime::ZERO,
+      lock_time: self.lock_time,
       input: self
         .inputs
         .iter()
@@ -517,6 +716,17 @@ impl TransactionBuilder {
     }
     assert!(found, "invariant: outgoing sat is found in inputs");
 
+    let inscribed_utxos = Wallet::inscribed_outpoints(&self.inscriptions);
+
+    assert!(
+      transaction
+        .input
+        .iter()
+        .all(|tx_in| tx_in.previous_output == self.outgoing.outpoint
+          || !inscribed_utxos.contains(&tx_in.previous_output)),
+      "invariant: only outgoing input may be inscribed",
+    );
+
     let mut output_end = 0;
     let mut found = false;
     for tx_out in &transaction.output {
@@ -542,6 +752,11 @@ impl TransactionBuilder {
       "invariant: recipient address appears exactly once in outputs",
     );
 
+    assert!(
+      !self.recipient_first || transaction.output[0].script_pubkey == recipient,
+      "invariant: recipient output is first if required",
+    );
+
     assert!(
       self
         .change_addresses
@@ -618,10 +833,19 @@ impl TransactionBuilder {
     }
     let expected_fee = self.fee_rate.fee(modified_tx.vsize());
 
-    assert_eq!(
-      actual_fee, expected_fee,
-      "invariant: fee estimation is correct",
-    );
+    if let Some(threshold) = self.dust_change_to_fee {
+      assert!(
+        actual_fee >= expected_fee
+          && actual_fee - expected_fee
+            <= threshold + self.fee_rate.fee(Self::ADDITIONAL_OUTPUT_VBYTES),
+        "invariant: fee estimation is correct",
+      );
+    } else {
+      assert_eq!(
+        actual_fee, expected_fee,
+        "invariant: fee estimation is correct",
+      );
+    }
 
     for tx_out in &transaction.output {
       assert!(
@@ -661,11 +885,7 @@ impl TransactionBuilder {
       if prefer_under { "smaller" } else { "bigger" }
     );
 
-    let inscribed_utxos = self
-      .inscriptions
-      .keys()
-      .map(|satpoint| satpoint.outpoint)
-      .collect::<BTreeSet<OutPoint>>();
+    let inscribed_utxos = Wallet::inscribed_outpoints(&self.inscriptions);
 
     let mut best_match = None;
     for utxo in &self.utxos {
@@ -778,8 +998,14 @@ mod tests {
       locked_utxos: BTreeSet::new(),
       runic_utxos: BTreeSet::new(),
       recipient: recipient(),
+      recipient_first: false,
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      change_split: Vec::new(),
+      consolidate: 0,
+      consolidate_max_fee: None,
+      lock_time: LockTime::ZERO,
+      dust_change_to_fee: None,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -869,6 +1095,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .align_outgoing()
+    .unwrap()
     .strip_value()
     .deduct_fee();
   }
@@ -1168,6 +1395,165 @@ mod tests {
     )
   }
 
+  #[test]
+  fn lock_time_is_set_and_enabled_by_input_sequences() {
+    let transaction = TransactionBuilder::new(
+      satpoint(1, 0),
+      BTreeMap::new(),
+      [(outpoint(1), tx_out(10_000, address()))]
+        .into_iter()
+        .collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      recipient(),
+      [change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      Target::Postage,
+    )
+    .lock_time(LockTime::from_height(800_000).unwrap())
+    .build_transaction()
+    .unwrap();
+
+    assert_eq!(
+      transaction.lock_time,
+      LockTime::from_height(800_000).unwrap()
+    );
+
+    for input in &transaction.input {
+      assert!(input.sequence.enables_absolute_lock_time());
+      assert!(input.sequence.is_rbf());
+    }
+  }
+
+  #[test]
+  fn consolidate_sweeps_small_cardinal_utxos_into_change() {
+    let builder = || {
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::from([(satpoint(4, 0), vec![inscription_id(1)])]),
+        [
+          (outpoint(1), tx_out(20_000, address())),
+          (outpoint(2), tx_out(1_000, address())),
+          (outpoint(3), tx_out(2_000, address())),
+          (outpoint(4), tx_out(3_000, address())),
+          (outpoint(5), tx_out(50, address())),
+        ]
+        .into_iter()
+        .collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(10_000)),
+      )
+    };
+
+    let fee = |inputs| {
+      FeeRate::try_from(1.0)
+        .unwrap()
+        .fee(TransactionBuilder::estimate_vbytes_with(
+          inputs,
+          vec![recipient(), change(1)],
+        ))
+    };
+
+    pretty_assert_eq!(
+      builder().consolidate(10, None).build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2)), tx_in(outpoint(3))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(23_000 - 10_000 - fee(3).to_sat(), change(1)),
+        ],
+      })
+    );
+
+    pretty_assert_eq!(
+      builder().consolidate(1, None).build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(21_000 - 10_000 - fee(2).to_sat(), change(1)),
+        ],
+      })
+    );
+
+    pretty_assert_eq!(
+      builder().consolidate(10, Some(fee(2))).build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(21_000 - 10_000 - fee(2).to_sat(), change(1)),
+        ],
+      })
+    );
+  }
+
+  #[test]
+  fn consolidate_requires_change_output() {
+    let transaction = TransactionBuilder::new(
+      satpoint(1, 0),
+      BTreeMap::new(),
+      [
+        (outpoint(1), tx_out(10_000, address())),
+        (outpoint(2), tx_out(1_000, address())),
+      ]
+      .into_iter()
+      .collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      recipient(),
+      [change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      Target::Postage,
+    )
+    .consolidate(10, None)
+    .build_transaction()
+    .unwrap();
+
+    assert_eq!(transaction.input, vec![tx_in(outpoint(1))]);
+    assert_eq!(transaction.output.len(), 1);
+  }
+
+  #[test]
+  fn recipient_first_requires_aligned_sat() {
+    let utxos = vec![(outpoint(1), tx_out(10_000, address()))];
+
+    let builder = |offset| {
+      TransactionBuilder::new(
+        satpoint(1, offset),
+        BTreeMap::new(),
+        utxos.clone().into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Postage,
+      )
+      .recipient_first(true)
+    };
+
+    pretty_assert_eq!(
+      builder(3_333).build_transaction(),
+      Err(Error::RecipientNotFirst(satpoint(1, 3_333)))
+    );
+
+    pretty_assert_eq!(
+      builder(0).build_transaction().unwrap().output[0].script_pubkey,
+      recipient().script_pubkey(),
+    );
+  }
+
   #[test]
   fn alignment_output_under_dust_limit_is_padded() {
     let utxos = vec![
@@ -1216,6 +1602,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .align_outgoing()
+    .unwrap()
     .add_value()
     .unwrap()
     .strip_value()
@@ -1245,6 +1632,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .align_outgoing()
+    .unwrap()
     .add_value()
     .unwrap()
     .strip_value()
@@ -1317,8 +1705,14 @@ mod tests {
       outgoing: satpoint(1, 0),
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
+      recipient_first: false,
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      change_split: Vec::new(),
+      consolidate: 0,
+      consolidate_max_fee: None,
+      lock_time: LockTime::ZERO,
+      dust_change_to_fee: None,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1348,8 +1742,14 @@ mod tests {
       outgoing: satpoint(1, 0),
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
+      recipient_first: false,
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      change_split: Vec::new(),
+      consolidate: 0,
+      consolidate_max_fee: None,
+      lock_time: LockTime::ZERO,
+      dust_change_to_fee: None,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1495,6 +1895,60 @@ mod tests {
     )
   }
 
+  #[test]
+  fn change_below_threshold_is_added_to_fee() {
+    let utxos = vec![(outpoint(1), tx_out(5_000, address()))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000))
+      )
+      .dust_change_to_fee(Some(Amount::from_sat(4000)))
+      .build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(1000, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn change_above_threshold_is_kept() {
+    let utxos = vec![(outpoint(1), tx_out(5_000, address()))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000))
+      )
+      .dust_change_to_fee(Some(Amount::from_sat(3870)))
+      .build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(1000, recipient()), tx_out(3870, change(1))],
+      })
+    )
+  }
+
   #[test]
   fn exact_transaction_adds_output_to_cover_value() {
     let utxos = vec![
@@ -1524,6 +1978,87 @@ mod tests {
     )
   }
 
+  #[test]
+  fn change_is_split() {
+    let utxos = vec![(outpoint(1), tx_out(5_000, address()))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000))
+      )
+      .change_split(vec![Amount::from_sat(1000), Amount::from_sat(1000)])
+      .build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(1000, recipient()),
+          tx_out(1000, change(1)),
+          tx_out(2839, change(0))
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn change_split_may_not_exceed_change() {
+    let utxos = vec![(outpoint(1), tx_out(5_000, address()))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000))
+      )
+      .change_split(vec![Amount::from_sat(2000), Amount::from_sat(2000)])
+      .build_transaction(),
+      Err(Error::ChangeSplitExceedsChange {
+        requested: Amount::from_sat(4000),
+        available: Amount::from_sat(3839),
+      })
+    )
+  }
+
+  #[test]
+  fn change_split_may_not_be_dust() {
+    let utxos = vec![(outpoint(1), tx_out(5_000, address()))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000))
+      )
+      .change_split(vec![Amount::from_sat(100), Amount::from_sat(1000)])
+      .build_transaction(),
+      Err(Error::Dust {
+        output_value: Amount::from_sat(100),
+        dust_value: Amount::from_sat(294)
+      })
+    )
+  }
+
   #[test]
   fn refuse_to_send_dust() {
     let utxos = vec![(outpoint(1), tx_out(1_000, address()))];
@@ -1882,6 +2417,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .align_outgoing()
+    .unwrap()
     .pad_alignment_output()
     .unwrap();
 
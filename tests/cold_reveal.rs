@@ -0,0 +1,91 @@
+use {
+  super::*,
+  bitcoin::consensus,
+  ord::subcommand::cold_reveal::{key, sign},
+};
+
+#[test]
+fn inscribe_with_cold_reveal_key() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let key = CommandBuilder::new("cold-reveal key key.txt")
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<key::Output>();
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --file foo.txt --cold-reveal-key {} --cold-reveal-file reveal.json",
+    key.public_key
+  ))
+  .temp_dir(tempdir.clone())
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    r".*Commit transaction [[:xdigit:]]{64} was broadcast, sign reveal transaction [[:xdigit:]]{64} in `reveal.json` offline with `ord cold-reveal sign` and broadcast it\n",
+  )
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.mempool().len(), 1);
+  assert_eq!(core.mempool()[0].txid(), output.commit);
+
+  let signed = CommandBuilder::new("cold-reveal sign --key-file key.txt reveal.json")
+    .temp_dir(tempdir)
+    .run_and_deserialize_output::<sign::Output>();
+
+  assert_eq!(signed.reveal, output.reveal);
+
+  let reveal_tx: Transaction =
+    consensus::encode::deserialize(&hex::decode(signed.reveal_tx).unwrap()).unwrap();
+
+  assert_eq!(reveal_tx.input[0].witness.len(), 3);
+
+  core.state().mempool.push(reveal_tx);
+
+  core.mine_blocks(1);
+
+  ord.assert_response(format!("/content/{}", output.inscriptions[0].id), "FOO");
+}
+
+#[test]
+fn cold_reveal_key_requires_cold_reveal_file() {
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --cold-reveal-key 79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+  )
+  .write("foo.txt", "FOO")
+  .expected_exit_code(2)
+  .stderr_regex(".*--cold-reveal-file <COLD_REVEAL_FILE>.*")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn cold_reveal_key_cannot_be_used_with_parent() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (parent, _) = inscribe(&core, &ord);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --file foo.txt --parent {parent} --cold-reveal-key 79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 --cold-reveal-file reveal.json",
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: --cold-reveal-key cannot be used with a parent, reveal satpoints, a separately funded reveal transaction, or when etching a rune\n",
+  )
+  .run_and_extract_stdout();
+}
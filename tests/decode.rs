@@ -137,7 +137,9 @@ fn compact() {
       inscriptions: vec![CompactInscription {
         body: Some("00010203".into()),
         content_encoding: None,
+        content_length: Some(4),
         content_type: Some("text/plain;charset=utf-8".into()),
+        delegate: None,
         duplicate_field: false,
         incomplete_field: false,
         metadata: None,
@@ -150,3 +152,131 @@ fn compact() {
     },
   );
 }
+
+#[test]
+fn from_hex() {
+  pretty_assert_eq!(
+    CommandBuilder::new(format!("decode --hex {}", hex::encode(transaction())))
+      .run_and_deserialize_output::<RawOutput>(),
+    RawOutput {
+      inscriptions: vec![Envelope {
+        payload: Inscription {
+          body: Some(vec![0, 1, 2, 3]),
+          content_type: Some(b"text/plain;charset=utf-8".into()),
+          ..default()
+        },
+        input: 0,
+        offset: 0,
+        pushnum: false,
+        stutter: false,
+      }],
+      runestone: Some(Runestone::default()),
+    },
+  );
+}
+
+#[test]
+fn invalid_hex() {
+  CommandBuilder::new("decode --hex xyz")
+    .expected_exit_code(1)
+    .stderr_regex("error: invalid transaction hex\nbecause: .*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscription_in_second_input() {
+  let mut witness = Witness::new();
+
+  witness.push(
+    script::Builder::new()
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_slice([1])
+      .push_slice(b"text/plain;charset=utf-8")
+      .push_slice([])
+      .push_slice(b"bar")
+      .push_opcode(opcodes::all::OP_ENDIF)
+      .into_script(),
+  );
+  witness.push([]);
+
+  let transaction = Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![
+      TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[[0; 64]]),
+      },
+      TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness,
+      },
+    ],
+    output: Vec::new(),
+  };
+
+  let mut buffer = Vec::new();
+
+  transaction.consensus_encode(&mut buffer).unwrap();
+
+  pretty_assert_eq!(
+    CommandBuilder::new("decode --compact --file transaction.bin")
+      .write("transaction.bin", buffer)
+      .run_and_deserialize_output::<CompactOutput>(),
+    CompactOutput {
+      inscriptions: vec![CompactInscription {
+        body: Some(hex::encode("bar")),
+        content_encoding: None,
+        content_length: Some(3),
+        content_type: Some("text/plain;charset=utf-8".into()),
+        delegate: None,
+        duplicate_field: false,
+        incomplete_field: false,
+        metadata: None,
+        metaprotocol: None,
+        parents: Vec::new(),
+        pointer: None,
+        unrecognized_even_field: false,
+      }],
+      runestone: None,
+    },
+  );
+}
+
+#[test]
+fn no_inscriptions() {
+  let transaction = Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint::null(),
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    }],
+    output: Vec::new(),
+  };
+
+  let mut buffer = Vec::new();
+
+  transaction.consensus_encode(&mut buffer).unwrap();
+
+  pretty_assert_eq!(
+    CommandBuilder::new(format!("decode --hex {}", hex::encode(buffer)))
+      .expected_stderr(format!(
+        "No inscriptions found in 1 inputs of transaction {}\n",
+        transaction.txid()
+      ))
+      .run_and_deserialize_output::<RawOutput>(),
+    RawOutput {
+      inscriptions: Vec::new(),
+      runestone: None,
+    },
+  );
+}
@@ -90,3 +90,75 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::InscriptionId(inscription),
   );
 }
+
+#[test]
+fn unreachable_bitcoin_core_exits_with_rpc_error_code() {
+  CommandBuilder::new(
+    "--regtest --bitcoin-rpc-url 127.0.0.1:1 --bitcoin-rpc-username foo --bitcoin-rpc-password bar index update",
+  )
+  .stderr_regex("error: Failed to connect to Bitcoin Core RPC at `127.0.0.1:1/`\nbecause: .*")
+  .expected_exit_code(3)
+  .run_and_extract_stdout();
+}
+
+#[cfg(unix)]
+#[test]
+fn interrupted_update_resumes_from_last_commit() {
+  use nix::{
+    sys::signal::{self, Signal},
+    unistd::Pid,
+  };
+
+  let core = mockcore::spawn();
+
+  core.mine_blocks(1000);
+
+  core.state().get_block_delay = Duration::from_millis(10);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  let spawn = CommandBuilder::new(format!(
+    "--commit-interval 1 --index {} index update",
+    index_path.display()
+  ))
+  .core(&core)
+  .temp_dir(tempdir.clone())
+  .spawn();
+
+  for attempt in 0.. {
+    if index_path.is_file() {
+      break;
+    }
+
+    if attempt == 100 {
+      panic!("index was not created");
+    }
+
+    thread::sleep(Duration::from_millis(10));
+  }
+
+  signal::kill(Pid::from_raw(spawn.child.id() as i32), Signal::SIGINT).unwrap();
+
+  let output = spawn.child.wait_with_output().unwrap();
+
+  assert_eq!(output.status.code(), Some(130));
+  assert_regex_match!(
+    str::from_utf8(&output.stderr).unwrap(),
+    "error: index update interrupted after committing block \\d+, run again to resume\n",
+  );
+
+  core.state().get_block_delay = Duration::ZERO;
+
+  CommandBuilder::new(format!("--index {} index update", index_path.display()))
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .run_and_extract_stdout();
+
+  CommandBuilder::new(format!("--index {} index info", index_path.display()))
+    .core(&core)
+    .temp_dir(tempdir)
+    .stdout_regex(r#".*"blocks_indexed": 1001,.*"#)
+    .run_and_extract_stdout();
+}
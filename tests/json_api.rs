@@ -87,7 +87,7 @@ fn get_sat_with_inscription_on_common_sat_and_more_inscriptions() {
   let txid = core.mine_blocks(1)[0].txdata[0].txid();
 
   let Batch { reveal, .. } = CommandBuilder::new(format!(
-    "wallet inscribe --satpoint {}:0:1 --fee-rate 1 --file foo.txt",
+    "wallet inscribe --allow-duplicate --satpoint {}:0:1 --fee-rate 1 --file foo.txt",
     txid
   ))
   .write("foo.txt", "FOO")
@@ -155,6 +155,11 @@ fn get_inscription() {
       address: None,
       charms: vec![Charm::Coin, Charm::Uncommon],
       children: Vec::new(),
+      content_hash: Some(
+        "9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3"
+          .parse()
+          .unwrap()
+      ),
       content_length: Some(3),
       content_type: Some("text/plain;charset=utf-8".to_string()),
       effective_content_type: Some("text/plain;charset=utf-8".to_string()),
@@ -5,7 +5,7 @@ use {
   bitcoin::{
     address::{Address, NetworkUnchecked},
     blockdata::constants::COIN_VALUE,
-    Network, OutPoint, Sequence, Txid, Witness,
+    Amount, Network, OutPoint, Sequence, Transaction, Txid, Witness,
   },
   bitcoincore_rpc::bitcoincore_rpc_json::ListDescriptorsResult,
   chrono::{DateTime, Utc},
@@ -13,7 +13,7 @@ use {
   mockcore::TransactionTemplate,
   ord::{
     api, chain::Chain, outgoing::Outgoing, subcommand::runes::RuneInfo, wallet::batch,
-    InscriptionId, RuneEntry,
+    InscriptionId, Manifest, RuneEntry,
   },
   ordinals::{Charm, Edict, Pile, Rarity, Rune, RuneId, Runestone, Sat, SatPoint, SpacedRune},
   pretty_assertions::assert_eq as pretty_assert_eq,
@@ -53,6 +53,7 @@ mod expected;
 mod test_server;
 
 mod balances;
+mod cold_reveal;
 mod decode;
 mod epochs;
 mod find;
@@ -61,12 +62,16 @@ mod info;
 mod json_api;
 mod list;
 mod parse;
+mod predict_id;
+mod recovery_address;
 mod runes;
+mod selftest;
 mod server;
 mod settings;
 mod subsidy;
 mod supply;
 mod traits;
+mod verify;
 mod version;
 mod wallet;
 
@@ -74,10 +79,14 @@ const RUNE: u128 = 99246114928149462;
 
 type Balance = ord::subcommand::wallet::balance::Output;
 type Batch = ord::wallet::batch::Output;
+type BumpFee = ord::subcommand::wallet::bump_fee::Output;
 type Create = ord::subcommand::wallet::create::Output;
 type Inscriptions = Vec<ord::subcommand::wallet::inscriptions::Output>;
+type RecoverCommit = ord::subcommand::wallet::recover_commit::Output;
+type RecoveryKeys = Vec<ord::subcommand::wallet::recovery_keys::Output>;
 type Send = ord::subcommand::wallet::send::Output;
 type Supply = ord::subcommand::supply::Output;
+type TraceFunding = ord::subcommand::wallet::trace_funding::Output;
 
 fn create_wallet(core: &mockcore::Handle, ord: &TestServer) {
   CommandBuilder::new(format!("--chain {} wallet create", core.network()))
@@ -100,7 +109,7 @@ fn inscribe(core: &mockcore::Handle, ord: &TestServer) -> (InscriptionId, Txid)
   core.mine_blocks(1);
 
   let output = CommandBuilder::new(format!(
-    "--chain {} wallet inscribe --fee-rate 1 --file foo.txt",
+    "--chain {} wallet inscribe --allow-rare --allow-duplicate --fee-rate 1 --file foo.txt",
     core.network()
   ))
   .write("foo.txt", "FOO")
@@ -108,13 +117,54 @@ fn inscribe(core: &mockcore::Handle, ord: &TestServer) -> (InscriptionId, Txid)
   .ord(ord)
   .run_and_deserialize_output::<Batch>();
 
-  core.mine_blocks(1);
+  mine_commit_and_reveal(core, &output);
 
   assert_eq!(output.inscriptions.len(), 1);
 
   (output.inscriptions[0].id, output.reveal)
 }
 
+#[track_caller]
+fn mine_commit_and_reveal(core: &mockcore::Handle, output: &Batch) -> Transaction {
+  let block = core.mine_blocks(1).remove(0);
+
+  let txids = block
+    .txdata
+    .iter()
+    .map(Transaction::txid)
+    .collect::<Vec<Txid>>();
+
+  assert!(
+    txids.contains(&output.commit),
+    "commit transaction {} was not mined",
+    output.commit
+  );
+
+  assert!(
+    txids.contains(&output.reveal),
+    "reveal transaction {} was not mined",
+    output.reveal
+  );
+
+  let reveal = core.tx_by_id(output.reveal);
+
+  assert!(
+    reveal
+      .input
+      .iter()
+      .any(|input| input.previous_output.txid == output.commit),
+    "reveal transaction {} does not spend commit transaction {}",
+    output.reveal,
+    output.commit
+  );
+
+  for inscription in &output.inscriptions {
+    assert_eq!(inscription.location.outpoint.txid, output.reveal);
+  }
+
+  reveal
+}
+
 fn drain(core: &mockcore::Handle, ord: &TestServer) {
   let balance = CommandBuilder::new("--regtest --index-runes wallet balance")
     .core(core)
@@ -176,11 +226,12 @@ fn etch(core: &mockcore::Handle, ord: &TestServer, rune: Rune) -> Etched {
 fn batch(core: &mockcore::Handle, ord: &TestServer, batchfile: batch::File) -> Etched {
   core.mine_blocks(1);
 
-  let mut builder =
-    CommandBuilder::new("--regtest --index-runes wallet batch --fee-rate 0 --batch batch.yaml")
-      .write("batch.yaml", serde_yaml::to_string(&batchfile).unwrap())
-      .core(core)
-      .ord(ord);
+  let mut builder = CommandBuilder::new(
+    "--regtest --index-runes wallet batch --allow-duplicate --fee-rate 0 --batch batch.yaml",
+  )
+  .write("batch.yaml", serde_yaml::to_string(&batchfile).unwrap())
+  .core(core)
+  .ord(ord);
 
   for inscription in &batchfile.inscriptions {
     builder = builder.write(&inscription.file, "inscription");
@@ -0,0 +1,76 @@
+use {
+  super::*,
+  bitcoin::{
+    absolute::LockTime, consensus::Encodable, opcodes, script, ScriptBuf, Sequence, Transaction,
+    TxIn, Witness,
+  },
+  ord::subcommand::predict_id::Output,
+};
+
+fn reveal(witness: Witness) -> Transaction {
+  Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint::null(),
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness,
+    }],
+    output: Vec::new(),
+  }
+}
+
+fn encode(transaction: &Transaction) -> String {
+  let mut buffer = Vec::new();
+
+  transaction.consensus_encode(&mut buffer).unwrap();
+
+  hex::encode(buffer)
+}
+
+#[test]
+fn predict_id() {
+  let script = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord")
+    .push_slice([1])
+    .push_slice(b"text/plain;charset=utf-8")
+    .push_slice([])
+    .push_slice(b"foo")
+    .push_opcode(opcodes::all::OP_ENDIF)
+    .into_script();
+
+  let mut witness = Witness::new();
+
+  witness.push(script);
+  witness.push([]);
+
+  let reveal = reveal(witness);
+
+  assert_eq!(
+    CommandBuilder::new(format!("predict-id {}", encode(&reveal)))
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      reveal: reveal.txid(),
+      inscriptions: vec![InscriptionId {
+        txid: reveal.txid(),
+        index: 0,
+      }],
+    },
+  );
+}
+
+#[test]
+fn predict_id_fails_without_inscriptions() {
+  let reveal = reveal(Witness::new());
+
+  CommandBuilder::new(format!("predict-id {}", encode(&reveal)))
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: reveal transaction {} contains no inscriptions\n",
+      reveal.txid()
+    ))
+    .run_and_extract_stdout();
+}
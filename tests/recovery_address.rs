@@ -0,0 +1,77 @@
+use {super::*, ord::subcommand::recovery_address::Output};
+
+#[test]
+fn recovery_address_matches_commit_address() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let inscribe = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --compress --recovery-key-file key.txt",
+  )
+  .temp_dir(tempdir.clone())
+  .write("foo.txt", "FOO".repeat(100))
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(r#"\{"code":"plaintext-recovery-key",.*\}\n"#)
+  .run_and_deserialize_output::<Batch>();
+
+  let output = CommandBuilder::new(format!(
+    "recovery-address --recovery-key-file key.txt --file foo.txt --compress --internal-key {}",
+    inscribe.commit_internal_key
+  ))
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+
+  let output = CommandBuilder::new(format!(
+    "recovery-address --file foo.txt --compress --internal-key {}",
+    inscribe.commit_internal_key
+  ))
+  .stdin(fs::read(tempdir.path().join("key.txt")).unwrap())
+  .temp_dir(tempdir)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+}
+
+#[test]
+fn recovery_address_fails_for_different_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let inscribe =
+    CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt --recovery-key-file key.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .stderr_regex(r#"\{"code":"plaintext-recovery-key",.*\}\n"#)
+      .run_and_deserialize_output::<Batch>();
+
+  CommandBuilder::new(format!(
+    "recovery-address --recovery-key-file key.txt --file bar.txt --internal-key {}",
+    inscribe.commit_internal_key
+  ))
+  .temp_dir(tempdir)
+  .write("bar.txt", "BAR")
+  .expected_exit_code(1)
+  .stderr_regex(
+    "error: recovery key controls .* but inscription file and internal key derive commit address .*\n",
+  )
+  .run_and_extract_stdout();
+}
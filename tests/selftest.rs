@@ -0,0 +1,9 @@
+use {super::*, ord::subcommand::selftest::taproot::Output};
+
+#[test]
+fn taproot() {
+  let output = CommandBuilder::new("selftest taproot").run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.vectors.len(), 2);
+  assert!(output.vectors.iter().all(|vector| vector.passed));
+}
@@ -287,7 +287,7 @@ fn recursive_inscription_endpoint() {
 
   core.mine_blocks(1);
 
-  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+  let output = CommandBuilder::new("wallet inscribe --allow-rare --fee-rate 1 --file foo.txt")
     .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
@@ -16,6 +16,7 @@ fn default() {
   "config_dir": null,
   "cookie_file": ".*\.cookie",
   "data_dir": ".*",
+  "fee_rate": null,
   "first_inscription_height": 767430,
   "height_limit": null,
   "hidden": \[\],
@@ -27,6 +28,7 @@ fn default() {
   "index_transactions": false,
   "integration_test": false,
   "no_index_inscriptions": false,
+  "quiet": false,
   "server_password": null,
   "server_url": null,
   "server_username": null
@@ -0,0 +1,109 @@
+use {super::*, ord::subcommand::verify::Output};
+
+#[test]
+fn verify_matching_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify {inscription} foo.txt"))
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      verified: true,
+      content_type: Some("text/plain;charset=utf-8".into()),
+      expected_content_type: Some("text/plain;charset=utf-8".into()),
+      content_length: Some(3),
+      expected_content_length: Some(3),
+      length_difference: None,
+    }
+  );
+}
+
+#[test]
+fn verify_mismatched_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify {inscription} foo.html"))
+      .write("foo.html", "FOOBAR")
+      .core(&core)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      verified: false,
+      content_type: Some("text/plain;charset=utf-8".into()),
+      expected_content_type: Some("text/html;charset=utf-8".into()),
+      content_length: Some(3),
+      expected_content_length: Some(6),
+      length_difference: Some(3),
+    }
+  );
+}
+
+#[test]
+fn verify_compressed_inscription_compares_decoded_content() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("wallet inscribe --allow-rare --compress --fee-rate 1 --file foo.txt")
+      .write("foo.txt", "FOO".repeat(100))
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  mine_commit_and_reveal(&core, &output);
+
+  let inscription = output.inscriptions[0].id;
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify {inscription} foo.txt"))
+      .write("foo.txt", "FOO".repeat(100))
+      .core(&core)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      verified: true,
+      content_type: Some("text/plain;charset=utf-8".into()),
+      expected_content_type: Some("text/plain;charset=utf-8".into()),
+      content_length: Some(300),
+      expected_content_length: Some(300),
+      length_difference: None,
+    }
+  );
+}
+
+#[test]
+fn verify_unknown_inscription() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new(
+    "verify 0000000000000000000000000000000000000000000000000000000000000000i0 foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .expected_stderr(
+    "error: inscription 0000000000000000000000000000000000000000000000000000000000000000i0 not found\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
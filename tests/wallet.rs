@@ -3,16 +3,23 @@ use super::*;
 mod authentication;
 mod balance;
 mod batch_command;
+mod broadcast_bundle;
+mod bump_fee;
 mod cardinals;
 mod create;
 mod dump;
+mod export;
 mod inscribe;
 mod inscriptions;
 mod mint;
 mod outputs;
 mod receive;
+mod recover_commit;
+mod recovery_keys;
 mod restore;
+mod resume_reveal;
 mod sats;
 mod selection;
 mod send;
+mod trace_funding;
 mod transactions;
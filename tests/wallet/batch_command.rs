@@ -72,6 +72,38 @@ fn batch_inscribe_can_create_one_inscription() {
   );
 }
 
+#[test]
+fn batch_inscribe_with_content_type() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "{}")
+    .write(
+      "batch.yaml",
+      "mode: shared-output\ninscriptions:\n- file: inscription.txt\n  content_type: application/json",
+    )
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let request = ord.request(format!("/content/{}", output.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+  assert_eq!(
+    request.headers().get("content-type").unwrap(),
+    "application/json"
+  );
+  assert_eq!(request.text().unwrap(), "{}");
+}
+
 #[test]
 fn batch_inscribe_with_multiple_inscriptions() {
   let core = mockcore::spawn();
@@ -262,6 +294,43 @@ fn batch_in_same_output_but_different_satpoints() {
   );
 }
 
+#[test]
+fn batch_with_minify_prints_one_inscription_per_line() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("--minify wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("meow.wav", [0; 2048])
+    .write(
+      "batch.yaml",
+      "mode: separate-outputs\ninscriptions:\n- file: inscription.txt\n- file: meow.wav\n",
+    )
+    .core(&core)
+    .ord(&ord)
+    .stdout_regex(".*")
+    .run_and_extract_stdout();
+
+  let outputs = stdout
+    .lines()
+    .map(|line| serde_json::from_str::<Batch>(line).unwrap())
+    .collect::<Vec<Batch>>();
+
+  assert_eq!(outputs.len(), 2);
+
+  for output in &outputs {
+    assert_eq!(output.inscriptions.len(), 1);
+    assert_eq!(output.reveal, outputs[0].reveal);
+  }
+
+  assert_ne!(outputs[0].inscriptions[0].id, outputs[1].inscriptions[0].id);
+}
+
 #[test]
 fn batch_in_same_output_with_non_default_postage() {
   let core = mockcore::spawn();
@@ -282,6 +351,9 @@ fn batch_in_same_output_with_non_default_postage() {
     )
     .core(&core)
     .ord(&ord)
+    .stderr_regex(
+      r#"\{"code":"low-postage","message":".*"\}\n\{"code":"large-funding-utxo","message":".*"\}\n"#,
+    )
     .run_and_deserialize_output::<Batch>();
 
   let outpoint = output.inscriptions[0].location.outpoint;
@@ -407,6 +479,102 @@ fn batch_in_separate_outputs_with_parent() {
   );
 }
 
+#[test]
+fn batch_bundles_envelopes_into_one_reveal_with_output_per_inscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("a.txt", "A")
+    .write("b.txt", "B")
+    .write("c.txt", "C")
+    .write(
+      "batch.yaml",
+      "mode: separate-outputs\ninscriptions:\n- file: a.txt\n- file: b.txt\n- file: c.txt\n",
+    )
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let reveal = mine_commit_and_reveal(&core, &output);
+
+  assert_eq!(reveal.input.len(), 1);
+  assert_eq!(reveal.output.len(), 3);
+
+  for output in &reveal.output {
+    assert_eq!(output.value, 10_000);
+  }
+
+  pretty_assert_eq!(
+    output.inscriptions_by_output,
+    (0..3)
+      .map(|index| {
+        (
+          index,
+          vec![InscriptionId {
+            txid: output.reveal,
+            index,
+          }],
+        )
+      })
+      .collect::<BTreeMap<u32, Vec<InscriptionId>>>(),
+  );
+}
+
+#[test]
+fn batch_reveal_spends_commit_and_parent_once_mined() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let parent_output = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
+    .write("parent.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  mine_commit_and_reveal(&core, &parent_output);
+
+  let parent_id = parent_output.inscriptions[0].id;
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("tulip.png", [0; 555])
+    .write(
+      "batch.yaml",
+      format!("parent: {parent_id}\nmode: separate-outputs\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n"),
+    )
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let reveal = mine_commit_and_reveal(&core, &output);
+
+  assert!(reveal
+    .input
+    .iter()
+    .any(|input| input.previous_output == parent_output.inscriptions[0].location.outpoint));
+
+  for inscription in &output.inscriptions {
+    ord.assert_response_regex(
+      format!("/inscription/{}", inscription.id),
+      format!(
+        r".*<dt>parents</dt>\s*<dd>.*{parent_id}.*</dd>.*<dt>location</dt>.*<dd class=monospace>{}</dd>.*",
+        inscription.location
+      ),
+    );
+  }
+}
+
 #[test]
 fn batch_in_separate_outputs_with_parent_and_non_default_postage() {
   let core = mockcore::spawn();
@@ -439,6 +607,9 @@ fn batch_in_separate_outputs_with_parent_and_non_default_postage() {
     )
     .core(&core)
     .ord(&ord)
+    .stderr_regex(
+      r#"\{"code":"low-postage","message":".*"\}\n\{"code":"large-funding-utxo","message":".*"\}\n"#,
+    )
     .run_and_deserialize_output::<Batch>();
 
   for inscription in &output.inscriptions {
@@ -851,12 +1022,13 @@ fn batch_inscribe_with_sat_argument_with_parent() {
 
   core.mine_blocks(1);
 
-  let parent_output =
-    CommandBuilder::new("--index-sats wallet inscribe --fee-rate 5.0 --file parent.png")
-      .write("parent.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>();
+  let parent_output = CommandBuilder::new(
+    "--index-sats wallet inscribe --allow-rare --fee-rate 5.0 --file parent.png",
+  )
+  .write("parent.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
 
   core.mine_blocks(1);
 
@@ -1082,12 +1254,13 @@ fn batch_inscribe_with_satpoints_with_parent() {
 
   core.mine_blocks(1);
 
-  let parent_output =
-    CommandBuilder::new("--index-sats wallet inscribe --fee-rate 5.0 --file parent.png")
-      .write("parent.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>();
+  let parent_output = CommandBuilder::new(
+    "--index-sats wallet inscribe --allow-rare --fee-rate 5.0 --file parent.png",
+  )
+  .write("parent.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
 
   core.mine_blocks(1);
 
@@ -2374,7 +2547,7 @@ fn oversize_runestones_are_allowed_with_no_limit() {
   core.mine_blocks(1);
 
   CommandBuilder::new(
-    "--regtest --index-runes wallet batch --fee-rate 0 --dry-run --no-limit --batch batch.yaml",
+    "--regtest --index-runes wallet batch --fee-rate 0 --dry-run --no-limit --i-know-what-im-doing --batch batch.yaml",
   )
   .write("inscription.txt", "foo")
   .write(
@@ -2412,5 +2585,65 @@ fn oversize_runestones_are_allowed_with_no_limit() {
   )
   .core(&core)
   .ord(&ord)
+  .expected_stderr(
+    "{\"code\":\"no-limit-check\",\"message\":\"transaction weight limit check disabled, transactions may not be relayed\"}\n",
+  )
   .run_and_deserialize_output::<Batch>();
 }
+
+#[test]
+fn batch_aborts_reveal_if_commit_is_replaced() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mut spawn =
+    CommandBuilder::new("--regtest --index-runes wallet batch --fee-rate 0 --batch batch.yaml")
+      .write(
+        "batch.yaml",
+        serde_yaml::to_string(&batch::File {
+          etching: Some(batch::Etching {
+            divisibility: 0,
+            rune: SpacedRune {
+              rune: Rune(RUNE),
+              spacers: 0,
+            },
+            supply: "1000".parse().unwrap(),
+            premine: "1000".parse().unwrap(),
+            symbol: '¢',
+            terms: None,
+          }),
+          inscriptions: vec![batch::Entry {
+            file: "inscription.jpeg".into(),
+            ..default()
+          }],
+          ..default()
+        })
+        .unwrap(),
+      )
+      .write("inscription.jpeg", "inscription")
+      .core(&core)
+      .ord(&ord)
+      .stderr_regex(
+        "error: commit output [[:xdigit:]]{64}:0 no longer exists, commit transaction was likely replaced: \
+        rebuild the reveal transaction against the replacement commit transaction\n",
+      )
+      .expected_exit_code(1)
+      .spawn();
+
+  let mut buffer = String::new();
+
+  BufReader::new(spawn.child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
+    .unwrap();
+
+  assert_eq!(buffer, "Waiting for rune commitment to mature…\n");
+
+  core.state().mempool.clear();
+
+  spawn.run_and_extract_stdout();
+}
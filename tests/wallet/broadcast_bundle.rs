@@ -0,0 +1,91 @@
+use {super::*, ord::subcommand::wallet::broadcast_bundle::Output};
+
+#[test]
+fn prepared_bundle_can_be_broadcast_later() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let prepared =
+    CommandBuilder::new("wallet inscribe --prepare-only bundle.json --fee-rate 1 --file foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .stderr_regex(r#"\{"code":"plaintext-recovery-key",.*\}\n"#)
+      .run_and_deserialize_output::<Batch>();
+
+  assert!(core.mempool().is_empty());
+
+  let bundle = serde_json::from_str::<batch::Bundle>(
+    &fs::read_to_string(tempdir.path().join("bundle.json")).unwrap(),
+  )
+  .unwrap();
+
+  assert_eq!(bundle.commit, prepared.commit);
+  assert_eq!(bundle.reveal, prepared.reveal);
+
+  let output = CommandBuilder::new("wallet broadcast-bundle bundle.json")
+    .temp_dir(tempdir)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.commit, prepared.commit);
+  assert_eq!(output.reveal, prepared.reveal);
+
+  mine_commit_and_reveal(&core, &prepared);
+
+  ord.assert_response(format!("/content/{}", prepared.inscriptions[0].id), "FOO");
+}
+
+#[test]
+fn broadcast_bundle_succeeds_if_already_broadcast() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let prepared =
+    CommandBuilder::new("wallet inscribe --prepare-only bundle.json --fee-rate 1 --file foo.txt")
+      .temp_dir(tempdir.clone())
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .stderr_regex(r#"\{"code":"plaintext-recovery-key",.*\}\n"#)
+      .run_and_deserialize_output::<Batch>();
+
+  for _ in 0..2 {
+    let output = CommandBuilder::new("wallet broadcast-bundle bundle.json")
+      .temp_dir(tempdir.clone())
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Output>();
+
+    assert_eq!(output.commit, prepared.commit);
+    assert_eq!(output.reveal, prepared.reveal);
+  }
+
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn prepare_only_conflicts_with_dry_run() {
+  CommandBuilder::new(
+    "wallet inscribe --prepare-only bundle.json --dry-run --fee-rate 1 --file foo.txt",
+  )
+  .expected_exit_code(2)
+  .stderr_regex(".*--prepare-only <BUNDLE>.*cannot be used with.*--dry-run.*")
+  .run_and_extract_stdout();
+}
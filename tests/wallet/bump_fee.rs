@@ -0,0 +1,167 @@
+use super::*;
+
+#[test]
+fn bump_fee_replaces_commit_transaction() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let original = core
+    .mempool()
+    .into_iter()
+    .find(|tx| tx.txid() == inscribe.commit)
+    .unwrap();
+
+  let output = CommandBuilder::new(format!("wallet bump-fee --abandon-reveal --fee-rate 2 {}", inscribe.commit))
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(format!(
+      "Reveal transaction spending {} is invalid, recover commit output of [[:xdigit:]]{{64}} with `ord wallet recover-commit`\n",
+      inscribe.commit
+    ))
+    .run_and_deserialize_output::<BumpFee>();
+
+  assert_eq!(output.original, inscribe.commit);
+  assert!(output.fee > output.original_fee);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+
+  assert_eq!(replacement.txid(), output.replacement);
+  assert_eq!(replacement.input.len(), original.input.len());
+  assert_eq!(replacement.output[0], original.output[0]);
+  assert_eq!(
+    replacement.output[1].value,
+    original.output[1].value - (output.fee - output.original_fee)
+  );
+}
+
+#[test]
+fn bump_fee_requires_unconfirmed_transaction() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (_, reveal) = inscribe(&core, &ord);
+
+  CommandBuilder::new(format!(
+    "wallet bump-fee --abandon-reveal --fee-rate 2 {reveal}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!(
+    "error: transaction {reveal} is already confirmed\n"
+  ))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_fee_requires_higher_fee() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 2 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  CommandBuilder::new(format!("wallet bump-fee --abandon-reveal --fee-rate 1 {}", inscribe.commit))
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(
+      "error: replacement fee of [0-9]+ sat must exceed original fee of [0-9]+ sat by at least the incremental relay fee of 0 sat\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_fee_requires_abandon_reveal() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  CommandBuilder::new(format!("wallet bump-fee --fee-rate 2 {}", inscribe.commit))
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(format!(
+      "error: replacing commit transaction {} invalidates its reveal transaction, .* use --abandon-reveal .*\n",
+      inscribe.commit
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn bump_fee_pays_incremental_relay_fee() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.state().incremental_relay_fee = Amount::from_sat(2000);
+
+  CommandBuilder::new(format!(
+    "wallet bump-fee --abandon-reveal --fee-rate 2 {}",
+    inscribe.commit
+  ))
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    "error: replacement fee of [0-9]+ sat must exceed original fee of [0-9]+ sat by at least the incremental relay fee of [0-9]+ sat\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "wallet bump-fee --abandon-reveal --fee-rate 4 {}",
+    inscribe.commit
+  ))
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(".*")
+  .run_and_deserialize_output::<BumpFee>();
+}
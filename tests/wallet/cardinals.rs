@@ -1,6 +1,9 @@
 use {
   super::*,
-  ord::subcommand::wallet::{cardinals::CardinalUtxo, outputs::Output},
+  ord::subcommand::wallet::{
+    cardinals::{CardinalUtxo, Utxo},
+    outputs::Output,
+  },
 };
 
 #[test]
@@ -25,3 +28,34 @@ fn cardinals() {
 
   assert_eq!(all_outputs.len() - cardinal_outputs.len(), 1);
 }
+
+#[test]
+fn cardinals_all() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _reveal) = inscribe(&core, &ord);
+
+  let all_outputs = CommandBuilder::new("wallet outputs")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  let utxos = CommandBuilder::new("wallet cardinals --all")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Utxo>>();
+
+  assert_eq!(utxos.len(), all_outputs.len());
+
+  let inscribed = utxos
+    .iter()
+    .filter(|utxo| !utxo.cardinal)
+    .collect::<Vec<&Utxo>>();
+
+  assert_eq!(inscribed.len(), 1);
+  assert_eq!(inscribed[0].output.txid, inscription.txid);
+}
@@ -0,0 +1,66 @@
+use {super::*, ord::subcommand::wallet::export::Output};
+
+#[test]
+fn export_writes_inscription_content_to_directory() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _reveal) = inscribe(&core, &ord);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let output = CommandBuilder::new("wallet export inscriptions")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<BTreeMap<InscriptionId, Output>>();
+
+  assert_eq!(
+    output,
+    [(
+      inscription,
+      Output {
+        filename: format!("{inscription}.txt"),
+        content_type: Some("text/plain;charset=utf-8".into()),
+        content_length: 3,
+      }
+    )]
+    .into_iter()
+    .collect(),
+  );
+
+  assert_eq!(
+    fs::read_to_string(
+      tempdir
+        .path()
+        .join("inscriptions")
+        .join(format!("{inscription}.txt"))
+    )
+    .unwrap(),
+    "FOO",
+  );
+}
+
+#[test]
+fn export_with_no_inscriptions_creates_empty_directory() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let output = CommandBuilder::new("wallet export inscriptions")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<BTreeMap<InscriptionId, Output>>();
+
+  assert!(output.is_empty());
+
+  assert!(tempdir.path().join("inscriptions").is_dir());
+}
@@ -1,6 +1,11 @@
 use {
   super::*,
-  ord::subcommand::wallet::{create, inscriptions, receive},
+  base64::Engine,
+  bitcoin::psbt::Psbt,
+  ord::subcommand::{
+    decode::{CompactOutput, RawOutput},
+    wallet::{create, inscriptions, receive},
+  },
   std::ops::Deref,
 };
 
@@ -29,6 +34,48 @@ fn inscribe_creates_inscriptions() {
   assert_eq!(request.text().unwrap(), "FOO");
 }
 
+#[test]
+fn inscribe_labels_recovery_key_with_inscription_id() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(
+    core
+      .state()
+      .descriptor_labels
+      .values()
+      .collect::<Vec<&String>>(),
+    [&format!(
+      "commit tx recovery key for {}",
+      output.inscriptions[0].id
+    )],
+  );
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt --label pepe")
+    .write("foo.txt", "BAR")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert!(core.state().descriptor_labels.values().any(|label| *label
+    == format!(
+      "commit tx recovery key pepe for {}",
+      output.inscriptions[0].id
+    )));
+}
+
 #[test]
 fn inscribe_works_with_huge_expensive_inscriptions() {
   let core = mockcore::spawn();
@@ -44,7 +91,56 @@ fn inscribe_works_with_huge_expensive_inscriptions() {
   .write("foo.txt", [0; 350_000])
   .core(&core)
   .ord(&ord)
+  .stderr_regex(
+    r#"\{"code":"large-content","message":"inscription content of 350000 bytes .*"\}\n"#,
+  )
+  .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_at_nonzero_satpoint_offset() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let txid = core.mine_blocks(1)[0].txdata[0].txid();
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --file foo.txt --satpoint {txid}:0:5000 --fee-rate 1"
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
   .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let commit = core.tx_by_id(output.commit);
+
+  assert_eq!(commit.input[0].previous_output, OutPoint { txid, vout: 0 });
+  assert_eq!(commit.output[0].value, 5000);
+
+  let reveal = core.tx_by_id(output.reveal);
+
+  assert_eq!(
+    reveal.input[0].previous_output,
+    OutPoint {
+      txid: output.commit,
+      vout: 1,
+    }
+  );
+
+  assert_eq!(
+    output.inscriptions[0].location,
+    SatPoint {
+      outpoint: OutPoint {
+        txid: output.reveal,
+        vout: 0,
+      },
+      offset: 0,
+    }
+  );
 }
 
 #[test]
@@ -62,6 +158,9 @@ fn metaprotocol_appears_on_inscription_page() {
   .write("foo.txt", [0; 350_000])
   .core(&core)
   .ord(&ord)
+  .stderr_regex(
+    r#"\{"code":"large-content","message":"inscription content of 350000 bytes .*"\}\n"#,
+  )
   .run_and_deserialize_output::<Batch>();
 
   core.mine_blocks(1);
@@ -106,6 +205,33 @@ fn inscribe_no_backup() {
   assert_eq!(core.descriptors().len(), 2);
 }
 
+#[test]
+fn inscribe_file_without_extension_sniffs_content_type() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --file download --fee-rate 1")
+    .write("download", "hello")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let request = ord.request(format!("/content/{}", output.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+  assert_eq!(
+    request.headers().get("content-type").unwrap(),
+    "text/plain;charset=utf-8"
+  );
+  assert_eq!(request.text().unwrap(), "hello");
+}
+
 #[test]
 fn inscribe_unknown_file_extension() {
   let core = mockcore::spawn();
@@ -124,6 +250,31 @@ fn inscribe_unknown_file_extension() {
     .run_and_extract_stdout();
 }
 
+#[test]
+fn inscribe_with_content_type() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("wallet inscribe --file pepe.xyz --fee-rate 1 --content-type text/html")
+      .write("pepe.xyz", "<h1>pepe</h1>")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let request = ord.request(format!("/content/{}", output.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+  assert_eq!(request.headers().get("content-type").unwrap(), "text/html");
+  assert_eq!(request.text().unwrap(), "<h1>pepe</h1>");
+}
+
 #[test]
 fn inscribe_exceeds_chain_limit() {
   let core = mockcore::builder().network(Network::Signet).build();
@@ -194,7 +345,7 @@ fn inscribe_does_not_use_inscribed_sats_as_cardinal_utxos() {
   .core(&core)
   .ord(&ord)
   .write("degenerate.png", [1; 100])
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.\n")
   .run_and_extract_stdout();
 }
@@ -238,7 +389,7 @@ fn refuse_to_inscribe_already_inscribed_utxo() {
   };
 
   CommandBuilder::new(format!(
-    "wallet inscribe --satpoint {output}:55555 --file hello.txt --fee-rate 1"
+    "wallet inscribe --satpoint {output}:5555 --file hello.txt --fee-rate 1"
   ))
   .write("hello.txt", "HELLOWORLD")
   .core(&core)
@@ -250,6 +401,28 @@ fn refuse_to_inscribe_already_inscribed_utxo() {
   .run_and_extract_stdout();
 }
 
+#[test]
+fn inscribe_with_satpoint_offset_beyond_output_value_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let txid = core.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file foo.txt --satpoint {txid}:0:5000000000 --fee-rate 1"
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: satpoint {txid}:0:5000000000 offset 5000000000 is not less than output value of 5000000000 sat\n"
+  ))
+  .run_and_extract_stdout();
+}
+
 #[test]
 fn inscribe_with_optional_satpoint_arg() {
   let core = mockcore::spawn();
@@ -293,12 +466,13 @@ fn inscribe_with_fee_rate() {
 
   core.mine_blocks(1);
 
-  let output =
-    CommandBuilder::new("--index-sats wallet inscribe --file degenerate.png --fee-rate 2.0")
-      .write("degenerate.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>();
+  let output = CommandBuilder::new(
+    "--index-sats wallet inscribe --allow-rare --file degenerate.png --fee-rate 2.0",
+  )
+  .write("degenerate.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
 
   let tx1 = &core.mempool()[0];
   let mut fee = 0;
@@ -337,901 +511,3827 @@ fn inscribe_with_fee_rate() {
   );
 }
 
-#[test]
-fn inscribe_with_commit_fee_rate() {
-  let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
-
-  create_wallet(&core, &ord);
-
-  core.mine_blocks(1);
-
-  CommandBuilder::new(
-    "--index-sats wallet inscribe --file degenerate.png --commit-fee-rate 2.0 --fee-rate 1",
-  )
-  .write("degenerate.png", [1; 520])
-  .core(&core)
-  .ord(&ord)
-  .run_and_deserialize_output::<Batch>();
-
-  let tx1 = &core.mempool()[0];
-  let mut fee = 0;
-  for input in &tx1.input {
-    fee += core
-      .get_utxo_amount(&input.previous_output)
-      .unwrap()
-      .to_sat();
-  }
-  for output in &tx1.output {
-    fee -= output.value;
-  }
-
-  let fee_rate = fee as f64 / tx1.vsize() as f64;
-
-  pretty_assert_eq!(fee_rate, 2.0);
-
-  let tx2 = &core.mempool()[1];
+fn reveal_fee_rate(core: &mockcore::Handle) -> f64 {
+  let mempool = core.mempool();
+  let (commit, reveal) = (&mempool[0], &mempool[1]);
   let mut fee = 0;
-  for input in &tx2.input {
-    fee += &tx1.output[input.previous_output.vout as usize].value;
+  for input in &reveal.input {
+    fee += commit.output[input.previous_output.vout as usize].value;
   }
-  for output in &tx2.output {
+  for output in &reveal.output {
     fee -= output.value;
   }
-
-  let fee_rate = fee as f64 / tx2.vsize() as f64;
-
-  pretty_assert_eq!(fee_rate, 1.0);
+  fee as f64 / reveal.vsize() as f64
 }
 
 #[test]
-fn inscribe_with_wallet_named_foo() {
+fn inscribe_with_fee_rate_from_environment() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
 
-  CommandBuilder::new("wallet --name foo create")
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt")
+    .write("foo.txt", "FOO")
+    .env("ORD_FEE_RATE", "2.0")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<create::Output>();
+    .run_and_deserialize_output::<Batch>();
+
+  pretty_assert_eq!(reveal_fee_rate(&core), 2.0);
 
   core.mine_blocks(1);
 
-  CommandBuilder::new("wallet --name foo inscribe --file degenerate.png --fee-rate 1")
-    .write("degenerate.png", [1; 520])
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 3.0")
+    .write("foo.txt", "BAR")
+    .env("ORD_FEE_RATE", "2.0")
     .core(&core)
     .ord(&ord)
     .run_and_deserialize_output::<Batch>();
+
+  pretty_assert_eq!(reveal_fee_rate(&core), 3.0);
 }
 
 #[test]
-fn inscribe_with_dry_run_flag() {
+fn inscribe_with_invalid_fee_rate_in_environment_fails() {
+  CommandBuilder::new("wallet inscribe --file foo.txt")
+    .write("foo.txt", "FOO")
+    .env("ORD_FEE_RATE", "fast")
+    .stderr_regex(
+      "error: failed to parse environment variable ORD_FEE_RATE as fee rate\nbecause:.*",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_without_fee_rate_uses_chain_default() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let inscribe =
-    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
-      .write("degenerate.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>();
-
-  assert!(inscribe.commit_psbt.is_some());
-  assert!(inscribe.reveal_psbt.is_some());
-
-  assert!(core.mempool().is_empty());
-
-  let inscribe = CommandBuilder::new("wallet inscribe --file degenerate.png --fee-rate 1")
-    .write("degenerate.png", [1; 520])
+  CommandBuilder::new("wallet inscribe --file foo.txt")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
     .run_and_deserialize_output::<Batch>();
 
-  assert!(inscribe.commit_psbt.is_none());
-  assert!(inscribe.reveal_psbt.is_none());
+  pretty_assert_eq!(reveal_fee_rate(&core), 10.0);
 
-  assert_eq!(core.mempool().len(), 2);
-}
+  let core = mockcore::builder().network(Network::Regtest).build();
 
-#[test]
-fn inscribe_with_dry_run_flag_fees_increase() {
-  let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let total_fee_dry_run =
-    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
-      .write("degenerate.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>()
-      .total_fees;
-
-  let total_fee_normal =
-    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1.1")
-      .write("degenerate.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output::<Batch>()
-      .total_fees;
+  CommandBuilder::new("--regtest wallet inscribe --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
 
-  assert!(total_fee_dry_run < total_fee_normal);
+  pretty_assert_eq!(reveal_fee_rate(&core), 1.0);
 }
 
 #[test]
-fn inscribe_to_specific_destination() {
+fn inscribe_warns_about_large_funding_utxo() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let addresses = CommandBuilder::new("wallet receive")
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --warn-funding-utxo-ratio 1000")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<receive::Output>()
-    .addresses;
+    .stderr_regex(
+      r#"\{"code":"large-funding-utxo","message":"commit transaction spends output [[:xdigit:]]{64}:0 of 5000000000 sat, more than 1000 times the postage and fees of \d+ sat, .*"\}\n"#,
+    )
+    .run_and_deserialize_output::<Batch>();
+}
 
-  let destination = addresses.first().unwrap();
+#[test]
+fn inscribe_with_change_addresses_file() {
+  let core = mockcore::builder().network(Network::Regtest).build();
 
-  let txid = CommandBuilder::new(format!(
-    "wallet inscribe --destination {} --file degenerate.png --fee-rate 1",
-    destination.clone().assume_checked()
-  ))
-  .write("degenerate.png", [1; 520])
-  .core(&core)
-  .ord(&ord)
-  .run_and_deserialize_output::<Batch>()
-  .reveal;
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
 
-  let reveal_tx = &core.mempool()[1]; // item 0 is the commit, item 1 is the reveal.
-  assert_eq!(reveal_tx.txid(), txid);
-  assert_eq!(
-    reveal_tx.output.first().unwrap().script_pubkey,
-    destination.payload.script_pubkey()
-  );
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let change = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw"
+    .parse::<Address<NetworkUnchecked>>()
+    .unwrap()
+    .assume_checked();
+
+  let output = CommandBuilder::new(
+    "--regtest wallet inscribe --file foo.txt --fee-rate 1 --change-addresses change.txt",
+  )
+  .write("foo.txt", "FOO")
+  .write(
+    "change.txt",
+    format!("{change}\n\nbcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k\n"),
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let commit = core.tx_by_id(output.commit);
+
+  assert_eq!(commit.output.len(), 2);
+  assert!(commit
+    .output
+    .iter()
+    .any(|tx_out| tx_out.script_pubkey == change.script_pubkey()));
 }
 
 #[test]
-fn inscribe_to_address_on_different_network() {
-  let core = mockcore::spawn();
-  let ord = TestServer::spawn(&core);
+fn inscribe_with_change_addresses_file_checks_addresses() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
 
   create_wallet(&core, &ord);
 
-  core.mine_blocks(1);
+  let (_inscription, reveal) = inscribe(&core, &ord);
 
   CommandBuilder::new(
-    "wallet inscribe --destination tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm --file degenerate.png --fee-rate 1"
+    "--regtest wallet inscribe --file foo.txt --fee-rate 1 --change-addresses change.txt",
+  )
+  .write("foo.txt", "FOO")
+  .write(
+    "change.txt",
+    "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw\n",
   )
-  .write("degenerate.png", [1; 520])
   .core(&core)
   .ord(&ord)
+  .expected_stderr(
+    "error: change addresses file `change.txt` must contain at least 2 addresses but contains 1\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  CommandBuilder::new("--regtest wallet inscribe --file foo.txt --fee-rate 1 --change-addresses change.txt")
+    .write("foo.txt", "FOO")
+    .write(
+      "change.txt",
+      "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw\nbc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n",
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "error: change address bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 on line 2 of `change.txt` is not valid for regtest\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  let inscribed = Address::from_script(
+    &core.tx_by_id(reveal).output[0].script_pubkey,
+    Network::Regtest,
+  )
+  .unwrap();
+
+  CommandBuilder::new(
+    "--regtest wallet inscribe --allow-duplicate --file foo.txt --fee-rate 1 --change-addresses change.txt",
+  )
+  .write("foo.txt", "FOO")
+  .write(
+    "change.txt",
+    format!("bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw\n{inscribed}\n"),
+  )
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!(
+    "error: change address {inscribed} on line 2 of `change.txt` holds inscriptions\n"
+  ))
   .expected_exit_code(1)
-  .stderr_regex("error: address tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm belongs to network testnet which is different from required bitcoin\n")
   .run_and_extract_stdout();
 }
 
 #[test]
-fn inscribe_with_no_limit() {
-  let core = mockcore::spawn();
-  let ord = TestServer::spawn(&core);
+fn inscribe_plan_predicts_inscription_id() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let one_megger = std::iter::repeat(0).take(1_000_000).collect::<Vec<u8>>();
-  CommandBuilder::new("wallet inscribe --no-limit --file degenerate.png --fee-rate 1")
-    .write("degenerate.png", one_megger)
+  let args = format!(
+    "--regtest wallet inscribe --file foo.txt --fee-rate 1 --key-seed {} --destination bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw --change-addresses change.txt",
+    "11".repeat(32),
+  );
+
+  let change = "bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k";
+  let other = "bcrt1pgfhzycz8pckwsdspf04hn6rps5tpapgrchtzx5f3k8wlvqhmxu6qeuhw7g";
+
+  let plan = CommandBuilder::new(format!("{args} --plan"))
+    .write("foo.txt", "FOO")
+    .write("change.txt", format!("{change}\n{other}\n"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let info = plan.plan.as_ref().unwrap();
+
+  assert_eq!(info.funding.len(), 1);
+  assert_eq!(info.funding[0].value, 50 * COIN_VALUE);
+  assert_eq!(info.change.len(), 1);
+  assert_eq!(
+    info.change[0].address,
+    change.parse::<Address<NetworkUnchecked>>().unwrap()
+  );
+  assert_eq!(info.postages, [10_000]);
+  assert_eq!(info.recovery_key_fingerprint.len(), 8);
+  assert_eq!(info.commit_fee + info.reveal_fee, plan.total_fees);
+  assert_eq!(
+    info.funding[0].value,
+    info.change[0].value + info.commit_fee + info.reveal_fee + 10_000,
+  );
+
+  assert!(core.mempool().is_empty());
+
+  assert!(CommandBuilder::new("--regtest wallet recovery-keys")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoveryKeys>()
+    .is_empty());
+
+  let output = CommandBuilder::new(args)
+    .write("foo.txt", "FOO")
+    .write("change.txt", format!("{change}\n{other}\n"))
     .core(&core)
     .ord(&ord)
     .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.plan, None);
+  assert_eq!(output.commit, plan.commit);
+  assert_eq!(output.inscriptions[0].id, plan.inscriptions[0].id);
 }
 
 #[test]
-fn inscribe_works_with_postage() {
+fn inscribe_with_minify_prints_single_line() {
   let core = mockcore::spawn();
+
   let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
+
   core.mine_blocks(1);
 
-  CommandBuilder::new("wallet inscribe --file foo.txt --postage 5btc --fee-rate 10".to_string())
-    .write("foo.txt", [0; 350])
+  let stdout = CommandBuilder::new("--minify wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<Batch>();
+    .stdout_regex(".*")
+    .run_and_extract_stdout();
 
-  core.mine_blocks(1);
+  assert_eq!(stdout.lines().count(), 1);
 
-  let inscriptions = CommandBuilder::new("wallet inscriptions".to_string())
-    .write("foo.txt", [0; 350])
-    .core(&core)
-    .ord(&ord)
-    .run_and_deserialize_output::<Vec<inscriptions::Output>>();
+  let output = serde_json::from_str::<Batch>(&stdout).unwrap();
 
-  pretty_assert_eq!(inscriptions[0].postage, 5 * COIN_VALUE);
+  assert_eq!(output.inscriptions.len(), 1);
 }
 
 #[test]
-fn inscribe_with_non_existent_parent_inscription() {
+fn inscribe_with_no_index_update_warns_that_index_may_be_stale() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn(&core);
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &["--no-sync"]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let parent_id = "0000000000000000000000000000000000000000000000000000000000000000i0";
+  ord.sync_server();
 
-  CommandBuilder::new(format!(
-    "wallet inscribe --fee-rate 1.0 --parent {parent_id} --file child.png"
-  ))
-  .write("child.png", [1; 520])
-  .core(&core)
-  .ord(&ord)
-  .expected_stderr(format!("error: parent {parent_id} does not exist\n"))
-  .expected_exit_code(1)
-  .run_and_extract_stdout();
+  // keep the next block's unindexed coinbase output out of the wallet
+  core.mine_blocks(1);
+
+  core.state().receive_addresses.pop();
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --no-index-update")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(
+      r#"\{"code":"unsynced-index","message":"not waiting for `ord server` to synchronize, it has indexed 2 of 3 blocks and results may be stale"\}\n"#,
+    )
+    .run_and_deserialize_output::<Batch>();
 }
 
 #[test]
-fn inscribe_with_parent_inscription_and_fee_rate() {
+fn inscribe_with_no_index_update_fails_if_index_is_empty() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn(&core);
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &["--no-sync"]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let parent_output = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
-    .write("parent.png", [1; 520])
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --no-index-update")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<Batch>();
+    .expected_stderr("error: `ord server` has not indexed any blocks, so its index cannot be used without waiting for it to synchronize\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
 
-  assert_eq!(core.descriptors().len(), 3);
-  let parent_id = parent_output.inscriptions[0].id;
+#[test]
+fn inscribe_with_reveal_annex() {
+  let core = mockcore::spawn();
 
-  let commit_tx = &core.mempool()[0];
-  let reveal_tx = &core.mempool()[1];
+  let ord = TestServer::spawn(&core);
 
-  assert_eq!(
-    ord::FeeRate::try_from(5.0)
-      .unwrap()
-      .fee(commit_tx.vsize() + reveal_tx.vsize())
-      .to_sat(),
-    parent_output.total_fees
-  );
+  create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let child_output = CommandBuilder::new(format!(
-    "wallet inscribe --fee-rate 7.3 --parent {parent_id} --file child.png"
-  ))
-  .write("child.png", [1; 520])
+  let output = CommandBuilder::new(
+    "wallet inscribe --file foo.txt --fee-rate 1 --reveal-annex 50ff --skip-mempool-check",
+  )
+  .write("foo.txt", "FOO")
   .core(&core)
   .ord(&ord)
+  .stderr_regex(
+    r#"\{"code":"non-standard-annex","message":"reveal transaction has a taproot annex, .*"\}\n"#,
+  )
   .run_and_deserialize_output::<Batch>();
 
-  assert_eq!(core.descriptors().len(), 4);
-  assert_eq!(parent_id, child_output.parent.unwrap());
-
-  let commit_tx = &core.mempool()[0];
-  let reveal_tx = &core.mempool()[1];
-
-  assert_eq!(
-    ord::FeeRate::try_from(7.3)
-      .unwrap()
-      .fee(commit_tx.vsize() + reveal_tx.vsize())
-      .to_sat(),
-    child_output.total_fees
-  );
-
-  core.mine_blocks(1);
+  let reveal = core
+    .mempool()
+    .into_iter()
+    .find(|tx| tx.txid() == output.reveal)
+    .unwrap();
 
-  ord.assert_response_regex(
-    format!("/inscription/{}", child_output.parent.unwrap()),
-    format!(
-      ".*<dt>children</dt>.*<a href=/inscription/{}>.*",
-      child_output.inscriptions[0].id
-    ),
-  );
+  assert_eq!(reveal.input[0].witness.last().unwrap(), [0x50, 0xff]);
+}
 
-  ord.assert_response_regex(
-    format!("/inscription/{}", child_output.inscriptions[0].id),
-    format!(
-      ".*<dt>parents</dt>.*<a href=/inscription/{}>.*",
-      child_output.parent.unwrap()
-    ),
-  );
+#[test]
+fn reveal_annex_must_have_annex_prefix() {
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --reveal-annex 51")
+    .stderr_regex(".*annex must begin with the taproot annex prefix 0x50.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
 }
 
 #[test]
-fn reinscribe_with_flag() {
+fn inscribe_with_future_commit_locktime_requires_no_broadcast() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
 
-  core.mine_blocks(1);
-
-  assert_eq!(core.descriptors().len(), 0);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
-  let inscribe = CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
-    .write("tulip.png", [1; 520])
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --commit-locktime 100")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<Batch>();
-
-  assert_eq!(core.descriptors().len(), 3);
-
-  let txid = core.mine_blocks(1)[0].txdata[2].txid();
-
-  let request = ord.request(format!("/content/{}", inscribe.inscriptions[0].id));
+    .expected_stderr(
+      "error: commit locktime 100 has not passed, so the commit transaction would be rejected by the mempool, use --prepare-only, --psbt, or --dry-run to create it without broadcasting\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
 
-  assert_eq!(request.status(), 200);
+  assert!(core.mempool().is_empty());
 
-  let reinscribe = CommandBuilder::new(format!(
-    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {txid}:0:0"
-  ))
-  .write("orchid.png", [1; 520])
+  let output = CommandBuilder::new(
+    "wallet inscribe --file foo.txt --fee-rate 1 --commit-locktime 100 --dry-run",
+  )
+  .write("foo.txt", "FOO")
   .core(&core)
   .ord(&ord)
   .run_and_deserialize_output::<Batch>();
 
-  core.mine_blocks(1);
-
-  let request = ord.request(format!("/content/{}", reinscribe.inscriptions[0].id));
-
-  assert_eq!(request.status(), 200);
-  ord.assert_response_regex(
-    format!("/sat/{}", 50 * COIN_VALUE),
-    format!(
-      ".*<dt>inscriptions</dt>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
-      inscribe.inscriptions[0].id, reinscribe.inscriptions[0].id
-    ),
-  );
+  assert!(output.commit_psbt.is_some());
 
-  let inscriptions = CommandBuilder::new("wallet inscriptions")
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --commit-locktime 1")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<Inscriptions>();
+    .run_and_deserialize_output::<Batch>();
 
-  assert_eq!(inscriptions[0].inscription, inscribe.inscriptions[0].id);
-  assert_eq!(inscriptions[1].inscription, reinscribe.inscriptions[0].id);
+  assert_eq!(core.mempool()[0].lock_time.to_consensus_u32(), 1);
 }
 
 #[test]
-fn with_reinscribe_flag_but_not_actually_a_reinscription() {
+fn inscribe_with_max_funding_utxo_refuses_large_utxos() {
   let core = mockcore::spawn();
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
-    .write("tulip.png", [1; 520])
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --max-funding-utxo 1btc")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .run_and_deserialize_output::<Batch>();
+    .expected_stderr(
+      "error: wallet contains no cardinal utxos, 1 cardinal utxos are worth more than --max-funding-utxo 1 BTC\n",
+    )
+    .expected_exit_code(4)
+    .run_and_extract_stdout();
+}
 
-  let coinbase = core.mine_blocks(1)[0].txdata[0].txid();
-
-  CommandBuilder::new(format!(
-    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {coinbase}:0:0"
-  ))
-  .write("orchid.png", [1; 520])
-  .core(&core)
-  .ord(&ord)
-  .expected_exit_code(1)
-  .stderr_regex("error: reinscribe flag set but this would not be a reinscription.*")
-  .run_and_extract_stdout();
-}
+// extract the unsigned transaction from the PSBT and add a dummy witness to
+// its single input
+#[cfg(unix)]
+const SIGNER: &str = "psbt=$(base64 -d | od -An -v -tx1 | tr -d ' \\n')
+tx=${psbt:16:$((16#${psbt:14:2} * 2))}
+printf '%s0001%s0140%0128d%s\\n' \"${tx:0:8}\" \"${tx:8:${#tx}-16}\" 0 \"${tx: -8}\"
+";
 
+#[cfg(unix)]
 #[test]
-fn try_reinscribe_without_flag() {
+fn inscribe_with_signer_command() {
   let core = mockcore::spawn();
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let reveal_txid = CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
-    .write("tulip.png", [1; 520])
-    .core(&core)
-    .ord(&ord)
-    .run_and_deserialize_output::<Batch>()
-    .reveal;
+  let output = CommandBuilder::new([
+    "wallet",
+    "inscribe",
+    "--file",
+    "foo.txt",
+    "--fee-rate",
+    "1",
+    "--signer-command",
+    "bash signer.sh",
+  ])
+  .write("foo.txt", "FOO")
+  .write("signer.sh", SIGNER)
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
 
-  assert_eq!(core.descriptors().len(), 3);
+  assert_eq!(core.mempool()[0].txid(), output.commit);
+  assert_eq!(core.mempool()[0].input[0].witness.len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn signer_command_must_sign_all_inputs() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  CommandBuilder::new(format!(
-    "wallet inscribe --file orchid.png --fee-rate 1.1 --satpoint {reveal_txid}:0:0"
-  ))
-  .write("orchid.png", [1; 520])
+  CommandBuilder::new([
+    "wallet",
+    "inscribe",
+    "--file",
+    "foo.txt",
+    "--fee-rate",
+    "1",
+    "--signer-command",
+    "cat",
+  ])
+  .write("foo.txt", "FOO")
   .core(&core)
   .ord(&ord)
+  .stderr_regex("error: signer command `cat` did not sign commit transaction input 0\n")
   .expected_exit_code(1)
-  .stderr_regex(format!(
-    "error: sat at {reveal_txid}:0:0 already inscribed.*"
-  ))
   .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
 }
 
+#[cfg(unix)]
 #[test]
-fn no_metadata_appears_on_inscription_page_if_no_metadata_is_passed() {
+fn signer_command_result_is_checked_before_broadcast() {
   let core = mockcore::spawn();
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } =
-    CommandBuilder::new("wallet inscribe --fee-rate 1 --file content.png")
-      .write("content.png", [1; 520])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
-
-  core.mine_blocks(1);
+  core.state().mempool_reject_reason =
+    Some("mandatory-script-verify-flag-failed (Invalid Schnorr signature)".into());
+
+  CommandBuilder::new([
+    "wallet",
+    "inscribe",
+    "--file",
+    "foo.txt",
+    "--fee-rate",
+    "1",
+    "--signer-command",
+    "bash signer.sh",
+  ])
+  .write("foo.txt", "FOO")
+  .write("signer.sh", SIGNER)
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(
+    "error: signer command `bash signer.sh` returned invalid commit transaction: mandatory-script-verify-flag-failed (Invalid Schnorr signature)\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
 
-  assert!(!ord
-    .request(format!("/inscription/{inscription}"),)
-    .text()
-    .unwrap()
-    .contains("metadata"));
+  assert!(core.mempool().is_empty());
 }
 
+#[cfg(unix)]
 #[test]
-fn json_metadata_appears_on_inscription_page() {
+fn signer_command_failure_includes_stderr() {
   let core = mockcore::spawn();
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } = CommandBuilder::new(
-    "wallet inscribe --fee-rate 1 --json-metadata metadata.json --file content.png",
-  )
-  .write("content.png", [1; 520])
-  .write("metadata.json", r#"{"foo": "bar", "baz": 1}"#)
+  CommandBuilder::new([
+    "wallet",
+    "inscribe",
+    "--file",
+    "foo.txt",
+    "--fee-rate",
+    "1",
+    "--signer-command",
+    "echo device not connected >&2; exit 1",
+  ])
+  .write("foo.txt", "FOO")
   .core(&core)
   .ord(&ord)
-  .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
-
-  core.mine_blocks(1);
-
-  ord.assert_response_regex(
-    format!("/inscription/{inscription}"),
-    ".*<dt>metadata</dt>.*<dl><dt>foo</dt><dd>bar</dd><dt>baz</dt><dd>1</dd></dl>.*",
-  );
+  .stderr_regex(
+    "error: signer command `echo device not connected >&2; exit 1` failed with exit status: 1: device not connected\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
 }
 
 #[test]
-fn cbor_metadata_appears_on_inscription_page() {
+fn inscribe_with_commit_fee_rate() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } = CommandBuilder::new(
-    "wallet inscribe --fee-rate 1 --cbor-metadata metadata.cbor --file content.png",
-  )
-  .write("content.png", [1; 520])
-  .write(
-    "metadata.cbor",
-    [
-      0xA2, 0x63, b'f', b'o', b'o', 0x63, b'b', b'a', b'r', 0x63, b'b', b'a', b'z', 0x01,
-    ],
+  CommandBuilder::new(
+    "--index-sats wallet inscribe --allow-rare --file degenerate.png --commit-fee-rate 2.0 --fee-rate 1",
   )
+  .write("degenerate.png", [1; 520])
   .core(&core)
   .ord(&ord)
-  .run_and_deserialize_output();
+  .run_and_deserialize_output::<Batch>();
 
-  let inscription = inscriptions[0].id;
+  let tx1 = &core.mempool()[0];
+  let mut fee = 0;
+  for input in &tx1.input {
+    fee += core
+      .get_utxo_amount(&input.previous_output)
+      .unwrap()
+      .to_sat();
+  }
+  for output in &tx1.output {
+    fee -= output.value;
+  }
 
-  core.mine_blocks(1);
+  let fee_rate = fee as f64 / tx1.vsize() as f64;
 
-  ord.assert_response_regex(
-    format!("/inscription/{inscription}"),
-    ".*<dt>metadata</dt>.*<dl><dt>foo</dt><dd>bar</dd><dt>baz</dt><dd>1</dd></dl>.*",
-  );
-}
+  pretty_assert_eq!(fee_rate, 2.0);
 
-#[test]
-fn error_message_when_parsing_json_metadata_is_reasonable() {
-  let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let tx2 = &core.mempool()[1];
+  let mut fee = 0;
+  for input in &tx2.input {
+    fee += &tx1.output[input.previous_output.vout as usize].value;
+  }
+  for output in &tx2.output {
+    fee -= output.value;
+  }
 
-  create_wallet(&core, &ord);
+  let fee_rate = fee as f64 / tx2.vsize() as f64;
 
-  CommandBuilder::new(
-    "wallet inscribe --fee-rate 1 --json-metadata metadata.json --file content.png",
-  )
-  .write("content.png", [1; 520])
-  .write("metadata.json", "{")
-  .core(&core)
-  .ord(&ord)
-  .stderr_regex(".*failed to parse JSON metadata.*")
-  .expected_exit_code(1)
-  .run_and_extract_stdout();
+  pretty_assert_eq!(fee_rate, 1.0);
 }
 
 #[test]
-fn error_message_when_parsing_cbor_metadata_is_reasonable() {
+fn inscribe_with_commit_and_reveal_fee_rates() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
+  core.mine_blocks(1);
+
   CommandBuilder::new(
-    "wallet inscribe --fee-rate 1 --cbor-metadata metadata.cbor --file content.png",
+    "wallet inscribe --file degenerate.png --commit-fee-rate 1 --reveal-fee-rate 3 --fee-rate 2",
   )
-  .write("content.png", [1; 520])
-  .write("metadata.cbor", [0x61])
+  .write("degenerate.png", [1; 520])
   .core(&core)
   .ord(&ord)
-  .stderr_regex(".*failed to parse CBOR metadata.*")
-  .expected_exit_code(1)
-  .run_and_extract_stdout();
+  .run_and_deserialize_output::<Batch>();
+
+  let commit_tx = &core.mempool()[0];
+
+  let fee = commit_tx
+    .input
+    .iter()
+    .map(|input| {
+      core
+        .get_utxo_amount(&input.previous_output)
+        .unwrap()
+        .to_sat()
+    })
+    .sum::<u64>()
+    - commit_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .sum::<u64>();
+
+  pretty_assert_eq!(fee as f64 / commit_tx.vsize() as f64, 1.0);
+
+  let reveal_tx = &core.mempool()[1];
+
+  let fee = reveal_tx
+    .input
+    .iter()
+    .map(|input| commit_tx.output[input.previous_output.vout as usize].value)
+    .sum::<u64>()
+    - reveal_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .sum::<u64>();
+
+  pretty_assert_eq!(fee as f64 / reveal_tx.vsize() as f64, 3.0);
 }
 
 #[test]
-fn inscribe_does_not_pick_locked_utxos() {
+fn inscribe_with_zero_reveal_fee_rate_fails() {
   let core = mockcore::spawn();
-
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
-  let coinbase_tx = &core.mine_blocks(1)[0].txdata[0];
-  let outpoint = OutPoint::new(coinbase_tx.txid(), 0);
-
-  core.lock(outpoint);
+  core.mine_blocks(1);
 
-  CommandBuilder::new("wallet inscribe --file hello.txt --fee-rate 1")
+  CommandBuilder::new("wallet inscribe --file foo.txt --reveal-fee-rate 0 --fee-rate 1")
+    .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .write("hello.txt", "HELLOWORLD")
-    .expected_exit_code(1)
-    .stderr_regex("error: wallet contains no cardinal utxos\n")
+    .expected_exit_code(2)
+    .stderr_regex(".*fee rate must be positive.*")
     .run_and_extract_stdout();
 }
 
 #[test]
-fn inscribe_can_compress() {
+fn inscribe_warns_if_commit_fee_rate_is_below_min_relay_fee() {
   let core = mockcore::spawn();
-
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } =
-    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
-      .write("foo.txt", [0; 350_000])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
-
-  core.mine_blocks(1);
-
-  ord.sync_server();
-
-  let client = reqwest::blocking::Client::builder()
-    .brotli(false)
-    .build()
-    .unwrap();
-
-  let response = client
-    .get(
-      ord
-        .url()
-        .join(format!("/content/{inscription}",).as_ref())
-        .unwrap(),
-    )
-    .send()
-    .unwrap();
-
-  assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
-  assert_regex_match!(
-    response.text().unwrap(),
-    "inscription content encoding `br` is not acceptable. `Accept-Encoding` header not present"
-  );
-
-  let client = reqwest::blocking::Client::builder()
-    .brotli(true)
-    .build()
-    .unwrap();
+  core.state().min_relay_tx_fee = Amount::from_sat(2000);
 
-  let response = client
-    .get(
-      ord
-        .url()
-        .join(format!("/content/{inscription}",).as_ref())
-        .unwrap(),
+  CommandBuilder::new("wallet inscribe --dry-run --commit-fee-rate 1 --fee-rate 5 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(
+      r#"\{"code":"commit-below-min-relay-fee","message":"commit transaction fee rate of 1\.\d\d sats/vB is below node minimum relay fee rate of 2\.00 sats/vB, commit transaction may not propagate to nodes without package relay"\}\n"#,
     )
-    .send()
-    .unwrap();
+    .run_and_deserialize_output::<Batch>();
 
-  assert_eq!(response.status(), StatusCode::OK);
-  assert_eq!(response.bytes().unwrap().deref(), [0; 350_000]);
+  CommandBuilder::new("wallet inscribe --dry-run --commit-fee-rate 3 --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr("")
+    .run_and_deserialize_output::<Batch>();
 }
 
 #[test]
-fn inscriptions_are_not_compressed_if_no_space_is_saved_by_compression() {
+fn inscribe_with_per_phase_fee_rates() {
   let core = mockcore::spawn();
-
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } =
-    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
-      .write("foo.txt", "foo")
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
+  CommandBuilder::new(
+    "--index-sats wallet inscribe --allow-rare --file degenerate.png --fee-rates commit=1,reveal=3 --fee-rate 2",
+  )
+  .write("degenerate.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  let tx1 = &core.mempool()[0];
+  let mut fee = 0;
+  for input in &tx1.input {
+    fee += core
+      .get_utxo_amount(&input.previous_output)
+      .unwrap()
+      .to_sat();
+  }
+  for output in &tx1.output {
+    fee -= output.value;
+  }
+
+  pretty_assert_eq!(fee as f64 / tx1.vsize() as f64, 1.0);
+
+  let tx2 = &core.mempool()[1];
+  let mut fee = 0;
+  for input in &tx2.input {
+    fee += &tx1.output[input.previous_output.vout as usize].value;
+  }
+  for output in &tx2.output {
+    fee -= output.value;
+  }
+
+  pretty_assert_eq!(fee as f64 / tx2.vsize() as f64, 3.0);
+}
+
+#[test]
+fn fee_rates_conflicts_with_commit_fee_rate() {
+  CommandBuilder::new(
+    "wallet inscribe --file foo.txt --fee-rates commit=1 --commit-fee-rate 2 --fee-rate 1",
+  )
+  .expected_exit_code(2)
+  .stderr_regex(
+    ".*--fee-rates <PHASE=RATE,...>.*cannot be used with.*--commit-fee-rate <COMMIT_FEE_RATE>.*",
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_wallet_named_foo() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  CommandBuilder::new("wallet --name foo create")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<create::Output>();
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet --name foo inscribe --file degenerate.png --fee-rate 1")
+    .write("degenerate.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_with_wallet_flag() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  CommandBuilder::new("wallet --wallet foo create")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<create::Output>();
+
+  assert!(core.wallets().contains("foo"));
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet --wallet foo inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_with_missing_wallet_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet --wallet bar inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(3)
+    .stderr_regex(
+      "(?s)error: Bitcoin Core wallet `bar` is not loaded and could not be loaded\nbecause: .*",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_reports_transaction_sizes() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 2")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let commit = core.tx_by_id(output.commit);
+  let reveal = core.tx_by_id(output.reveal);
+
+  assert_eq!(output.commit_weight, Some(commit.weight().to_wu()));
+  assert_eq!(
+    output.commit_vsize,
+    Some(commit.vsize().try_into().unwrap())
+  );
+  assert_eq!(output.reveal_weight, Some(reveal.weight().to_wu()));
+  assert_eq!(
+    output.reveal_vsize,
+    Some(reveal.vsize().try_into().unwrap())
+  );
+
+  let reveal_fee = commit.output[usize::try_from(reveal.input[0].previous_output.vout).unwrap()]
+    .value
+    - reveal.output.iter().map(|output| output.value).sum::<u64>();
+
+  assert_eq!(reveal_fee, output.reveal_vsize.unwrap() * 2);
+}
+
+#[test]
+fn inscribe_with_dry_run_has_no_transaction_sizes() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --dry-run --file foo.txt --fee-rate 2")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.commit_vsize, None);
+  assert_eq!(output.reveal_weight, None);
+}
+
+#[test]
+fn inscribe_with_dry_run_flag() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe =
+    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
+      .write("degenerate.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  assert!(inscribe.commit_psbt.is_some());
+
+  let reveal_psbt = Psbt::deserialize(
+    &base64::engine::general_purpose::STANDARD
+      .decode(inscribe.reveal_psbt.unwrap())
+      .unwrap(),
+  )
+  .unwrap();
+
+  assert!(reveal_psbt.inputs[0].witness_utxo.is_some());
+
+  assert!(core.mempool().is_empty());
+
+  let inscribe = CommandBuilder::new("wallet inscribe --file degenerate.png --fee-rate 1")
+    .write("degenerate.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert!(inscribe.commit_psbt.is_none());
+  assert!(inscribe.reveal_psbt.is_none());
+
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_with_dry_run_flag_fees_increase() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let total_fee_dry_run =
+    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
+      .write("degenerate.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>()
+      .total_fees;
+
+  let total_fee_normal =
+    CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1.1")
+      .write("degenerate.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>()
+      .total_fees;
+
+  assert!(total_fee_dry_run < total_fee_normal);
+}
+
+#[test]
+fn inscribe_to_specific_destination() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let addresses = CommandBuilder::new("wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses;
+
+  let destination = addresses.first().unwrap();
+
+  let txid = CommandBuilder::new(format!(
+    "wallet inscribe --destination {} --file degenerate.png --fee-rate 1",
+    destination.clone().assume_checked()
+  ))
+  .write("degenerate.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>()
+  .reveal;
+
+  let reveal_tx = &core.mempool()[1]; // item 0 is the commit, item 1 is the reveal.
+  assert_eq!(reveal_tx.txid(), txid);
+  assert_eq!(
+    reveal_tx.output.first().unwrap().script_pubkey,
+    destination.payload.script_pubkey()
+  );
+}
+
+#[test]
+fn inscribe_to_destination_derived_from_descriptor() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(
+    "--regtest wallet inscribe --destination-descriptor tr(tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/0/*) --destination-index 7 --file degenerate.png --fee-rate 1",
+  )
+  .write("degenerate.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  let destination = "bcrt1ptl65uhgl6zgavkfh4y4e7uc4237neufcedhnyekjhvlvd0pw208qzfz4uh"
+    .parse::<Address<NetworkUnchecked>>()
+    .unwrap();
+
+  assert_eq!(output.inscriptions[0].destination, destination);
+
+  let reveal_tx = &core.mempool()[1];
+  assert_eq!(reveal_tx.txid(), output.reveal);
+  assert_eq!(
+    reveal_tx.output[0].script_pubkey,
+    destination.assume_checked().script_pubkey()
+  );
+}
+
+#[test]
+fn inscribe_to_multiple_destinations() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let destinations = [
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+    "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+    "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+  ];
+
+  let output = CommandBuilder::new(
+    "wallet inscribe --destinations destinations.txt --file foo.txt --fee-rate 1",
+  )
+  .write(
+    "destinations.txt",
+    format!("{}\n\n", destinations.join("\n")),
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.inscriptions.len(), destinations.len());
+
+  let reveal_tx = &core.mempool()[1];
+
+  assert_eq!(reveal_tx.txid(), output.reveal);
+
+  for (i, (inscription, destination)) in output.inscriptions.iter().zip(destinations).enumerate() {
+    let destination = destination.parse::<Address<NetworkUnchecked>>().unwrap();
+
+    assert_eq!(inscription.destination, destination);
+    assert_eq!(
+      inscription.id,
+      InscriptionId {
+        txid: output.reveal,
+        index: i.try_into().unwrap(),
+      }
+    );
+    assert_eq!(
+      inscription.location,
+      SatPoint {
+        outpoint: OutPoint {
+          txid: output.reveal,
+          vout: i.try_into().unwrap(),
+        },
+        offset: 0,
+      }
+    );
+    assert_eq!(
+      reveal_tx.output[i].script_pubkey,
+      destination.assume_checked().script_pubkey()
+    );
+  }
+
+  core.mine_blocks(1);
+
+  for inscription in &output.inscriptions {
+    ord.assert_response_regex(format!("/content/{}", inscription.id), "FOO");
+  }
+}
+
+#[test]
+fn inscribe_with_invalid_destinations_file_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --destinations destinations.txt --file foo.txt --fee-rate 1")
+    .write(
+      "destinations.txt",
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\ntb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm\n",
+    )
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: destination address tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm on line 2 of `.*destinations.txt` is not valid for mainnet\n",
+    )
+    .run_and_extract_stdout();
+
+  CommandBuilder::new(
+    "wallet inscribe --destinations destinations.txt --file foo.txt --fee-rate 1",
+  )
+  .write("destinations.txt", "\n")
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .stderr_regex("error: destinations file `.*destinations.txt` contains no addresses\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_to_address_on_different_network() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribe --destination tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm --file degenerate.png --fee-rate 1"
+  )
+  .write("degenerate.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .stderr_regex("error: address tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm belongs to network testnet which is different from required bitcoin\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_no_limit_requires_confirmation() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let one_megger = std::iter::repeat(0).take(1_000_000).collect::<Vec<u8>>();
+  CommandBuilder::new("wallet inscribe --no-limit --file degenerate.png --fee-rate 1")
+    .write("degenerate.png", one_megger)
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: --no-limit requires --i-know-what-im-doing when not running interactively\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_no_limit() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let one_megger = std::iter::repeat(0).take(1_000_000).collect::<Vec<u8>>();
+  CommandBuilder::new(
+    "wallet inscribe --no-limit --i-know-what-im-doing --file degenerate.png --fee-rate 1",
+  )
+  .write("degenerate.png", one_megger)
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    r#"\{"code":"no-limit-check","message":"transaction weight limit check disabled, transactions may not be relayed"\}\n\{"code":"large-content","message":"inscription content of 1000000 bytes .*"\}\n"#,
+  )
+  .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_refuses_duplicate_content() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: inscription content is identical to inscription {inscription} already in wallet, use --allow-duplicate to inscribe anyway\n"
+    ))
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet inscribe --file bar.txt --fee-rate 1")
+    .write("bar.txt", "BAR")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_refuses_compressed_duplicate_of_content() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO".repeat(100))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  mine_commit_and_reveal(&core, &output);
+
+  let inscription = output.inscriptions[0].id;
+
+  CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO".repeat(100))
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: inscription content is identical to inscription {inscription} already in wallet, use --allow-duplicate to inscribe anyway\n"
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_allow_duplicate() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  inscribe(&core, &ord);
+
+  CommandBuilder::new("wallet inscribe --allow-duplicate --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_with_change_split() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(
+    "wallet inscribe --change-split 10000sat,20000sat --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let commit = core.tx_by_id(output.commit);
+
+  assert_eq!(commit.output.len(), 3);
+  assert_eq!(commit.output[1].value, 10_000);
+  assert!(commit.output[2].value >= 20_000);
+}
+
+#[test]
+fn inscribe_with_consolidate() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(3);
+
+  let output = CommandBuilder::new("wallet inscribe --consolidate 1 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let commit = core.tx_by_id(output.commit);
+
+  assert_eq!(commit.input.len(), 2);
+  assert_eq!(commit.output.len(), 2);
+  assert!(commit
+    .output
+    .iter()
+    .any(|output| output.value > 50 * COIN_VALUE));
+}
+
+#[test]
+fn inscribe_with_change_split_exceeding_change_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --change-split 30btc,30btc --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex("error: requested change split of 60(\\.0+)? BTC exceeds available change of .*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_commit_hash() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let hash = "ab".repeat(32);
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --commit-hash {hash} --file foo.txt --fee-rate 1"
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let reveal = core.tx_by_id(output.reveal);
+
+  assert_eq!(reveal.output.len(), 2);
+  assert_eq!(reveal.output[1].value, 0);
+  assert_eq!(
+    reveal.output[1].script_pubkey.to_hex_string(),
+    format!("6a20{hash}"),
+  );
+}
+
+#[test]
+fn inscribe_with_reveal_output_index() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let hash = "ab".repeat(32);
+
+  for index in [0, 1] {
+    let output = CommandBuilder::new(format!(
+      "wallet inscribe --commit-hash {hash} --reveal-output-index {index} --file foo.txt --fee-rate 1"
+    ))
+    .write("foo.txt", format!("FOO {index}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+    core.mine_blocks(1);
+
+    let reveal = core.tx_by_id(output.reveal);
+
+    assert_eq!(reveal.output.len(), 2);
+    assert_eq!(reveal.output[index].value, 10_000);
+    assert_eq!(
+      reveal.output[1 - index].script_pubkey.to_hex_string(),
+      format!("6a20{hash}"),
+    );
+
+    let location = SatPoint {
+      outpoint: OutPoint {
+        txid: output.reveal,
+        vout: index.try_into().unwrap(),
+      },
+      offset: 0,
+    };
+
+    assert_eq!(output.inscriptions[0].location, location);
+
+    let inscription = ord
+      .json_request(format!("/inscription/{}", output.inscriptions[0].id))
+      .json::<api::Inscription>()
+      .unwrap();
+
+    assert_eq!(inscription.satpoint, location);
+  }
+}
+
+#[test]
+fn inscribe_with_reveal_output_index_out_of_range_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --reveal-output-index 1 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: reveal output index 1 out of range, reveal transaction has 1 outputs\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_oversized_commit_hash_fails() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --commit-hash {} --file foo.txt --fee-rate 1",
+    "00".repeat(81)
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: commit hash greater than maximum standard OP_RETURN data size: 81 > 80\n",
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_confirmation_target() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.state().fee_estimate = Some(Amount::from_sat(10_000));
+
+  let estimated =
+    CommandBuilder::new("wallet inscribe --dry-run --confirmation-target 6 --file foo.txt")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  let explicit = CommandBuilder::new("wallet inscribe --dry-run --fee-rate 10 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(estimated.total_fees, explicit.total_fees);
+}
+
+#[test]
+fn inscribe_with_confirmation_target_falls_back_without_estimate() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let estimated = CommandBuilder::new(
+    "wallet inscribe --dry-run --confirmation-target 6 --fallback-fee-rate 3 --file foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(
+    "{\"code\":\"fallback-fee-rate\",\"message\":\"Bitcoin Core has no fee estimate for confirmation target of 6 blocks, using fallback fee rate of 3 sats/vB\"}\n",
+  )
+  .run_and_deserialize_output::<Batch>();
+
+  let explicit = CommandBuilder::new("wallet inscribe --dry-run --fee-rate 3 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(estimated.total_fees, explicit.total_fees);
+}
+
+#[test]
+fn inscribe_with_fee_rate_from_mempool() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0, Default::default())],
+    fee: 10_000,
+    outputs: 1,
+    ..default()
+  });
+
+  let fee_rate = 10_000.0 / core.mempool()[0].vsize() as f64;
+
+  let mempool = CommandBuilder::new(
+    "wallet inscribe --dry-run --fee-rate-from-mempool --mempool-percentile 90 --file foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  let explicit = CommandBuilder::new(format!(
+    "wallet inscribe --dry-run --fee-rate {fee_rate} --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(mempool.total_fees, explicit.total_fees);
+}
+
+#[test]
+fn inscribe_with_fee_rate_from_empty_mempool_uses_minimum_fee() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mempool = CommandBuilder::new("wallet inscribe --dry-run --fee-rate-from-mempool --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "{\"code\":\"empty-mempool\",\"message\":\"mempool is empty, using mempool minimum fee rate of 1 sats/vB\"}\n",
+    )
+    .run_and_deserialize_output::<Batch>();
+
+  let explicit = CommandBuilder::new("wallet inscribe --dry-run --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(mempool.total_fees, explicit.total_fees);
+}
+
+#[test]
+fn inscribe_mempool_percentile_requires_fee_rate_from_mempool() {
+  CommandBuilder::new("wallet inscribe --mempool-percentile 50 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex(".*--fee-rate-from-mempool.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_fee_rate_conflicts_with_confirmation_target() {
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --confirmation-target 6 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex(
+      "error: the argument '--(fee-rate|confirmation-target) <.*>' cannot be used with '--(fee-rate|confirmation-target) <.*>'.*",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_aborts_if_mempool_would_reject() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.state().mempool_reject_reason = Some("min relay fee not met".into());
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: commit transaction .* rejected by mempool: min relay fee not met, use --skip-mempool-check to broadcast anyway\n",
+    )
+    .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_skip_mempool_check() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.state().mempool_reject_reason = Some("min relay fee not met".into());
+
+  CommandBuilder::new("wallet inscribe --skip-mempool-check --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_with_output_file() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("--output batch.json wallet inscribe --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_extract_file("batch.json");
+
+  let output = serde_json::from_str::<Batch>(&output).unwrap();
+
+  assert_eq!(output.inscriptions.len(), 1);
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_works_with_postage() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --postage 5btc --fee-rate 10".to_string())
+    .write("foo.txt", [0; 350])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions".to_string())
+    .write("foo.txt", [0; 350])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<inscriptions::Output>>();
+
+  pretty_assert_eq!(inscriptions[0].postage, 5 * COIN_VALUE);
+}
+
+#[test]
+fn inscribe_with_non_existent_parent_inscription() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let parent_id = "0000000000000000000000000000000000000000000000000000000000000000i0";
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1.0 --parent {parent_id} --file child.png"
+  ))
+  .write("child.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!("error: parent {parent_id} does not exist\n"))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_parent_inscription_and_fee_rate() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let parent_output = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
+    .write("parent.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.descriptors().len(), 3);
+  let parent_id = parent_output.inscriptions[0].id;
+
+  let commit_tx = &core.mempool()[0];
+  let reveal_tx = &core.mempool()[1];
+
+  assert_eq!(
+    ord::FeeRate::try_from(5.0)
+      .unwrap()
+      .fee(commit_tx.vsize() + reveal_tx.vsize())
+      .to_sat(),
+    parent_output.total_fees
+  );
+
+  core.mine_blocks(1);
+
+  let child_output = CommandBuilder::new(format!(
+    "wallet inscribe --allow-duplicate --fee-rate 7.3 --parent {parent_id} --file child.png"
+  ))
+  .write("child.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.descriptors().len(), 4);
+  assert_eq!(parent_id, child_output.parent.unwrap());
+
+  let commit_tx = &core.mempool()[0];
+  let reveal_tx = &core.mempool()[1];
+
+  assert_eq!(
+    ord::FeeRate::try_from(7.3)
+      .unwrap()
+      .fee(commit_tx.vsize() + reveal_tx.vsize())
+      .to_sat(),
+    child_output.total_fees
+  );
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", child_output.parent.unwrap()),
+    format!(
+      ".*<dt>children</dt>.*<a href=/inscription/{}>.*",
+      child_output.inscriptions[0].id
+    ),
+  );
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", child_output.inscriptions[0].id),
+    format!(
+      ".*<dt>parents</dt>.*<a href=/inscription/{}>.*",
+      child_output.parent.unwrap()
+    ),
+  );
+}
+
+#[test]
+fn reinscribe_with_flag() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  core.mine_blocks(1);
+
+  assert_eq!(core.descriptors().len(), 0);
+
+  create_wallet(&core, &ord);
+
+  let inscribe =
+    CommandBuilder::new("wallet inscribe --allow-rare --file tulip.png --fee-rate 5.0 ")
+      .write("tulip.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.descriptors().len(), 3);
+
+  let txid = core.mine_blocks(1)[0].txdata[2].txid();
+
+  let request = ord.request(format!("/content/{}", inscribe.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+
+  let reinscribe = CommandBuilder::new(format!(
+    "wallet inscribe --allow-rare --allow-duplicate --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {txid}:0:0"
+  ))
+  .write("orchid.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let request = ord.request(format!("/content/{}", reinscribe.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+  ord.assert_response_regex(
+    format!("/sat/{}", 50 * COIN_VALUE),
+    format!(
+      ".*<dt>inscriptions</dt>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
+      inscribe.inscriptions[0].id, reinscribe.inscriptions[0].id
+    ),
+  );
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions[0].inscription, inscribe.inscriptions[0].id);
+  assert_eq!(inscriptions[1].inscription, reinscribe.inscriptions[0].id);
+}
+
+#[test]
+fn with_reinscribe_flag_but_not_actually_a_reinscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --allow-rare --file tulip.png --fee-rate 5.0 ")
+    .write("tulip.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let coinbase = core.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {coinbase}:0:0"
+  ))
+  .write("orchid.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .stderr_regex("error: reinscribe flag set but this would not be a reinscription.*")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn try_reinscribe_without_flag() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let reveal_txid =
+    CommandBuilder::new("wallet inscribe --allow-rare --file tulip.png --fee-rate 5.0 ")
+      .write("tulip.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>()
+      .reveal;
+
+  assert_eq!(core.descriptors().len(), 3);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file orchid.png --fee-rate 1.1 --satpoint {reveal_txid}:0:0"
+  ))
+  .write("orchid.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .stderr_regex(format!(
+    "error: sat at {reveal_txid}:0:0 already inscribed.*"
+  ))
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn no_metadata_appears_on_inscription_page_if_no_metadata_is_passed() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } =
+    CommandBuilder::new("wallet inscribe --fee-rate 1 --file content.png")
+      .write("content.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  assert!(!ord
+    .request(format!("/inscription/{inscription}"),)
+    .text()
+    .unwrap()
+    .contains("metadata"));
+}
+
+#[test]
+fn json_metadata_appears_on_inscription_page() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --json-metadata metadata.json --file content.png",
+  )
+  .write("content.png", [1; 520])
+  .write("metadata.json", r#"{"foo": "bar", "baz": 1}"#)
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{inscription}"),
+    ".*<dt>metadata</dt>.*<dl><dt>foo</dt><dd>bar</dd><dt>baz</dt><dd>1</dd></dl>.*",
+  );
+}
+
+#[test]
+fn cbor_metadata_appears_on_inscription_page() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --cbor-metadata metadata.cbor --file content.png",
+  )
+  .write("content.png", [1; 520])
+  .write(
+    "metadata.cbor",
+    [
+      0xA2, 0x63, b'f', b'o', b'o', 0x63, b'b', b'a', b'r', 0x63, b'b', b'a', b'z', 0x01,
+    ],
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{inscription}"),
+    ".*<dt>metadata</dt>.*<dl><dt>foo</dt><dd>bar</dd><dt>baz</dt><dd>1</dd></dl>.*",
+  );
+}
+
+#[test]
+fn error_message_when_parsing_json_metadata_is_reasonable() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --json-metadata metadata.json --file content.png",
+  )
+  .write("content.png", [1; 520])
+  .write("metadata.json", "{")
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(".*failed to parse JSON metadata.*")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn error_message_when_parsing_cbor_metadata_is_reasonable() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --cbor-metadata metadata.cbor --file content.png",
+  )
+  .write("content.png", [1; 520])
+  .write("metadata.cbor", [0x61])
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(".*failed to parse CBOR metadata.*")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_does_not_pick_locked_utxos() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let coinbase_tx = &core.mine_blocks(1)[0].txdata[0];
+  let outpoint = OutPoint::new(coinbase_tx.txid(), 0);
+
+  core.lock(outpoint);
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --fee-rate 1")
+    .core(&core)
+    .ord(&ord)
+    .write("hello.txt", "HELLOWORLD")
+    .expected_exit_code(4)
+    .stderr_regex("error: wallet contains no cardinal utxos\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_does_not_pick_excluded_utxos() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let coinbase_tx = &core.mine_blocks(1)[0].txdata[0];
+  let outpoint = OutPoint::new(coinbase_tx.txid(), 0);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file hello.txt --fee-rate 1 --exclude-utxo {outpoint}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .write("hello.txt", "HELLOWORLD")
+  .expected_exit_code(4)
+  .stderr_regex("error: wallet contains no cardinal utxos\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_times_out_if_server_does_not_respond() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  // accepts connections but never responds
+  let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+  CommandBuilder::new(format!(
+    "wallet --server-url http://{} inscribe --file hello.txt --fee-rate 1 --timeout 1",
+    listener.local_addr().unwrap(),
+  ))
+  .core(&core)
+  .write("hello.txt", "HELLOWORLD")
+  .expected_exit_code(3)
+  .stderr_regex("error: timed out loading wallet, nothing was broadcast\n.*")
+  .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_respects_content_type_allowlist_and_denylist() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --fee-rate 1 --allow-content-type image/*")
+    .core(&core)
+    .ord(&ord)
+    .write("hello.txt", "HELLOWORLD")
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: content type `text/plain;charset=utf-8` is not allowed by --allow-content-type\n",
+    )
+    .run_and_extract_stdout();
+
+  CommandBuilder::new(
+    "wallet inscribe --file hello.txt --fee-rate 1 --allow-content-type text/* --deny-content-type text/plain",
+  )
+  .core(&core)
+  .ord(&ord)
+  .write("hello.txt", "HELLOWORLD")
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: content type `text/plain;charset=utf-8` is denied by --deny-content-type `text/plain`\n",
+  )
+  .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+
+  CommandBuilder::new(
+    "wallet inscribe --file hello.txt --fee-rate 1 --allow-content-type text/* --deny-content-type text/html",
+  )
+  .core(&core)
+  .ord(&ord)
+  .write("hello.txt", "HELLOWORLD")
+  .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_refuses_to_import_recovery_descriptor_for_wrong_address() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let descriptors = core.descriptors().len();
+
+  core.state().derive_wrong_addresses = true;
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --fee-rate 1")
+    .core(&core)
+    .ord(&ord)
+    .write("hello.txt", "HELLOWORLD")
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: recovery descriptor derives .* instead of commit address .*, refusing to import it\n",
+    )
+    .run_and_extract_stdout();
+
+  assert_eq!(core.descriptors().len(), descriptors);
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_debug_sigs_prints_sighash_and_signature() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --fee-rate 1 --debug-sigs")
+    .core(&core)
+    .ord(&ord)
+    .write("hello.txt", "HELLOWORLD")
+    .stderr_regex(
+      "reveal input 0 public key: [[:xdigit:]]{64}
+reveal input 0 sighash: [[:xdigit:]]{64}
+reveal input 0 signature: [[:xdigit:]]{128}
+",
+    )
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_can_compress() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } =
+    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
+      .write("foo.txt", [0; 350_000])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.sync_server();
+
+  let client = reqwest::blocking::Client::builder()
+    .brotli(false)
+    .build()
+    .unwrap();
+
+  let response = client
+    .get(
+      ord
+        .url()
+        .join(format!("/content/{inscription}",).as_ref())
+        .unwrap(),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+  assert_regex_match!(
+    response.text().unwrap(),
+    "inscription content encoding `br` is not acceptable. `Accept-Encoding` header not present"
+  );
+
+  let client = reqwest::blocking::Client::builder()
+    .brotli(true)
+    .build()
+    .unwrap();
+
+  let response = client
+    .get(
+      ord
+        .url()
+        .join(format!("/content/{inscription}",).as_ref())
+        .unwrap(),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(response.bytes().unwrap().deref(), [0; 350_000]);
+}
+
+#[test]
+fn inscriptions_are_not_compressed_if_no_space_is_saved_by_compression() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } =
+    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
+      .write("foo.txt", "foo")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.sync_server();
+
+  let client = reqwest::blocking::Client::builder()
+    .brotli(false)
+    .build()
+    .unwrap();
+
+  let response = client
+    .get(
+      ord
+        .url()
+        .join(format!("/content/{inscription}",).as_ref())
+        .unwrap(),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(response.text().unwrap(), "foo");
+}
+
+#[test]
+fn inscribe_with_sat_arg() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let Batch { inscriptions, .. } = CommandBuilder::new(
+    "--index-sats wallet inscribe --file foo.txt --sat 5010000000 --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    "/sat/5010000000",
+    format!(".*<a href=/inscription/{inscription}>.*"),
+  );
+
+  ord.assert_response_regex(format!("/content/{inscription}",), "FOO");
+}
+
+#[test]
+fn inscribe_with_sat_arg_fails_if_no_index_or_not_found() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --sat 5010000000 --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: ord index must be built with `--index-sats` to use `--sat`\n")
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("--index-sats wallet inscribe --sat 5000000000 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&TestServer::spawn_with_server_args(
+      &core,
+      &["--index-sats"],
+      &[],
+    ))
+    .expected_exit_code(1)
+    .expected_stderr("error: could not find sat `5000000000` in wallet outputs\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn server_can_decompress_brotli() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let Batch { inscriptions, .. } =
+    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
+      .write("foo.txt", [0; 350_000])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output();
+
+  let inscription = inscriptions[0].id;
+
+  core.mine_blocks(1);
+
+  ord.sync_server();
+
+  let client = reqwest::blocking::Client::builder()
+    .brotli(false)
+    .build()
+    .unwrap();
+
+  let response = client
+    .get(
+      ord
+        .url()
+        .join(format!("/content/{inscription}",).as_ref())
+        .unwrap(),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+  let test_server = TestServer::spawn_with_server_args(&core, &[], &["--decompress"]);
+
+  test_server.sync_server();
+
+  let client = reqwest::blocking::Client::builder()
+    .brotli(false)
+    .build()
+    .unwrap();
+
+  let response = client
+    .get(
+      test_server
+        .url()
+        .join(format!("/content/{inscription}",).as_ref())
+        .unwrap(),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(response.bytes().unwrap().deref(), [0; 350_000]);
+}
+
+#[test]
+fn inscribe_with_compress_auto_reports_encoding() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &["--decompress"]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let content = "the quick brown fox jumps over the lazy dog\n".repeat(1000);
+
+  let output = CommandBuilder::new("wallet inscribe --compress-auto --file foo.txt --fee-rate 1")
+    .write("foo.txt", &content)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let content_encoding = output.inscriptions[0].content_encoding.clone().unwrap();
+
+  assert!(content_encoding == "br" || content_encoding == "gzip");
+
+  core.mine_blocks(1);
+
+  let decoded = CommandBuilder::new(format!("decode --compact --txid {}", output.reveal))
+    .core(&core)
+    .run_and_deserialize_output::<CompactOutput>();
+
+  let inscription = &decoded.inscriptions[0];
+
+  assert_eq!(inscription.content_encoding, Some(content_encoding));
+
+  assert_eq!(
+    u64::try_from(inscription.content_length.unwrap()).unwrap()
+      + output.inscriptions[0].bytes_saved.unwrap(),
+    u64::try_from(content.len()).unwrap(),
+  );
+
+  ord.sync_server();
+
+  let response = reqwest::blocking::Client::builder()
+    .brotli(false)
+    .build()
+    .unwrap()
+    .get(
+      ord
+        .url()
+        .join(format!("/content/{}", output.inscriptions[0].id).as_ref())
+        .unwrap(),
+    )
+    .header(reqwest::header::ACCEPT_ENCODING, "identity")
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(response.text().unwrap(), content);
+}
+
+#[test]
+fn compress_auto_conflicts_with_compress() {
+  CommandBuilder::new("wallet inscribe --compress-auto --compress --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex(".*--compress-auto.*cannot be used with.*--compress.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_reveal_csv_waits_for_relative_locktime() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mut spawn = CommandBuilder::new("wallet inscribe --reveal-csv 3 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .spawn();
+
+  let mut buffer = String::new();
+
+  BufReader::new(spawn.child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
+    .unwrap();
+
+  assert_eq!(
+    buffer,
+    "Waiting for commit transaction to reach 3 confirmations…\n"
+  );
+
+  core.mine_blocks(3);
+
+  let output = spawn.run_and_deserialize_output::<Batch>();
+
+  let reveal = core
+    .mempool()
+    .into_iter()
+    .find(|tx| tx.txid() == output.reveal)
+    .unwrap();
+
+  assert_eq!(reveal.version, 2);
+  assert_eq!(reveal.input[0].sequence, Sequence::from_height(3));
+  assert!(reveal.input[0].sequence.is_rbf());
+}
+
+#[test]
+fn inscribe_with_await_commit_confirmations_waits_before_broadcasting_reveal() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mut spawn = CommandBuilder::new(
+    "wallet inscribe --await-commit-confirmations 2 --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .spawn();
+
+  let mut stderr = BufReader::new(spawn.child.stderr.take().unwrap());
+
+  let mut buffer = String::new();
+
+  stderr.read_line(&mut buffer).unwrap();
+
+  assert_eq!(
+    buffer,
+    "Waiting for commit transaction to reach 2 confirmations…\n"
+  );
+
+  assert_eq!(core.mempool().len(), 1);
+
+  core.mine_blocks(1);
+
+  buffer.clear();
+
+  stderr.read_line(&mut buffer).unwrap();
+
+  assert_eq!(buffer, "Commit transaction has 1 of 2 confirmations…\n");
+
+  assert!(core.mempool().is_empty());
+
+  core.mine_blocks(1);
+
+  let output = spawn.run_and_deserialize_output::<Batch>();
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), output.reveal);
+  assert_eq!(
+    mempool[0].input[0].sequence,
+    Sequence::ENABLE_RBF_NO_LOCKTIME
+  );
+}
+
+#[test]
+fn await_commit_confirmations_aborts_if_commit_is_dropped() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mut spawn = CommandBuilder::new(
+    "wallet inscribe --await-commit-confirmations 1 --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    "error: commit output [[:xdigit:]]{64}:0 no longer exists, commit transaction was likely replaced: \
+    rebuild the reveal transaction against the replacement commit transaction\n",
+  )
+  .expected_exit_code(1)
+  .spawn();
+
+  let mut buffer = String::new();
+
+  BufReader::new(spawn.child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
+    .unwrap();
+
+  assert_eq!(
+    buffer,
+    "Waiting for commit transaction to reach 1 confirmations…\n"
+  );
+
+  core.state().mempool.clear();
+
+  spawn.run_and_extract_stdout();
+}
+
+#[test]
+fn await_commit_confirmations_conflicts_with_use_package_relay() {
+  CommandBuilder::new(
+    "wallet inscribe --await-commit-confirmations 1 --use-package-relay --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .expected_exit_code(2)
+  .stderr_regex(
+    ".*--await-commit-confirmations <CONFIRMATIONS>.*cannot be used with.*--use-package-relay.*",
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn reveal_csv_conflicts_with_no_rbf() {
+  CommandBuilder::new("wallet inscribe --reveal-csv 3 --no-rbf --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex(".*--reveal-csv <BLOCKS>.*cannot be used with.*--no-rbf.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_validate_content_rejects_truncated_image() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --validate-content --file foo.png --fee-rate 1")
+    .write(
+      "foo.png",
+      b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x00",
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: invalid image/png content: truncated IHDR chunk\n")
+    .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+
+  CommandBuilder::new("wallet inscribe --validate-content --file foo.jpg --fee-rate 1")
+    .write("foo.jpg", [0xFF, 0xD8, 0xFF, 0xE0, 0, 0])
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: invalid image/jpeg content: missing JPEG end of image marker, file may be truncated\n",
+    )
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet inscribe --file foo.jpg --fee-rate 1")
+    .write("foo.jpg", [0xFF, 0xD8, 0xFF, 0xE0, 0, 0])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_with_use_package_relay() {
+  let core = mockcore::builder().version(280000).build();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("wallet inscribe --use-package-relay --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.package_msg.as_deref(), Some("success"));
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[0].txid(), output.commit);
+  assert_eq!(mempool[1].txid(), output.reveal);
+}
+
+#[test]
+fn use_package_relay_falls_back_to_separate_broadcast() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --use-package-relay --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(r#"\{"code":"no-package-relay","message":"Bitcoin Core 24.0.0 does not support package relay, broadcasting commit and reveal transactions separately"\}\n"#)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.package_msg, None);
+  assert_eq!(core.mempool().len(), 2);
+}
+
+#[test]
+fn use_package_relay_saves_reveal_if_only_commit_is_accepted() {
+  let core = mockcore::builder().version(280000).build();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.state().broadcast_limit = Some(1);
+
+  CommandBuilder::new("wallet inscribe --use-package-relay --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex(
+      r#"(?s).*error: Failed to send reveal transaction: package rejected: transaction failed, [[:xdigit:]]{64}: broadcast limit reached\nCommit tx [[:xdigit:]]{64} was broadcast, .*`ord wallet resume-reveal reveal-[[:xdigit:]]{64}\.json`\n"#,
+    )
+    .run_and_extract_stdout();
+
+  assert_eq!(core.mempool().len(), 1);
+}
+
+#[test]
+fn inscribe_with_emit_reveal_key() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("--regtest wallet inscribe --emit-reveal-key --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  let reveal_key = bitcoin::PrivateKey::from_wif(&output.reveal_key.unwrap()).unwrap();
+
+  // regtest keys use the testnet WIF prefix
+  assert_eq!(reveal_key.network, Network::Testnet);
+
+  let reveal_tx = core
+    .mempool()
+    .into_iter()
+    .find(|tx| tx.txid() == output.reveal)
+    .unwrap();
+
+  let (public_key, _parity) = reveal_key
+    .inner
+    .x_only_public_key(&bitcoin::secp256k1::Secp256k1::new());
+
+  assert_eq!(
+    &reveal_tx.input[0].witness.nth(1).unwrap()[1..33],
+    public_key.serialize(),
+  );
+}
+
+#[test]
+fn inscribe_without_emit_reveal_key_has_no_reveal_key() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.reveal_key, None);
+}
+
+#[test]
+fn emit_reveal_key_is_refused_on_mainnet() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --emit-reveal-key --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: --emit-reveal-key exposes the reveal transaction key and may only be used on regtest or signet, not mainnet\n")
+    .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_idempotency_key_is_reproducible() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = |idempotency_key: &str| {
+    CommandBuilder::new(format!(
+      "wallet inscribe --dry-run --idempotency-key {idempotency_key} --file foo.txt --fee-rate 1"
+    ))
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>()
+  };
+
+  let a = inscribe("foo");
+  let b = inscribe("foo");
+
+  assert_eq!(a.commit_address, b.commit_address);
+  assert_eq!(a.commit, b.commit);
+  assert_eq!(a.reveal, b.reveal);
+
+  assert_ne!(a.commit_address, inscribe("bar").commit_address);
+}
+
+#[test]
+fn inscribe_with_idempotency_key_refuses_to_fund_commit_address_twice() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let output =
+    CommandBuilder::new("wallet inscribe --idempotency-key foo --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribe --idempotency-key foo --allow-duplicate --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: commit address {} for idempotency key `foo` was already funded by transaction {}, refusing to fund it again\n",
+    output.commit_address.assume_checked(),
+    output.commit,
+  ))
+  .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_shard_size_inscribes_shards_and_manifest() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let content = "the quick brown fox jumps over the lazy dog";
+
+  let output = CommandBuilder::new("wallet inscribe --shard-size 16 --file foo.txt --fee-rate 1")
+    .write("foo.txt", content)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::wallet::inscribe::ShardedOutput>();
+
+  assert_eq!(output.shards.len(), 3);
+  assert_eq!(core.mempool().len(), 8);
+
+  core.mine_blocks(1);
+
+  let manifest = ord
+    .request(format!("/content/{}", output.manifest.inscriptions[0].id))
+    .json::<Manifest>()
+    .unwrap();
+
+  assert_eq!(
+    manifest.shards,
+    output
+      .shards
+      .iter()
+      .map(|shard| shard.inscriptions[0].id)
+      .collect::<Vec<InscriptionId>>(),
+  );
+
+  assert_eq!(
+    manifest.content_type.as_deref(),
+    Some("text/plain;charset=utf-8")
+  );
+
+  let shards = manifest
+    .shards
+    .iter()
+    .map(|shard| {
+      ord
+        .request(format!("/content/{shard}"))
+        .bytes()
+        .unwrap()
+        .to_vec()
+    })
+    .collect::<Vec<Vec<u8>>>();
+
+  assert_eq!(shards[0], b"the quick brown ");
+
+  assert_eq!(manifest.reassemble(&shards).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn inscribe_with_shard_size_refuses_unconfirmed_chain_over_mempool_limit() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --shard-size 1 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "0123456789ab")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: inscribing 12 shards and a manifest creates a chain of 26 unconfirmed transactions, more than the mempool limit of 25, use a larger --shard-size\n",
+    )
+    .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_shard_size_reports_inscribed_shards_on_failure() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.state().broadcast_limit = Some(3);
+
+  CommandBuilder::new("wallet inscribe --shard-size 16 --file foo.txt --fee-rate 1")
+    .write("foo.txt", "the quick brown fox jumps over the lazy dog")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex(
+      "error: failed after inscribing 1 shards: [[:xdigit:]]{64}i0\nbecause: Failed to send reveal transaction: .*",
+    )
+    .run_and_extract_stdout();
+
+  assert_eq!(core.mempool().len(), 3);
+}
+
+#[test]
+fn shard_size_conflicts_with_dry_run() {
+  CommandBuilder::new("wallet inscribe --shard-size 16 --dry-run --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex(".*--shard-size <SHARD_SIZE>.*cannot be used with.*--dry-run.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn file_inscribe_with_delegate_inscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (delegate, _) = inscribe(&core, &ord);
+
+  let inscribe = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file inscription.txt"
+  ))
+  .write("inscription.txt", "INSCRIPTION")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", inscribe.inscriptions[0].id),
+    format!(r#".*<dt>delegate</dt>\s*<dd><a href=/inscription/{delegate}>{delegate}</a></dd>.*"#,),
+  );
+
+  ord.assert_response(format!("/content/{}", inscribe.inscriptions[0].id), "FOO");
+}
+
+#[test]
+fn inscribe_delegate_without_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (delegate, _) = inscribe(&core, &ord);
+
+  let inscribe = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1.0 --delegate {delegate}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", inscribe.inscriptions[0].id),
+    format!(r#".*<dt>delegate</dt>\s*<dd><a href=/inscription/{delegate}>{delegate}</a></dd>.*"#,),
+  );
+
+  ord.assert_response(format!("/content/{}", inscribe.inscriptions[0].id), "FOO");
+}
+
+#[test]
+fn inscription_with_delegate_returns_effective_content_type() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+  let (delegate, _) = inscribe(&core, &ord);
+
+  let inscribe = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file meow.wav"
+  ))
+  .write("meow.wav", [0; 2048])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let inscription_id = inscribe.inscriptions[0].id;
+  let json_response = ord.json_request(format!("/inscription/{}", inscription_id));
+
+  let inscription_json: api::Inscription =
+    serde_json::from_str(&json_response.text().unwrap()).unwrap();
+  assert_regex_match!(inscription_json.address.unwrap(), r"bc1p.*");
+
+  assert_eq!(inscription_json.content_type, Some("audio/wav".to_string()));
+  assert_eq!(
+    inscription_json.effective_content_type,
+    Some("text/plain;charset=utf-8".to_string())
+  );
+}
+
+#[test]
+fn file_inscribe_with_non_existent_delegate_inscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let delegate = "0000000000000000000000000000000000000000000000000000000000000000i0";
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file child.png"
+  ))
+  .write("child.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!("error: delegate {delegate} does not exist\n"))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_refuses_to_inscribe_rare_sat() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let txid = core.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: sat 5000000000 at {txid}:0:0 is uncommon, use --allow-rare to inscribe on it anyway\n"
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_empty_file_requires_allow_empty() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  ord.sync_server();
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file empty.txt")
+    .write("empty.txt", "")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: inscription content is empty, use --allow-empty to inscribe it anyway\n",
+    )
+    .run_and_extract_stdout();
 
-  let client = reqwest::blocking::Client::builder()
-    .brotli(false)
-    .build()
+  let output = CommandBuilder::new("wallet inscribe --allow-empty --fee-rate 1 --file empty.txt")
+    .write("empty.txt", "")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let decoded = CommandBuilder::new(format!("decode --txid {}", output.reveal))
+    .core(&core)
+    .run_and_deserialize_output::<RawOutput>();
+
+  assert_eq!(decoded.inscriptions.len(), 1);
+  assert_eq!(decoded.inscriptions[0].payload.body, Some(Vec::new()));
+  assert_eq!(
+    decoded.inscriptions[0].payload.content_type,
+    Some(b"text/plain;charset=utf-8".to_vec())
+  );
+}
+
+#[test]
+fn inscribe_with_pointer() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --pointer 100 --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let location = SatPoint {
+    outpoint: OutPoint {
+      txid: output.reveal,
+      vout: 0,
+    },
+    offset: 100,
+  };
+
+  assert_eq!(output.inscriptions[0].location, location);
+
+  core.mine_blocks(1);
+
+  let decoded = CommandBuilder::new(format!("decode --txid {}", output.reveal))
+    .core(&core)
+    .run_and_deserialize_output::<RawOutput>();
+
+  assert_eq!(decoded.inscriptions[0].payload.pointer, Some(vec![100]));
+
+  let inscription = ord
+    .json_request(format!("/inscription/{}", output.inscriptions[0].id))
+    .json::<api::Inscription>()
+    .unwrap();
+
+  assert_eq!(inscription.satpoint, location);
+}
+
+#[test]
+fn inscribe_with_pointer_beyond_outputs_fails() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --pointer 10000 --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: pointer 10000 is not less than total reveal output value of 10000 sat\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_rare_sat_with_allow_rare() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --allow-rare --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.rarity, Some(Rarity::Uncommon));
+}
+
+#[test]
+fn inscribe_output_has_no_rarity_without_sat_index() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.rarity, None);
+}
+
+#[test]
+fn inscribe_with_check_rarity_warns_without_sat_index() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --check-rarity --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(r#"\{"code":"unknown-rarity","message":".*"\}\n"#)
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_warns_about_large_content() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", [0; 100_001])
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "{\"code\":\"large-content\",\"message\":\"inscription content of 100001 bytes uses over a quarter of the maximum standard transaction weight of 400000 weight units and will be expensive to inscribe\"}\n",
+    )
+    .run_and_deserialize_output::<Batch>();
+}
+
+#[test]
+fn inscribe_with_await_number() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let mut spawn = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt --await-number")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .spawn();
+
+  let mut buffer = String::new();
+
+  BufReader::new(spawn.child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
     .unwrap();
 
-  let response = client
-    .get(
-      ord
-        .url()
-        .join(format!("/content/{inscription}",).as_ref())
-        .unwrap(),
-    )
-    .send()
-    .unwrap();
+  assert_regex_match!(
+    buffer,
+    "Waiting for inscription [[:xdigit:]]{64}i0 to be indexed…\n"
+  );
+
+  core.mine_blocks(1);
+
+  let output = spawn.run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.inscriptions[0].number, Some(0));
+}
+
+#[test]
+fn inscribe_without_await_number_has_no_number() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.inscriptions[0].number, None);
+}
+
+#[test]
+fn inscribe_with_psbt_does_not_sign_commit() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt --psbt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert!(core.mempool().is_empty());
+
+  let decode = |psbt: &str| {
+    Psbt::deserialize(
+      &base64::engine::general_purpose::STANDARD
+        .decode(psbt)
+        .unwrap(),
+    )
+    .unwrap()
+  };
+
+  let commit_psbt = decode(output.commit_psbt.as_ref().unwrap());
+
+  assert_eq!(commit_psbt.unsigned_tx.txid(), output.commit);
+
+  assert!(commit_psbt
+    .inputs
+    .iter()
+    .all(|input| input.final_script_witness.is_none()));
+
+  let reveal_psbt = decode(output.reveal_psbt.as_ref().unwrap());
+
+  assert_eq!(reveal_psbt.unsigned_tx.txid(), output.reveal);
+  assert_eq!(
+    reveal_psbt.unsigned_tx.input[0].previous_output.txid,
+    output.commit
+  );
+  assert!(reveal_psbt.inputs[0].final_script_witness.is_some());
+  assert!(reveal_psbt.inputs[0].witness_utxo.is_some());
+}
+
+#[test]
+fn inscribe_with_reuse_change_uses_unused_wallet_address() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let unused = CommandBuilder::new("wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .assume_checked();
+
+  let pays_unused = |args: &str| {
+    let output = CommandBuilder::new(format!(
+      "wallet inscribe --allow-duplicate --file foo.txt --fee-rate 1{args}"
+    ))
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+    core.mine_blocks(1);
+
+    core
+      .tx_by_id(output.commit)
+      .output
+      .iter()
+      .any(|output| output.script_pubkey == unused.script_pubkey())
+  };
+
+  assert!(!pays_unused(""));
+
+  assert!(pays_unused(" --reuse-change"));
+
+  assert!(!pays_unused(" --reuse-change"));
+}
+
+#[test]
+fn inscribe_with_from_label_only_spends_labeled_outputs() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let labeled = core.mine_blocks(1)[0].txdata[0].txid();
+
+  {
+    let mut state = core.state();
+    let address = state.receive_addresses.last().unwrap().clone();
+    state.labels.insert(address, "savings".into());
+  }
+
+  let output =
+    CommandBuilder::new("wallet inscribe --from-label savings --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
 
-  assert_eq!(response.status(), StatusCode::OK);
-  assert_eq!(response.text().unwrap(), "foo");
+  assert!(core
+    .tx_by_id(output.commit)
+    .input
+    .iter()
+    .all(|input| input.previous_output.txid == labeled));
 }
 
 #[test]
-fn inscribe_with_sat_arg() {
+fn inscribe_with_from_label_fails_without_labeled_outputs() {
   let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
 
-  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --from-label savings --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: no unspent outputs with label `savings` found in wallet\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_from_url() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
-  core.mine_blocks(2);
+  let (inscription, _reveal) = inscribe(&core, &ord);
 
-  let Batch { inscriptions, .. } = CommandBuilder::new(
-    "--index-sats wallet inscribe --file foo.txt --sat 5010000000 --fee-rate 1",
-  )
-  .write("foo.txt", "FOO")
+  let url = ord.url().join(&format!("/content/{inscription}")).unwrap();
+
+  CommandBuilder::new(format!("wallet inscribe --fee-rate 1 --file {url}"))
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: inscribing from URL {url} requires --allow-remote\n"
+    ))
+    .run_and_extract_stdout();
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --allow-remote --allow-duplicate --fee-rate 1 --file {url}"
+  ))
   .core(&core)
   .ord(&ord)
-  .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
+  .run_and_deserialize_output::<Batch>();
 
-  core.mine_blocks(1);
+  mine_commit_and_reveal(&core, &output);
 
   ord.assert_response_regex(
-    "/sat/5010000000",
-    format!(".*<a href=/inscription/{inscription}>.*"),
+    format!("/inscription/{}", output.inscriptions[0].id),
+    ".*<dt>content type</dt>\\s*<dd>text/plain;charset=utf-8</dd>.*",
   );
 
-  ord.assert_response_regex(format!("/content/{inscription}",), "FOO");
+  ord.assert_response(format!("/content/{}", output.inscriptions[0].id), "FOO");
 }
 
 #[test]
-fn inscribe_with_sat_arg_fails_if_no_index_or_not_found() {
+fn inscribe_from_url_fails_on_error_response() {
   let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let url = ord
+    .url()
+    .join("/content/6ac5cacb768794f4fd7a78bf00f2074891fce68bd65c4ff36e77177237aacacai0")
+    .unwrap();
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --allow-remote --fee-rate 1 --file {url}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: error downloading {url}: server returned 404 Not Found\n"
+  ))
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_log_level_logs_to_stderr() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
-  CommandBuilder::new("wallet inscribe --file foo.txt --sat 5010000000 --fee-rate 1")
+  core.mine_blocks(1);
+
+  CommandBuilder::new("--log-level info wallet inscribe --fee-rate 1 --file foo.txt")
     .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .expected_exit_code(1)
-    .expected_stderr("error: ord index must be built with `--index-sats` to use `--sat`\n")
-    .run_and_extract_stdout();
+    .stderr_regex(
+      "(?s).*Built commit transaction [[:xdigit:]]{64} and reveal transaction [[:xdigit:]]{64} in .*\
+      Signed commit and reveal transactions in .*\
+      Broadcast commit transaction [[:xdigit:]]{64} in .*\
+      Broadcast reveal transaction [[:xdigit:]]{64} in .*",
+    )
+    .run_and_deserialize_output::<Batch>();
+}
 
-  CommandBuilder::new("--index-sats wallet inscribe --sat 5000000000 --file foo.txt --fee-rate 1")
+#[test]
+fn inscribe_with_confirm_requires_yes() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --confirm --fee-rate 1 --file foo.txt")
     .write("foo.txt", "FOO")
     .core(&core)
-    .ord(&TestServer::spawn_with_server_args(
-      &core,
-      &["--index-sats"],
-      &[],
-    ))
+    .ord(&ord)
+    .stdin("no\n".into())
     .expected_exit_code(1)
-    .expected_stderr("error: could not find sat `5000000000` in wallet outputs\n")
+    .stderr_regex(
+      "Inscribing at satpoint [[:xdigit:]]{64}:\\d+:\\d+:
+  3 bytes of text/plain;charset=utf-8 to bc1p\\w+
+Commit fee: \\d+ sat
+Reveal fee: \\d+ sat
+Postage: 10000 sat
+Total cost: \\d+ sat
+Broadcast commit and reveal transactions\\? Type `yes` to continue: error: aborted by user
+",
+    )
     .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+
+  CommandBuilder::new("wallet inscribe --confirm --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stdin("yes\n".into())
+    .stderr_regex("(?s).*Type `yes` to continue: ")
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.mempool().len(), 2);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribe --confirm --yes --allow-duplicate --fee-rate 1 --file foo.txt",
+  )
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(core.mempool().len(), 2);
 }
 
 #[test]
-fn server_can_decompress_brotli() {
-  let core = mockcore::spawn();
+fn inscribe_with_inscription_request() {
+  use {
+    clap::Parser,
+    ord::{wallet::InscriptionRequest, FeeRate, Options},
+  };
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let Batch { inscriptions, .. } =
-    CommandBuilder::new("wallet inscribe --compress --file foo.txt --fee-rate 1".to_string())
-      .write("foo.txt", [0; 350_000])
-      .core(&core)
-      .ord(&ord)
-      .run_and_deserialize_output();
-
-  let inscription = inscriptions[0].id;
+  let tempdir = TempDir::new().unwrap();
 
-  core.mine_blocks(1);
+  let file = tempdir.path().join("foo.txt");
 
-  ord.sync_server();
+  fs::write(&file, "FOO").unwrap();
 
-  let client = reqwest::blocking::Client::builder()
-    .brotli(false)
-    .build()
-    .unwrap();
+  let options = Options::try_parse_from([
+    "ord".into(),
+    "--bitcoin-rpc-url".into(),
+    core.url(),
+    "--cookie-file".into(),
+    core.cookie_file().display().to_string(),
+    "--datadir".into(),
+    tempdir.path().display().to_string(),
+  ])
+  .unwrap();
 
-  let response = client
-    .get(
-      ord
-        .url()
-        .join(format!("/content/{inscription}",).as_ref())
-        .unwrap(),
-    )
-    .send()
+  let destination = CommandBuilder::new("wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses
+    .remove(0);
+
+  let output = InscriptionRequest::new(&file)
+    .fee_rate(FeeRate::try_from(2.0).unwrap())
+    .destination(destination.clone())
+    .postage(Amount::from_sat(5_000))
+    .server_url(ord.url())
+    .build(&options)
     .unwrap();
 
-  assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
-
-  let test_server = TestServer::spawn_with_server_args(&core, &[], &["--decompress"]);
+  assert_eq!(output.inscriptions.len(), 1);
+  assert_eq!(output.inscriptions[0].destination, destination);
 
-  test_server.sync_server();
+  let reveal_tx = &core.mempool()[1];
 
-  let client = reqwest::blocking::Client::builder()
-    .brotli(false)
-    .build()
-    .unwrap();
+  assert_eq!(reveal_tx.txid(), output.reveal);
+  assert_eq!(reveal_tx.output[0].value, 5_000);
 
-  let response = client
-    .get(
-      test_server
-        .url()
-        .join(format!("/content/{inscription}",).as_ref())
-        .unwrap(),
-    )
-    .send()
-    .unwrap();
+  core.mine_blocks(1);
 
-  assert_eq!(response.status(), StatusCode::OK);
-  assert_eq!(response.bytes().unwrap().deref(), [0; 350_000]);
+  ord.assert_response_regex(format!("/content/{}", output.inscriptions[0].id), "FOO");
 }
 
 #[test]
-fn file_inscribe_with_delegate_inscription() {
-  let core = mockcore::spawn();
+fn inscribe_requires_active_taproot() {
+  let core = mockcore::builder().network(Network::Regtest).build();
 
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  core.state().taproot_activation_height = 5;
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest"], &[]);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let (delegate, _) = inscribe(&core, &ord);
-
-  let inscribe = CommandBuilder::new(format!(
-    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file inscription.txt"
-  ))
-  .write("inscription.txt", "INSCRIPTION")
-  .core(&core)
-  .ord(&ord)
-  .run_and_deserialize_output::<Batch>();
-
-  core.mine_blocks(1);
+  CommandBuilder::new("--regtest wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: taproot is not active on regtest, inscribing requires taproot outputs and schnorr signatures\n",
+    )
+    .run_and_extract_stdout();
 
-  ord.assert_response_regex(
-    format!("/inscription/{}", inscribe.inscriptions[0].id),
-    format!(r#".*<dt>delegate</dt>\s*<dd><a href=/inscription/{delegate}>{delegate}</a></dd>.*"#,),
-  );
+  core.mine_blocks(3);
 
-  ord.assert_response(format!("/content/{}", inscribe.inscriptions[0].id), "FOO");
+  CommandBuilder::new("--regtest wallet inscribe --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
 }
 
 #[test]
-fn inscription_with_delegate_returns_effective_content_type() {
+fn inscribe_with_extra_tags() {
   let core = mockcore::spawn();
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
+
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
-  let (delegate, _) = inscribe(&core, &ord);
 
-  let inscribe = CommandBuilder::new(format!(
-    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file meow.wav"
-  ))
-  .write("meow.wav", [0; 2048])
-  .core(&core)
-  .ord(&ord)
-  .run_and_deserialize_output::<Batch>();
+  let output =
+    CommandBuilder::new("wallet inscribe --tag 17=abcd --tag 19= --file foo.txt --fee-rate 1")
+      .write("foo.txt", "FOO")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
 
-  core.mine_blocks(1);
+  let reveal_tx = &core.mempool()[1];
 
-  let inscription_id = inscribe.inscriptions[0].id;
-  let json_response = ord.json_request(format!("/inscription/{}", inscription_id));
+  assert_eq!(reveal_tx.txid(), output.reveal);
 
-  let inscription_json: api::Inscription =
-    serde_json::from_str(&json_response.text().unwrap()).unwrap();
-  assert_regex_match!(inscription_json.address.unwrap(), r"bc1p.*");
+  let tapscript = reveal_tx.input[0].witness.tapscript().unwrap().as_bytes();
 
-  assert_eq!(inscription_json.content_type, Some("audio/wav".to_string()));
-  assert_eq!(
-    inscription_json.effective_content_type,
-    Some("text/plain;charset=utf-8".to_string())
+  assert!(tapscript
+    .windows(6)
+    .any(|window| window == [1, 17, 2, 0xab, 0xcd, 1]));
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", output.inscriptions[0].id),
+    ".*<title>Inscription 0</title>.*",
   );
 }
 
 #[test]
-fn file_inscribe_with_non_existent_delegate_inscription() {
+fn inscribe_with_even_tag_requires_allow_even_tags() {
   let core = mockcore::spawn();
-
-  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+  let ord = TestServer::spawn(&core);
 
   create_wallet(&core, &ord);
 
   core.mine_blocks(1);
 
-  let delegate = "0000000000000000000000000000000000000000000000000000000000000000i0";
+  CommandBuilder::new("wallet inscribe --tag 18=abcd --file foo.txt --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: tag 18 is even, and unrecognized even tags make the inscription cursed and unbound, use --allow-even-tags to add it anyway\n")
+    .run_and_extract_stdout();
 
-  CommandBuilder::new(format!(
-    "wallet inscribe --fee-rate 1.0 --delegate {delegate} --file child.png"
-  ))
-  .write("child.png", [1; 520])
+  CommandBuilder::new(
+    "wallet inscribe --tag 18=abcd --allow-even-tags --file foo.txt --fee-rate 1",
+  )
+  .write("foo.txt", "FOO")
   .core(&core)
   .ord(&ord)
-  .expected_stderr(format!("error: delegate {delegate} does not exist\n"))
-  .expected_exit_code(1)
-  .run_and_extract_stdout();
+  .run_and_deserialize_output::<Batch>();
 }
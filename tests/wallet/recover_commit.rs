@@ -0,0 +1,291 @@
+use super::*;
+
+fn abandon_reveal(core: &mockcore::Handle, ord: &TestServer) -> (Batch, String) {
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(core)
+    .ord(ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.state().mempool.retain(|tx| tx.txid() != output.reveal);
+
+  core.mine_blocks(1);
+
+  (output, recovery_key(core))
+}
+
+fn recovery_key(core: &mockcore::Handle) -> String {
+  core
+    .descriptors()
+    .into_iter()
+    .find_map(|descriptor| {
+      descriptor
+        .strip_prefix("rawtr(")
+        .and_then(|descriptor| descriptor.split(')').next())
+        .map(str::to_string)
+    })
+    .unwrap()
+}
+
+#[test]
+fn recover_commit_sweeps_commit_output_to_wallet() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscribe, recovery_key) = abandon_reveal(&core, &ord);
+
+  let output = CommandBuilder::new("wallet recover-commit --fee-rate 1")
+    .stdin(format!("{recovery_key}\n").into())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoverCommit>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+  assert_eq!(output.outputs.len(), 1);
+  assert_eq!(output.outputs[0].txid, inscribe.commit);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid(), output.txid);
+  assert_eq!(mempool[0].input[0].previous_output, output.outputs[0]);
+  assert_eq!(mempool[0].input[0].witness.len(), 1);
+  assert_eq!(mempool[0].output[0].value, output.amount);
+}
+
+#[test]
+fn recover_commit_reads_recovery_key_from_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscribe, recovery_key) = abandon_reveal(&core, &ord);
+
+  let output =
+    CommandBuilder::new("wallet recover-commit --fee-rate 1 --recovery-key-file key.txt")
+      .write("key.txt", format!("{recovery_key}\n"))
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<RecoverCommit>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+}
+
+#[test]
+fn recover_commit_sweeps_unconfirmed_commit_output() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core
+    .state()
+    .mempool
+    .retain(|tx| tx.txid() != inscribe.reveal);
+
+  let output = CommandBuilder::new("wallet recover-commit --fee-rate 1")
+    .stdin(recovery_key(&core).into())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoverCommit>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+  assert_eq!(output.outputs.len(), 1);
+  assert_eq!(output.outputs[0].txid, inscribe.commit);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[1].txid(), output.txid);
+}
+
+#[test]
+fn recover_commit_fails_if_commit_output_is_spent() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet recover-commit --fee-rate 1")
+    .stdin(recovery_key(&core).into())
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex("error: no unspent outputs found at commit address .*\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn recover_commit_with_encrypted_recovery_key_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let key = tempdir.path().join("key.txt");
+
+  let inscribe = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --file foo.txt --recovery-key-file {}",
+    key.display()
+  ))
+  .env("ORD_RECOVERY_KEY_PASSPHRASE", "bar")
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core
+    .state()
+    .mempool
+    .retain(|tx| tx.txid() != inscribe.reveal);
+
+  core.mine_blocks(1);
+
+  let contents = fs::read_to_string(&key).unwrap();
+
+  assert!(contents.starts_with("ord-encrypted-recovery-key-v1:"));
+  assert!(!contents.contains(&recovery_key(&core)));
+
+  CommandBuilder::new(format!(
+    "wallet recover-commit --fee-rate 1 --recovery-key-file {}",
+    key.display()
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: recovery key is encrypted, use --recovery-key-passphrase to decrypt it\n",
+  )
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "wallet recover-commit --fee-rate 1 --recovery-key-file {}",
+    key.display()
+  ))
+  .env("ORD_RECOVERY_KEY_PASSPHRASE", "baz")
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: failed to decrypt recovery key, passphrase may be incorrect\n")
+  .run_and_extract_stdout();
+
+  let output = CommandBuilder::new(format!(
+    "wallet recover-commit --fee-rate 1 --recovery-key-file {}",
+    key.display()
+  ))
+  .env("ORD_RECOVERY_KEY_PASSPHRASE", "bar")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<RecoverCommit>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+}
+
+#[test]
+fn recover_commit_with_recovery_key_passphrase_flag() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let key = tempdir.path().join("key.txt");
+
+  let inscribe = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --file foo.txt --recovery-key-file {} --recovery-key-passphrase bar",
+    key.display()
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core
+    .state()
+    .mempool
+    .retain(|tx| tx.txid() != inscribe.reveal);
+
+  core.mine_blocks(1);
+
+  assert!(fs::read_to_string(&key)
+    .unwrap()
+    .starts_with("ord-encrypted-recovery-key-v1:"));
+
+  let output = CommandBuilder::new(format!(
+    "wallet recover-commit --fee-rate 1 --recovery-key-file {} --recovery-key-passphrase bar",
+    key.display()
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<RecoverCommit>();
+
+  assert_eq!(output.commit_address, inscribe.commit_address);
+}
+
+#[test]
+fn plaintext_recovery_key_file_warns() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let key = tempdir.path().join("key.txt");
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --file foo.txt --recovery-key-file {}",
+    key.display()
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    r#"\{"code":"plaintext-recovery-key","message":"writing recovery key to `.*key.txt` in plaintext, use --recovery-key-passphrase to encrypt it"\}\n"#,
+  )
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(
+    fs::read_to_string(&key).unwrap(),
+    format!("{}\n", recovery_key(&core)),
+  );
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+#[test]
+fn recovery_keys_lists_funded_and_spent_commit_addresses() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let revealed = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let abandoned = CommandBuilder::new("wallet inscribe --fee-rate 1 --file bar.txt")
+    .write("bar.txt", "BAR")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core
+    .state()
+    .mempool
+    .retain(|tx| tx.txid() != abandoned.reveal);
+
+  core.mine_blocks(1);
+
+  let keys = CommandBuilder::new("wallet recovery-keys")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoveryKeys>();
+
+  assert_eq!(keys.len(), 2);
+
+  let revealed_key = keys
+    .iter()
+    .find(|key| key.address == revealed.commit_address)
+    .unwrap();
+
+  assert_eq!(
+    revealed_key.label,
+    format!("commit tx recovery key for {}i0", revealed.reveal)
+  );
+  assert!(revealed_key.descriptor.starts_with("rawtr("));
+  assert_eq!(revealed_key.amount, 0);
+  assert!(revealed_key.outputs.is_empty());
+
+  let abandoned_key = keys
+    .iter()
+    .find(|key| key.address == abandoned.commit_address)
+    .unwrap();
+
+  assert_eq!(
+    abandoned_key.label,
+    format!("commit tx recovery key for {}i0", abandoned.reveal)
+  );
+  assert_eq!(abandoned_key.outputs.len(), 1);
+  assert_eq!(abandoned_key.outputs[0].txid, abandoned.commit);
+  assert!(abandoned_key.amount > 0);
+
+  let funded = CommandBuilder::new("wallet recovery-keys --funded")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoveryKeys>();
+
+  assert_eq!(funded.len(), 1);
+  assert_eq!(funded[0], *abandoned_key);
+}
+
+#[test]
+fn recovery_keys_is_empty_without_inscriptions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  assert!(CommandBuilder::new("wallet recovery-keys")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<RecoveryKeys>()
+    .is_empty());
+}
@@ -0,0 +1,113 @@
+use {super::*, ord::subcommand::wallet::resume_reveal::Output};
+
+#[test]
+fn failed_reveal_can_be_resumed() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  core.state().broadcast_limit = Some(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex(
+      r#"error: Failed to send reveal transaction: .*broadcast reveal transaction with `ord wallet resume-reveal reveal-[[:xdigit:]]{64}\.json`\n"#,
+    )
+    .run_and_extract_stdout();
+
+  core.state().broadcast_limit = None;
+
+  assert_eq!(core.mempool().len(), 1);
+
+  let commit = core.mempool()[0].txid();
+
+  let recovery_file = fs::read_dir(tempdir.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+    .find(|name| name.starts_with("reveal-"))
+    .unwrap();
+
+  assert!(!fs::read_to_string(tempdir.path().join(&recovery_file))
+    .unwrap()
+    .contains("recovery_key"));
+
+  let output = CommandBuilder::new(format!("wallet resume-reveal {recovery_file}"))
+    .temp_dir(tempdir)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.commit, commit);
+  assert_eq!(recovery_file, format!("reveal-{}.json", output.reveal));
+
+  core.mine_blocks(1);
+
+  ord.assert_response(
+    format!(
+      "/content/{}",
+      InscriptionId {
+        txid: output.reveal,
+        index: 0,
+      }
+    ),
+    "FOO",
+  );
+}
+
+#[test]
+fn already_broadcast_reveal_is_treated_as_success() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  core.state().broadcast_limit = Some(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex("(?s).*")
+    .run_and_extract_stdout();
+
+  core.state().broadcast_limit = None;
+
+  let recovery_file = fs::read_dir(tempdir.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+    .find(|name| name.starts_with("reveal-"))
+    .unwrap();
+
+  let first = CommandBuilder::new(format!("wallet resume-reveal {recovery_file}"))
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  let second = CommandBuilder::new(format!("wallet resume-reveal {recovery_file}"))
+    .temp_dir(tempdir)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(first, second);
+
+  assert_eq!(core.mempool().len(), 2);
+}
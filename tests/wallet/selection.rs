@@ -16,7 +16,7 @@ fn inscribe_does_not_select_runic_utxos() {
     .write("foo.txt", "FOO")
     .core(&core)
     .ord(&ord)
-    .expected_exit_code(1)
+    .expected_exit_code(4)
     .expected_stderr("error: wallet contains no cardinal utxos\n")
     .run_and_extract_stdout();
 }
@@ -36,7 +36,7 @@ fn send_amount_does_not_select_runic_utxos() {
   CommandBuilder::new("--regtest --index-runes wallet send --fee-rate 1 bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 600sat")
     .core(&core)
     .ord(&ord)
-    .expected_exit_code(1)
+    .expected_exit_code(4)
     .expected_stderr("error: not enough cardinal utxos\n")
     .run_and_extract_stdout();
 }
@@ -101,7 +101,7 @@ fn send_inscription_does_not_select_runic_utxos() {
     .core(&core)
     .ord(&ord)
     .expected_stderr("error: wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.\n")
-    .expected_exit_code(1)
+    .expected_exit_code(4)
     .run_and_extract_stdout();
 }
 
@@ -149,7 +149,7 @@ fn mint_does_not_select_inscription() {
   ))
   .core(&core)
   .ord(&ord)
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: not enough cardinal utxos\n")
   .run_and_extract_stdout();
 }
@@ -204,7 +204,7 @@ fn sending_rune_does_not_send_inscription() {
   ))
   .core(&core)
   .ord(&ord)
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: not enough cardinal utxos\n")
   .run_and_extract_stdout();
 }
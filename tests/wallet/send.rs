@@ -246,7 +246,7 @@ fn send_does_not_use_inscribed_sats_as_cardinal_utxos() {
   ))
   .core(&core)
     .ord(&ord)
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.\n")
   .run_and_extract_stdout();
 }
@@ -315,7 +315,7 @@ fn splitting_merged_inscriptions_is_possible() {
 
   core.mine_blocks(1);
 
-  let inscribe = CommandBuilder::new("wallet batch --fee-rate 0 --batch batch.yaml")
+  let inscribe = CommandBuilder::new("wallet batch --allow-rare --fee-rate 0 --batch batch.yaml")
     .write("inscription.txt", "INSCRIPTION")
     .write(
       "batch.yaml",
@@ -639,7 +639,7 @@ fn send_btc_does_not_send_locked_utxos() {
   CommandBuilder::new("wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc")
     .core(&core)
     .ord(&ord)
-    .expected_exit_code(1)
+    .expected_exit_code(4)
     .stderr_regex("error:.*")
     .run_and_extract_stdout();
 }
@@ -739,7 +739,7 @@ fn sending_rune_with_insufficient_balance_is_an_error() {
   ))
   .core(&core)
     .ord(&ord)
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: insufficient `AAAAAAAAAAAAA` balance, only 1000\u{A0}¢ in wallet\n")
   .run_and_extract_stdout();
 }
@@ -1099,7 +1099,7 @@ fn error_messages_use_spaced_runes() {
   )
   .core(&core)
     .ord(&ord)
-  .expected_exit_code(1)
+  .expected_exit_code(4)
   .expected_stderr("error: insufficient `A•AAAAAAAAAAAA` balance, only 1000\u{A0}¢ in wallet\n")
   .run_and_extract_stdout();
 
@@ -0,0 +1,128 @@
+use super::*;
+
+#[test]
+fn trace_funding_reports_sat_ranges_of_commit_and_reveal_outputs() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  let output = CommandBuilder::new(format!("wallet trace-funding {inscription}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<TraceFunding>();
+
+  assert_eq!(output.reveal.len(), 1);
+
+  let inscribed = &output.reveal[0];
+
+  assert_eq!(inscribed.output, OutPoint::new(reveal, 0));
+  assert_eq!(inscribed.inscriptions, [inscription]);
+  assert_eq!(inscribed.unavailable, None);
+  assert_eq!(
+    inscribed
+      .sat_ranges
+      .as_ref()
+      .unwrap()
+      .iter()
+      .map(|(start, end)| end - start)
+      .sum::<u64>(),
+    inscribed.value,
+  );
+
+  let commit = output
+    .commit
+    .iter()
+    .find(|output| output.sat_ranges.is_none())
+    .unwrap();
+
+  assert_eq!(
+    commit.unavailable.as_deref(),
+    Some("output is spent and ord server index does not keep sat ranges of spent outputs, rebuild it with `--index-spent-sats`"),
+  );
+
+  let change = output
+    .commit
+    .iter()
+    .find(|output| output.sat_ranges.is_some())
+    .unwrap();
+
+  assert_eq!(change.unavailable, None);
+  assert!(change.inscriptions.is_empty());
+}
+
+#[test]
+fn trace_funding_reports_unavailable_sat_ranges() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  let output = CommandBuilder::new(format!("wallet trace-funding {inscription}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<TraceFunding>();
+
+  assert_eq!(output.reveal[0].inscriptions, [inscription]);
+
+  for output in output.commit.iter().chain(&output.reveal) {
+    assert_eq!(output.sat_ranges, None);
+    assert_eq!(
+      output.unavailable.as_deref(),
+      Some("ord server index does not have sat ranges, rebuild it with `--index-sats`"),
+    );
+  }
+}
+
+#[test]
+fn trace_funding_reports_unconfirmed_transactions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --allow-rare --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let trace = CommandBuilder::new(format!(
+    "wallet trace-funding {}",
+    output.inscriptions[0].id
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<TraceFunding>();
+
+  for traced in &trace.commit {
+    assert_eq!(traced.sat_ranges, None);
+    assert_eq!(
+      traced.unavailable,
+      Some(format!(
+        "transaction {} is not yet confirmed",
+        output.commit
+      )),
+    );
+  }
+
+  for traced in &trace.reveal {
+    assert_eq!(traced.sat_ranges, None);
+    assert_eq!(
+      traced.unavailable,
+      Some(format!(
+        "transaction {} is not yet confirmed",
+        output.reveal
+      )),
+    );
+  }
+}